@@ -0,0 +1,351 @@
+//! Parsing of the `#[nbt(...)]` and `#[list(...)]` attributes
+use proc_macro2::Span;
+use syn::spanned::Spanned;
+use syn::{
+    Attribute, Error, ExprPath, Lit, LitInt, LitStr, Meta, MetaNameValue, NestedMeta, Path, Result,
+};
+
+/// How field names are turned into keys. Field names are expected to be snake_case
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum RenameRule {
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    Lower,
+    Upper,
+}
+
+impl RenameRule {
+    const RULES: [(&'static str, RenameRule); 7] = [
+        ("PascalCase", RenameRule::Pascal),
+        ("camelCase", RenameRule::Camel),
+        ("snake_case", RenameRule::Snake),
+        ("SCREAMING_SNAKE_CASE", RenameRule::ScreamingSnake),
+        ("kebab-case", RenameRule::Kebab),
+        ("lowercase", RenameRule::Lower),
+        ("UPPERCASE", RenameRule::Upper),
+    ];
+
+    fn parse(lit: &LitStr) -> Result<Self> {
+        let value = lit.value();
+        Self::RULES
+            .iter()
+            .find(|(name, _)| *name == value)
+            .map(|(_, rule)| *rule)
+            .ok_or_else(|| {
+                let names: Vec<&str> = Self::RULES.iter().map(|(name, _)| *name).collect();
+                Error::new(
+                    lit.span(),
+                    format!(
+                        "unknown rename rule `{}`, expected one of {}",
+                        value,
+                        names.join(", ")
+                    ),
+                )
+            })
+    }
+
+    pub(crate) fn apply(self, field: &str) -> String {
+        let words = field.split('_').filter(|word| !word.is_empty());
+        match self {
+            RenameRule::Pascal => words.map(capitalize).collect(),
+            RenameRule::Camel => {
+                let pascal: String = words.map(capitalize).collect();
+                let mut chars = pascal.chars();
+                match chars.next() {
+                    Some(first) => first.to_lowercase().chain(chars).collect(),
+                    None => pascal,
+                }
+            }
+            RenameRule::Snake => field.to_string(),
+            RenameRule::ScreamingSnake => field.to_uppercase(),
+            RenameRule::Kebab => field.replace('_', "-"),
+            RenameRule::Lower => field.to_lowercase(),
+            RenameRule::Upper => field.to_uppercase(),
+        }
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// The tag a sequence field is written with
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum TagOverride {
+    List,
+    ByteArray,
+    IntArray,
+    LongArray,
+}
+
+impl TagOverride {
+    const TAGS: [(&'static str, TagOverride); 4] = [
+        ("list", TagOverride::List),
+        ("byte_array", TagOverride::ByteArray),
+        ("int_array", TagOverride::IntArray),
+        ("long_array", TagOverride::LongArray),
+    ];
+
+    pub(crate) fn parse(lit: &LitStr) -> Result<Self> {
+        let value = lit.value();
+        Self::TAGS
+            .iter()
+            .find(|(name, _)| *name == value)
+            .map(|(_, tag)| *tag)
+            .ok_or_else(|| {
+                let names: Vec<&str> = Self::TAGS.iter().map(|(name, _)| *name).collect();
+                Error::new(
+                    lit.span(),
+                    format!(
+                        "unknown tag `{}`, expected one of {}",
+                        value,
+                        names.join(", ")
+                    ),
+                )
+            })
+    }
+
+    /// The variant of `axolotl_nbt::Tag`
+    pub(crate) fn tag_name(self) -> &'static str {
+        match self {
+            TagOverride::List => "List",
+            TagOverride::ByteArray => "ByteArray",
+            TagOverride::IntArray => "IntArray",
+            TagOverride::LongArray => "LongArray",
+        }
+    }
+
+    /// The element types of an array. `None` for lists, which take any element
+    pub(crate) fn elements(self) -> Option<&'static [&'static str]> {
+        match self {
+            TagOverride::List => None,
+            TagOverride::ByteArray => Some(&["i8", "bool"]),
+            TagOverride::IntArray => Some(&["i32", "usize"]),
+            TagOverride::LongArray => Some(&["i64"]),
+        }
+    }
+
+    /// The name used in the attribute
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            TagOverride::List => "list",
+            TagOverride::ByteArray => "byte_array",
+            TagOverride::IntArray => "int_array",
+            TagOverride::LongArray => "long_array",
+        }
+    }
+
+    /// The module in `axolotl_nbt::serde_impl` that writes a sequence with this tag
+    pub(crate) fn adapter(self) -> &'static str {
+        match self {
+            TagOverride::List => "as_list",
+            TagOverride::ByteArray => "as_byte_array",
+            TagOverride::IntArray => "as_int_array",
+            TagOverride::LongArray => "as_long_array",
+        }
+    }
+}
+
+/// `default` or `default = "path"`
+#[derive(Debug, Clone)]
+pub(crate) enum DefaultValue {
+    Default,
+    Path(ExprPath),
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct ContainerAttributes {
+    pub rename_all: Option<RenameRule>,
+    /// The span of `deny_unknown` when set
+    pub deny_unknown: Option<Span>,
+    pub data_version: Option<LitInt>,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct FieldAttributes {
+    pub rename: Option<LitStr>,
+    pub default: Option<DefaultValue>,
+    pub skip: Option<Span>,
+    pub skip_if: Option<ExprPath>,
+    pub flatten: Option<Span>,
+    /// The tag and the span of its string
+    pub tag: Option<(TagOverride, Span)>,
+}
+
+/// Every `name` or `name = value` inside the `#[attribute(...)]` attributes
+pub(crate) fn metas(attributes: &[Attribute], attribute: &str) -> Result<Vec<Meta>> {
+    let mut metas = Vec::new();
+    for found in attributes.iter().filter(|a| a.path.is_ident(attribute)) {
+        match found.parse_meta()? {
+            Meta::List(list) => {
+                for nested in list.nested {
+                    match nested {
+                        NestedMeta::Meta(meta) => metas.push(meta),
+                        NestedMeta::Lit(lit) => {
+                            return Err(Error::new(lit.span(), "expected an attribute name"))
+                        }
+                    }
+                }
+            }
+            other => {
+                return Err(Error::new(
+                    other.span(),
+                    format!("expected #[{}(...)] with a list of attributes", attribute),
+                ))
+            }
+        }
+    }
+    Ok(metas)
+}
+
+pub(crate) fn name_of(path: &Path) -> String {
+    path.get_ident()
+        .map(|ident| ident.to_string())
+        .unwrap_or_default()
+}
+
+pub(crate) fn set<T>(slot: &mut Option<T>, value: T, path: &Path) -> Result<()> {
+    if slot.is_some() {
+        return Err(Error::new_spanned(
+            path,
+            format!("duplicate attribute `{}`", name_of(path)),
+        ));
+    }
+    *slot = Some(value);
+    Ok(())
+}
+
+pub(crate) fn string(meta: &Meta) -> Result<LitStr> {
+    match meta {
+        Meta::NameValue(name_value) => match &name_value.lit {
+            Lit::Str(lit) => Ok(lit.clone()),
+            lit => Err(Error::new(
+                lit.span(),
+                format!("expected `{} = \"...\"`", name_of(&name_value.path)),
+            )),
+        },
+        _ => Err(Error::new_spanned(
+            meta,
+            format!("expected `{} = \"...\"`", name_of(meta.path())),
+        )),
+    }
+}
+
+pub(crate) fn flag(meta: &Meta) -> Result<Span> {
+    match meta {
+        Meta::Path(path) => Ok(path.span()),
+        _ => Err(Error::new_spanned(
+            meta,
+            format!("`{}` does not take a value", name_of(meta.path())),
+        )),
+    }
+}
+
+pub(crate) fn unknown(meta: &Meta, expected: &str) -> Error {
+    Error::new_spanned(
+        meta.path(),
+        format!(
+            "unknown attribute `{}`, expected one of {}",
+            name_of(meta.path()),
+            expected
+        ),
+    )
+}
+
+impl ContainerAttributes {
+    pub(crate) fn parse(attributes: &[Attribute]) -> Result<Self> {
+        let mut result = Self::default();
+        for meta in metas(attributes, "nbt")? {
+            let path = meta.path();
+            if path.is_ident("rename_all") {
+                let rule = RenameRule::parse(&string(&meta)?)?;
+                set(&mut result.rename_all, rule, path)?;
+            } else if path.is_ident("deny_unknown") {
+                set(&mut result.deny_unknown, flag(&meta)?, path)?;
+            } else if path.is_ident("data_version") {
+                let version = match &meta {
+                    Meta::NameValue(MetaNameValue {
+                        lit: Lit::Int(version),
+                        ..
+                    }) => version.clone(),
+                    _ => {
+                        return Err(Error::new_spanned(
+                            &meta,
+                            "expected `data_version = <integer>`",
+                        ))
+                    }
+                };
+                version.base10_parse::<i32>()?;
+                set(&mut result.data_version, version, path)?;
+            } else {
+                return Err(unknown(&meta, "rename_all, deny_unknown, data_version"));
+            }
+        }
+        Ok(result)
+    }
+}
+
+impl FieldAttributes {
+    pub(crate) fn parse(attributes: &[Attribute]) -> Result<Self> {
+        let mut result = Self::default();
+        let mut conflicts = Vec::new();
+        for meta in metas(attributes, "nbt")? {
+            let path = meta.path();
+            if path.is_ident("rename") {
+                set(&mut result.rename, string(&meta)?, path)?;
+            } else if path.is_ident("default") {
+                let default = match &meta {
+                    Meta::Path(_) => DefaultValue::Default,
+                    _ => DefaultValue::Path(string(&meta)?.parse()?),
+                };
+                set(&mut result.default, default, path)?;
+            } else if path.is_ident("skip") {
+                set(&mut result.skip, flag(&meta)?, path)?;
+            } else if path.is_ident("skip_if") {
+                set(&mut result.skip_if, string(&meta)?.parse()?, path)?;
+            } else if path.is_ident("flatten") {
+                set(&mut result.flatten, flag(&meta)?, path)?;
+            } else if path.is_ident("tag") {
+                let lit = string(&meta)?;
+                let tag = TagOverride::parse(&lit)?;
+                set(&mut result.tag, (tag, lit.span()), path)?;
+            } else {
+                return Err(unknown(
+                    &meta,
+                    "rename, default, skip, skip_if, flatten, tag",
+                ));
+            }
+            conflicts.push(meta);
+        }
+        // flatten and skip leave no key of their own, so the key attributes make no sense with them
+        for (attribute, allowed) in [
+            (result.flatten.is_some(), &["flatten"][..]),
+            (result.skip.is_some(), &["skip", "default"][..]),
+        ] {
+            if !attribute {
+                continue;
+            }
+            if let Some(meta) = conflicts
+                .iter()
+                .find(|meta| !allowed.iter().any(|name| meta.path().is_ident(name)))
+            {
+                return Err(Error::new_spanned(
+                    meta.path(),
+                    format!(
+                        "`{}` can not be used together with `{}`",
+                        name_of(meta.path()),
+                        allowed[0]
+                    ),
+                ));
+            }
+        }
+        Ok(result)
+    }
+}
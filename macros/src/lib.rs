@@ -1,7 +1,10 @@
+mod attributes;
 mod list_serialize;
+mod nbt_compound;
+mod nbt_enum;
 
 use proc_macro::TokenStream;
-use syn::{parse_macro_input, Data, DeriveInput};
+use syn::{parse_macro_input, DeriveInput};
 
 /// Minecraft uses lists to represent a lot of things that I want to represent in memory as a Structure.
 /// For example Armor within a Mob is an Array of 4 Items. I want it represented as a Structure
@@ -19,14 +22,89 @@ use syn::{parse_macro_input, Data, DeriveInput};
 /// When using this macro please make sure you order the fields in the same order as the list.
 ///
 /// This macro will generate a `Serialize` and `Deserialize` implementation for a structure that in NBT will be represented as a list.
-#[proc_macro_derive(ListSerialize)]
+///
+/// Tuple structs and generic structs are supported. `struct Pos(f64, f64, f64)` is a list of 3 doubles.
+/// - `#[list(rest)]` on the last field, a `Vec`, collects every element after the other fields
+/// - `#[list(default)]` on the trailing fields, they are set with `Default` when the list is shorter
+/// - `#[list(encoding = "int_array")]` on the structure picks the tag of the sequence.
+///   Also `list`, `byte_array` and `long_array`. By default sequences of bytes, ints and longs are arrays
+#[proc_macro_derive(ListSerialize, attributes(list))]
 pub fn list_serialize(stream: TokenStream) -> TokenStream {
     let input: DeriveInput = parse_macro_input!(stream as DeriveInput);
-    if let Data::Struct(v) = input.data {
-        list_serialize::parse_struct(input.ident, v)
-            .unwrap_or_else(|e| e.to_compile_error())
-            .into()
-    } else {
-        panic!("ListSerialize can only be used on structs");
-    }
+    list_serialize::parse_struct(input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+/// Generates a `NBTDataType<Binary>` implementation that reads and writes the structure as a compound
+/// without going through serde.
+/// ```rust ignore
+///#[derive(Debug, NbtCompound)]
+///pub struct Item {
+///    pub id: String,
+///    #[allow(non_snake_case)]
+///    pub Count: i8,
+///    pub tag: Option<Value>,
+///}
+///```
+/// Fields are matched by name. Keys that are not a field are skipped.
+/// `Option` fields may be missing and are not written when `None`.
+/// Every other field must be present.
+/// Fields whose type sets `NBTDataType::ANY_TAG`, like `Value` and `NameLessValue`, take a key of
+/// any tag. A `Value` is named after its key.
+///
+/// On the structure:
+/// - `#[nbt(rename_all = "PascalCase")]` renames every field. Also `camelCase`, `snake_case`,
+///   `SCREAMING_SNAKE_CASE`, `kebab-case`, `lowercase` and `UPPERCASE`
+/// - `#[nbt(deny_unknown)]` fails with `NBTError::UnknownField` instead of skipping unknown keys
+/// - `#[nbt(data_version = 3465)]` implements `migrate::Versioned`. `DataVersion` is written and skipped when read
+///   unless a field holds it
+///
+/// On a field:
+/// - `#[nbt(rename = "HurtTime")]` the key of the field
+/// - `#[nbt(default)]` or `#[nbt(default = "path")]` the value when the key is missing
+/// - `#[nbt(skip)]` never read or written. Set with `Default` or the `default` path
+/// - `#[nbt(skip_if = "path")]` not written when `path(&field)` returns true
+/// - `#[nbt(flatten)]` the keys of the field are part of this compound. A `Vec<Value>` collects every unknown key
+/// - `#[nbt(tag = "int_array")]` the tag of a `Vec` field. Also `list`, `byte_array` and `long_array`.
+///   The elements of an array must fit its tag, `Vec<i32>` for an `int_array`
+#[proc_macro_derive(NbtCompound, attributes(nbt))]
+pub fn nbt_compound(stream: TokenStream) -> TokenStream {
+    let input: DeriveInput = parse_macro_input!(stream as DeriveInput);
+    nbt_compound::parse_struct(input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+/// Generates `Serialize`, `Deserialize` and `NBTDataType<Binary>` for an enum stored as a string or an id.
+/// ```rust ignore
+///#[derive(Debug, NbtEnum)]
+///pub enum Dimension {
+///    #[nbt(string = "minecraft:overworld")]
+///    Overworld,
+///    #[nbt(string = "minecraft:the_nether")]
+///    Nether,
+///    Unknown(String),
+///}
+///
+///#[derive(Debug, NbtEnum)]
+///#[nbt(tag = "byte")]
+///pub enum Facing {
+///    #[nbt(id = 2)]
+///    North,
+///    #[nbt(id = 3)]
+///    South,
+///}
+///```
+/// Every unit variant has a `string` or every unit variant has an `id`.
+/// Ids are a TAG_Int unless `#[nbt(tag = "byte")]` or `#[nbt(tag = "short")]` is set on the enum.
+///
+/// A single variant with one field, like `Unknown(String)` or `Unknown(i8)`, keeps the values no variant matches.
+/// The field is a `String` or the type of the ids. Without it an unknown value is an error.
+#[proc_macro_derive(NbtEnum, attributes(nbt))]
+pub fn nbt_enum(stream: TokenStream) -> TokenStream {
+    let input: DeriveInput = parse_macro_input!(stream as DeriveInput);
+    nbt_enum::parse_enum(input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
 }
@@ -1,88 +1,331 @@
-use proc_macro2::TokenStream;
+use proc_macro2::{Span, TokenStream};
 
 use quote::{format_ident, quote};
 
-use syn::{DataStruct, Result};
-use syn::{Fields, Ident};
+use syn::{
+    parse_quote, Data, DeriveInput, Error, Fields, GenericParam, Generics, Ident, Member, Result,
+    Type,
+};
 
-pub(crate) fn parse_struct(type_ident: Ident, data: DataStruct) -> Result<TokenStream> {
-    let fields_size = data.fields.len();
+use crate::attributes::{flag, metas, set, string, unknown, TagOverride};
 
-    let fields = if let Fields::Named(fields) = data.fields {
-        fields
+struct ListField {
+    member: Member,
+    ty: Type,
+    /// `#[list(default)]`
+    default: Option<Span>,
+    /// `#[list(rest)]`
+    rest: Option<Span>,
+}
+
+impl ListField {
+    fn parse(member: Member, field: syn::Field) -> Result<Self> {
+        let mut default = None;
+        let mut rest = None;
+        for meta in metas(&field.attrs, "list")? {
+            let path = meta.path();
+            if path.is_ident("default") {
+                set(&mut default, flag(&meta)?, path)?;
+            } else if path.is_ident("rest") {
+                set(&mut rest, flag(&meta)?, path)?;
+            } else {
+                return Err(unknown(&meta, "default, rest"));
+            }
+        }
+        if let (Some(_), Some(rest)) = (default, rest) {
+            return Err(Error::new(
+                rest,
+                "`rest` can not be used together with `default`",
+            ));
+        }
+        Ok(Self {
+            member,
+            ty: field.ty,
+            default,
+            rest,
+        })
+    }
+}
+
+fn parse_encoding(input: &DeriveInput) -> Result<Option<TagOverride>> {
+    let mut encoding = None;
+    for meta in metas(&input.attrs, "list")? {
+        let path = meta.path();
+        if path.is_ident("encoding") {
+            set(&mut encoding, TagOverride::parse(&string(&meta)?)?, path)?;
+        } else {
+            return Err(unknown(&meta, "encoding"));
+        }
+    }
+    Ok(encoding)
+}
+
+/// Adds `bound` to every type parameter
+fn with_bound(generics: &Generics, bound: syn::TypeParamBound) -> Generics {
+    let mut generics = generics.clone();
+    for param in generics.type_params_mut() {
+        param.bounds.push(bound.clone());
+    }
+    generics
+}
+
+/// Adds a lifetime in front of the parameters
+fn with_lifetime(generics: &Generics, lifetime: GenericParam) -> Generics {
+    let mut generics = generics.clone();
+    generics.params.insert(0, lifetime);
+    generics
+}
+
+pub(crate) fn parse_struct(input: DeriveInput) -> Result<TokenStream> {
+    let type_ident = input.ident.clone();
+    let data = match &input.data {
+        Data::Struct(data) => data,
+        _ => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "ListSerialize can only be used on structs",
+            ))
+        }
+    };
+    let members: Vec<(Member, syn::Field)> = match &data.fields {
+        Fields::Named(fields) => fields
             .named
-            .into_iter()
-            .map(|f| f.ident.unwrap())
-            .collect::<Vec<Ident>>()
-    } else {
-        return Err(syn::Error::new_spanned(
-            type_ident,
-            "ListSerialize can only be used on structs with named fields",
-        ))?;
+            .iter()
+            .map(|f| (Member::Named(f.ident.clone().unwrap()), f.clone()))
+            .collect(),
+        Fields::Unnamed(fields) => fields
+            .unnamed
+            .iter()
+            .enumerate()
+            .map(|(index, f)| (Member::Unnamed(index.into()), f.clone()))
+            .collect(),
+        Fields::Unit => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "ListSerialize can not be used on unit structs",
+            ))
+        }
     };
-    let visitor_name = format_ident!("{}Visitor", type_ident);
-    let visitor_in_place_name = format_ident!("{}InPlaceVisitor", type_ident);
-    Ok(quote! {
-        impl serde::Serialize for #type_ident {
-            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: serde::Serializer{
-                use serde::ser::SerializeSeq;
-                let mut seq = serializer.serialize_seq(Some(#fields_size))?;
-                #(
-                    seq.serialize_element(&self.#fields)?;
-                )*
-                seq.end()
+    let encoding = parse_encoding(&input)?;
+    let mut fields = members
+        .into_iter()
+        .map(|(member, field)| ListField::parse(member, field))
+        .collect::<Result<Vec<ListField>>>()?;
+
+    let rest = match fields.last() {
+        Some(last) if last.rest.is_some() => fields.pop(),
+        _ => None,
+    };
+    if let Some(field) = fields.iter().find(|f| f.rest.is_some()) {
+        return Err(Error::new(
+            field.rest.unwrap(),
+            "only the last field can be `rest`",
+        ));
+    }
+    if let Some(first_default) = fields.iter().position(|f| f.default.is_some()) {
+        if let Some(field) = fields[first_default..].iter().find(|f| f.default.is_none()) {
+            return Err(Error::new_spanned(
+                &field.ty,
+                "every field after a `default` field must be `default` as well",
+            ));
+        }
+    }
+
+    let fixed = fields.len();
+    let required = fields.iter().filter(|f| f.default.is_none()).count();
+    let expecting = match (&rest, required == fixed) {
+        (Some(_), _) => format!("a sequence of at least {} elements", required),
+        (None, true) => format!("a sequence of {} elements", fixed),
+        (None, false) => format!("a sequence of {} to {} elements", required, fixed),
+    };
+
+    let fixed_members: Vec<&Member> = fields.iter().map(|f| &f.member).collect();
+    let variables: Vec<Ident> = (0..fixed).map(|i| format_ident!("__field{}", i)).collect();
+    let mut all_members = fixed_members.clone();
+    let mut all_variables = variables.clone();
+    let read_fields: Vec<TokenStream> = fields
+        .iter()
+        .zip(&variables)
+        .enumerate()
+        .map(|(index, (field, variable))| {
+            let ty = &field.ty;
+            if field.default.is_some() {
+                quote! {
+                    let #variable: #ty = seq.next_element()?.unwrap_or_default();
+                }
+            } else {
+                quote! {
+                    let #variable: #ty = seq.next_element()?.ok_or_else(|| A::Error::invalid_length(#index, &self))?;
+                }
             }
+        })
+        .collect();
+
+    let (serialize_rest, read_rest, length, bounded) = match &rest {
+        Some(rest) => {
+            let member = &rest.member;
+            let ty = &rest.ty;
+            let variable = format_ident!("__rest");
+            all_members.push(member);
+            all_variables.push(variable.clone());
+            (
+                quote! {
+                    for element in this.#member.iter() {
+                        seq.serialize_element(element)?;
+                    }
+                },
+                quote! {
+                    let mut #variable: #ty = ::std::default::Default::default();
+                    while let ::std::option::Option::Some(element) = seq.next_element()? {
+                        #variable.push(element);
+                    }
+                },
+                quote! { #fixed + this.#member.len() },
+                false,
+            )
         }
-        pub struct #visitor_name;
-        impl<'de> serde::de::Visitor<'de> for #visitor_name {
-            type Value = #type_ident;
-            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-                formatter.write_str(concat!("a sequence of ", #fields_size, " elements"))
+        None => (
+            quote! {},
+            quote! {
+                if seq.next_element::<serde::de::IgnoredAny>()?.is_some() {
+                    return ::std::result::Result::Err(A::Error::invalid_length(#fixed + 1, &self));
+                }
+            },
+            quote! { #fixed },
+            true,
+        ),
+    };
+    // Written the way clippy wants it in the generated code
+    let invalid_size = match (required, bounded) {
+        (0, false) => None,
+        (0, true) => Some(quote! { size > #fixed }),
+        (_, false) => Some(quote! { size < #required }),
+        (_, true) if required == fixed => Some(quote! { size != #fixed }),
+        (_, true) => Some(quote! { !(#required..=#fixed).contains(&size) }),
+    };
+    let size_check = invalid_size.map(|invalid_size| {
+        quote! {
+            if let ::std::option::Option::Some(size) = seq.size_hint() {
+                if #invalid_size {
+                    return ::std::result::Result::Err(serde::de::Error::invalid_length(size, &self));
+                }
             }
-            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error> where A: serde::de::SeqAccess<'de> {
-                use serde::de::Error;
-                if let Some(size) = seq.size_hint() {
-                    if size != #fields_size {
-                        return Err(serde::de::Error::invalid_length(size, &self));
+        }
+    });
+
+    let serialize_elements = quote! {
+        use serde::ser::SerializeSeq;
+        let mut seq = serializer.serialize_seq(::std::option::Option::Some(#length))?;
+        #(
+            seq.serialize_element(&this.#fixed_members)?;
+        )*
+        #serialize_rest
+        seq.end()
+    };
+
+    let generics = &input.generics;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let ser_generics = with_bound(generics, parse_quote!(serde::Serialize));
+    let (ser_impl_generics, _, _) = ser_generics.split_for_impl();
+    let serialize = match encoding {
+        Some(encoding) => {
+            let adapter = format_ident!("{}", encoding.adapter());
+            let elements_generics = with_lifetime(&ser_generics, parse_quote!('a));
+            let (elements_impl_generics, _, _) = elements_generics.split_for_impl();
+            let elements_ty_generics = with_lifetime(generics, parse_quote!('a));
+            let (_, elements_ty_generics, _) = elements_ty_generics.split_for_impl();
+            quote! {
+                struct Elements #elements_impl_generics(&'a #type_ident #ty_generics) #where_clause;
+                impl #elements_impl_generics serde::Serialize for Elements #elements_ty_generics #where_clause {
+                    fn serialize<S>(&self, serializer: S) -> ::std::result::Result<S::Ok, S::Error> where S: serde::Serializer {
+                        let this = self.0;
+                        #serialize_elements
                     }
                 }
-                Ok(#type_ident {
+                ::axolotl_nbt::serde_impl::#adapter::serialize(&Elements(self), serializer)
+            }
+        }
+        None => quote! {
+            let this = self;
+            #serialize_elements
+        },
+    };
+
+    let de_generics = with_lifetime(
+        &with_bound(generics, parse_quote!(serde::Deserialize<'de>)),
+        parse_quote!('de),
+    );
+    let (de_impl_generics, _, _) = de_generics.split_for_impl();
+    let in_place_de_generics = with_lifetime(
+        &with_lifetime(
+            &with_bound(generics, parse_quote!(serde::Deserialize<'de>)),
+            parse_quote!('data),
+        ),
+        parse_quote!('de),
+    );
+    let (in_place_impl_generics, _, _) = in_place_de_generics.split_for_impl();
+    let in_place_generics = with_lifetime(generics, parse_quote!('data));
+    let (in_place_struct_generics, in_place_ty_generics, _) = in_place_generics.split_for_impl();
+
+    let visitor_name = format_ident!("__Visitor");
+    let visitor_in_place_name = format_ident!("__InPlaceVisitor");
+    // The scope keeps the visitors out of the user's module. serde is reached through axolotl_nbt
+    // so the user does not need it as a dependency
+    Ok(quote! {
+        const _: () = {
+        use ::axolotl_nbt::__private::serde;
+
+        impl #ser_impl_generics serde::Serialize for #type_ident #ty_generics #where_clause {
+            fn serialize<S>(&self, serializer: S) -> ::std::result::Result<S::Ok, S::Error> where S: serde::Serializer{
+                #serialize
+            }
+        }
+        struct #visitor_name #impl_generics (::std::marker::PhantomData<fn() -> #type_ident #ty_generics>) #where_clause;
+        impl #de_impl_generics serde::de::Visitor<'de> for #visitor_name #ty_generics #where_clause {
+            type Value = #type_ident #ty_generics;
+            fn expecting(&self, formatter: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                formatter.write_str(#expecting)
+            }
+            fn visit_seq<A>(self, mut seq: A) -> ::std::result::Result<Self::Value, A::Error> where A: serde::de::SeqAccess<'de> {
+                use serde::de::Error;
+                #size_check
+                #(#read_fields)*
+                #read_rest
+                ::std::result::Result::Ok(#type_ident {
                     #(
-                        #fields: seq.next_element()?.ok_or_else(|| A::Error::invalid_length(#fields_size, &self))?,
+                        #all_members: #all_variables,
                     )*
                 })
             }
         }
-        pub struct #visitor_in_place_name<'data>(&'data mut #type_ident);
+        struct #visitor_in_place_name #in_place_struct_generics(&'data mut #type_ident #ty_generics) #where_clause;
 
-        impl<'de,'data> serde::de::Visitor<'de> for #visitor_in_place_name<'data> {
+        impl #in_place_impl_generics serde::de::Visitor<'de> for #visitor_in_place_name #in_place_ty_generics #where_clause {
             type Value = ();
-            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-                formatter.write_str(concat!("a sequence of ", #fields_size, " elements"))
+            fn expecting(&self, formatter: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                formatter.write_str(#expecting)
             }
-            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error> where A: serde::de::SeqAccess<'de> {
+            fn visit_seq<A>(self, mut seq: A) -> ::std::result::Result<Self::Value, A::Error> where A: serde::de::SeqAccess<'de> {
                 use serde::de::Error;
-                if let Some(size) = seq.size_hint() {
-                    if size != #fields_size {
-                        return Err(serde::de::Error::invalid_length(size, &self));
-                    }
-                }
+                #size_check
+                #(#read_fields)*
+                #read_rest
                 #(
-                    self.0.#fields = seq.next_element()?.ok_or_else(|| A::Error::invalid_length(#fields_size, &self))?;
+                    self.0.#all_members = #all_variables;
                 )*
-                Ok(())
+                ::std::result::Result::Ok(())
             }
         }
 
 
-        impl<'de> serde::Deserialize<'de> for #type_ident {
-            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: serde::Deserializer<'de> {
-                deserializer.deserialize_seq(#visitor_name)
+        impl #de_impl_generics serde::Deserialize<'de> for #type_ident #ty_generics #where_clause {
+            fn deserialize<D>(deserializer: D) -> ::std::result::Result<Self, D::Error> where D: serde::Deserializer<'de> {
+                deserializer.deserialize_seq(#visitor_name(::std::marker::PhantomData))
             }
-            fn deserialize_in_place<D>(deserializer: D, place: &mut Self) -> Result<(), D::Error> where D: serde::Deserializer<'de> {
+            fn deserialize_in_place<D>(deserializer: D, place: &mut Self) -> ::std::result::Result<(), D::Error> where D: serde::Deserializer<'de> {
                 deserializer.deserialize_seq(#visitor_in_place_name(place))
             }
         }
-
+        };
     })
 }
@@ -0,0 +1,362 @@
+use std::collections::HashMap;
+
+use proc_macro2::{Span, TokenStream};
+
+use quote::{format_ident, quote, quote_spanned};
+
+use syn::spanned::Spanned;
+use syn::{Data, DeriveInput, Fields, GenericArgument, LitInt, PathArguments, Result, Type};
+
+use crate::attributes::{ContainerAttributes, DefaultValue, FieldAttributes, TagOverride};
+
+/// The inner type if the type is an `Option`
+fn option_type(ty: &Type) -> Option<&Type> {
+    wrapped_type(ty, "Option")
+}
+
+/// The inner type of `Wrapper<T>`
+fn wrapped_type<'a>(ty: &'a Type, wrapper: &str) -> Option<&'a Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != wrapper {
+        return None;
+    }
+    let PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+        return None;
+    };
+    match arguments.args.first() {
+        Some(GenericArgument::Type(inner)) if arguments.args.len() == 1 => Some(inner),
+        _ => None,
+    }
+}
+
+/// Sequences can only have a tag that their elements fit in
+fn check_tag(ty: &Type, tag: TagOverride, tag_span: Span) -> Result<()> {
+    let Some(element) = wrapped_type(ty, "Vec") else {
+        let mut error = syn::Error::new_spanned(ty, "`tag` can only be used on a `Vec` field");
+        error.combine(syn::Error::new(tag_span, "the tag is set here"));
+        return Err(error);
+    };
+    let Some(elements) = tag.elements() else {
+        return Ok(());
+    };
+    let fits = match element {
+        Type::Path(path) => elements.iter().any(|name| path.path.is_ident(name)),
+        _ => false,
+    };
+    if fits {
+        return Ok(());
+    }
+    let mut error = syn::Error::new_spanned(
+        element,
+        format!(
+            "`{}` can only hold {}",
+            tag.name(),
+            elements
+                .iter()
+                .map(|name| format!("`{}`", name))
+                .collect::<Vec<_>>()
+                .join(" or ")
+        ),
+    );
+    error.combine(syn::Error::new(tag_span, "the tag is set here"));
+    Err(error)
+}
+
+pub(crate) fn parse_struct(input: DeriveInput) -> Result<TokenStream> {
+    let type_ident = input.ident;
+    let fields = match input.data {
+        Data::Struct(data) => match data.fields {
+            Fields::Named(fields) => fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    type_ident,
+                    "NbtCompound can only be used on structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                type_ident,
+                "NbtCompound can only be used on structs",
+            ))
+        }
+    };
+    // Every invalid attribute is reported, not only the first one
+    let mut errors: Option<syn::Error> = None;
+    let mut report = |error: syn::Error| match &mut errors {
+        Some(errors) => errors.combine(error),
+        None => errors = Some(error),
+    };
+    let container = ContainerAttributes::parse(&input.attrs).unwrap_or_else(|error| {
+        report(error);
+        ContainerAttributes::default()
+    });
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let mut builder_fields = Vec::new();
+    let mut builder_defaults = Vec::new();
+    let mut read_keys = Vec::new();
+    let mut read_flattened = Vec::new();
+    let mut build = Vec::new();
+    let mut write = Vec::new();
+    let mut keys: HashMap<String, Span> = HashMap::new();
+    for (index, field) in fields.into_iter().enumerate() {
+        let attributes = match FieldAttributes::parse(&field.attrs) {
+            Ok(attributes) => attributes,
+            Err(error) => {
+                report(error);
+                continue;
+            }
+        };
+        let ident = field.ident.unwrap();
+        let ty = field.ty;
+        let variable = format_ident!("__field{}", index);
+
+        if attributes.skip.is_some() {
+            let value = match &attributes.default {
+                Some(DefaultValue::Path(path)) => quote! { #path() },
+                _ => quote_spanned! {ty.span()=> ::std::default::Default::default() },
+            };
+            build.push(quote! { #ident: #value });
+            continue;
+        }
+
+        if let Some(flatten) = attributes.flatten {
+            if let Some(deny_unknown) = container.deny_unknown {
+                let mut error = syn::Error::new(
+                    deny_unknown,
+                    "`deny_unknown` can not be used together with a flattened field",
+                );
+                error.combine(syn::Error::new(flatten, "the flattened field"));
+                report(error);
+            }
+            let compound = quote_spanned! {ty.span()=>
+                <#ty as ::axolotl_nbt::binary::compound::NBTCompound>
+            };
+            builder_fields.push(quote! { #variable: #compound::Builder });
+            builder_defaults.push(quote! { #variable: ::std::default::Default::default() });
+            read_flattened.push(quote! {
+                if #compound::read_field(&mut builder.#variable, name, compound)? {
+                    return ::std::result::Result::Ok(true);
+                }
+            });
+            build.push(quote! { #ident: #compound::build(builder.#variable)? });
+            write.push(quote! { #compound::write_fields(self.#ident, compound)?; });
+            continue;
+        }
+
+        let key = match (&attributes.rename, container.rename_all) {
+            (Some(rename), _) => rename.value(),
+            (None, Some(rule)) => rule.apply(&ident.to_string()),
+            (None, None) => ident.to_string(),
+        };
+        let key_span = attributes
+            .rename
+            .as_ref()
+            .map(|rename| rename.span())
+            .unwrap_or_else(|| ident.span());
+        if let Some(first) = keys.insert(key.clone(), key_span) {
+            let mut error = syn::Error::new(key_span, format!("duplicate key `{}`", key));
+            error.combine(syn::Error::new(first, "first used here"));
+            report(error);
+        }
+
+        let option = option_type(&ty);
+        let read_type = option.unwrap_or(&ty);
+        builder_fields.push(quote! { #variable: ::std::option::Option<#read_type> });
+        builder_defaults.push(quote! { #variable: ::std::option::Option::None });
+
+        if let Some((tag, span)) = attributes.tag {
+            if let Err(error) = check_tag(read_type, tag, span) {
+                report(error);
+            }
+        }
+        let (read, written) = match attributes.tag {
+            Some((tag, _)) => {
+                let tag = format_ident!("{}", tag.tag_name());
+                let sequence = quote_spanned! {ty.span()=>
+                    ::axolotl_nbt::binary::sync::Sequence::<_, { ::axolotl_nbt::Tag::#tag as i8 }>
+                };
+                (
+                    quote_spanned! {ty.span()=> compound.read_next_tag_value::<#sequence>()?.0 },
+                    quote_spanned! {ty.span()=> #sequence(value) },
+                )
+            }
+            None => (
+                quote_spanned! {ty.span()=> compound.read_next_entry::<#read_type>(name)? },
+                quote_spanned! {ty.span()=> value },
+            ),
+        };
+        read_keys.push(quote! {
+            #key => {
+                builder.#variable = ::std::option::Option::Some(#read);
+                ::std::result::Result::Ok(true)
+            }
+        });
+
+        let value = match (option, &attributes.default) {
+            (Some(_), Some(DefaultValue::Path(path))) => {
+                quote! { builder.#variable.or_else(#path) }
+            }
+            (Some(_), _) => quote! { builder.#variable },
+            (None, Some(DefaultValue::Default)) => quote_spanned! {ty.span()=>
+                builder.#variable.unwrap_or_default()
+            },
+            (None, Some(DefaultValue::Path(path))) => {
+                quote! { builder.#variable.unwrap_or_else(#path) }
+            }
+            (None, None) => quote! {
+                builder.#variable.ok_or_else(|| ::axolotl_nbt::NBTError::MissingField(#key.to_string()))?
+            },
+        };
+        build.push(quote! { #ident: #value });
+
+        let mut write_field = quote_spanned! {ty.span()=>
+            compound.write_next_tag(#key, #written)?;
+        };
+        if option.is_some() {
+            write_field = quote! {
+                if let ::std::option::Option::Some(value) = self.#ident {
+                    #write_field
+                }
+            };
+        } else {
+            write_field = quote! {
+                let value = self.#ident;
+                #write_field
+            };
+        }
+        if let Some(skip_if) = &attributes.skip_if {
+            write_field = quote! {
+                if !#skip_if(&self.#ident) {
+                    #write_field
+                }
+            };
+        }
+        write.push(write_field);
+    }
+
+    // The DataVersion is written unless a field already holds it
+    let versioned = container.data_version.as_ref().map(|version| {
+        let version = LitInt::new(&format!("{}i32", version.base10_digits()), version.span());
+        let key = "DataVersion";
+        let own_key = keys.contains_key(key);
+        if !own_key {
+            read_keys.push(quote! {
+                #key => {
+                    compound.skip_next_tag_value()?;
+                    ::std::result::Result::Ok(true)
+                }
+            });
+            write.push(quote! { compound.write_next_tag(#key, #version)?; });
+        }
+        quote! {
+            impl #impl_generics ::axolotl_nbt::migrate::Versioned for #type_ident #ty_generics #where_clause {
+                const DATA_VERSION: i32 = #version;
+            }
+        }
+    });
+
+    if let Some(errors) = errors {
+        return Err(errors);
+    }
+
+    let unknown = if container.deny_unknown.is_some() {
+        quote! { return ::std::result::Result::Err(::axolotl_nbt::NBTError::UnknownField(name)); }
+    } else {
+        quote! { compound.skip_next_tag_value()?; }
+    };
+
+    Ok(quote! {
+        #versioned
+
+        const _: () = {
+            pub struct __Builder #impl_generics #where_clause {
+                #(#builder_fields,)*
+                __marker: ::std::marker::PhantomData<fn() -> #type_ident #ty_generics>,
+            }
+
+            impl #impl_generics ::std::default::Default for __Builder #ty_generics #where_clause {
+                fn default() -> Self {
+                    Self {
+                        #(#builder_defaults,)*
+                        __marker: ::std::marker::PhantomData,
+                    }
+                }
+            }
+
+            impl #impl_generics ::axolotl_nbt::binary::compound::NBTCompound for #type_ident #ty_generics #where_clause {
+                type Builder = __Builder #ty_generics;
+
+                fn read_field<R: ::std::io::Read>(
+                    builder: &mut Self::Builder,
+                    name: &str,
+                    compound: &mut ::axolotl_nbt::binary::BinaryCompoundReader<R>,
+                ) -> ::std::result::Result<bool, ::axolotl_nbt::NBTError> {
+                    use ::axolotl_nbt::CompoundReader;
+                    match name {
+                        #(#read_keys)*
+                        _ => {
+                            #(#read_flattened)*
+                            ::std::result::Result::Ok(false)
+                        }
+                    }
+                }
+
+                fn build(builder: Self::Builder) -> ::std::result::Result<Self, ::axolotl_nbt::NBTError> {
+                    ::std::result::Result::Ok(Self {
+                        #(#build,)*
+                    })
+                }
+
+                fn write_fields<W: ::std::io::Write>(
+                    self,
+                    compound: &mut ::axolotl_nbt::binary::BinaryCompoundWriter<W>,
+                ) -> ::std::result::Result<(), ::axolotl_nbt::NBTError> {
+                    use ::axolotl_nbt::CompoundWriter;
+                    #(#write)*
+                    ::std::result::Result::Ok(())
+                }
+            }
+
+            impl #impl_generics ::axolotl_nbt::NBTDataType<::axolotl_nbt::binary::Binary> for #type_ident #ty_generics #where_clause {
+                fn read<R: ::std::io::Read>(reader: &mut R) -> ::std::result::Result<Self, ::axolotl_nbt::NBTError> {
+                    use ::axolotl_nbt::CompoundReader;
+                    use ::axolotl_nbt::binary::compound::NBTCompound;
+                    let mut builder = <<Self as NBTCompound>::Builder as ::std::default::Default>::default();
+                    let mut compound = ::axolotl_nbt::binary::BinaryCompoundReader::new(reader)?;
+                    while compound.next_tag()? != ::axolotl_nbt::Tag::End {
+                        let name = compound.read_next_tag_name()?;
+                        if !<Self as NBTCompound>::read_field(&mut builder, &name, &mut compound)? {
+                            #unknown
+                        }
+                    }
+                    <Self as NBTCompound>::build(builder)
+                }
+
+                fn write<W: ::std::io::Write, Name: ::std::convert::AsRef<[u8]>>(
+                    self,
+                    name: Name,
+                    writer: &mut W,
+                ) -> ::std::result::Result<(), ::axolotl_nbt::NBTError> {
+                    <::axolotl_nbt::binary::BinaryCompoundWriter<W> as ::axolotl_nbt::CompoundWriter<::axolotl_nbt::binary::Binary, W>>::write_start(writer, name)?;
+                    self.write_alone(writer)
+                }
+
+                fn write_alone<W: ::std::io::Write>(self, writer: &mut W) -> ::std::result::Result<(), ::axolotl_nbt::NBTError> {
+                    use ::axolotl_nbt::CompoundWriter;
+                    let mut compound = ::axolotl_nbt::binary::BinaryCompoundWriter::new(writer)?;
+                    ::axolotl_nbt::binary::compound::NBTCompound::write_fields(self, &mut compound)?;
+                    compound.end()
+                }
+
+                fn get_tag() -> ::axolotl_nbt::Tag {
+                    ::axolotl_nbt::Tag::Compound
+                }
+            }
+        };
+    })
+}
@@ -0,0 +1,435 @@
+use std::collections::HashMap;
+
+use proc_macro2::{Span, TokenStream};
+
+use quote::{format_ident, quote};
+
+use syn::{Data, DeriveInput, Error, Fields, Ident, Lit, LitInt, LitStr, Meta, Result, Type};
+
+use crate::attributes::{metas, set, string, unknown};
+
+/// The tag the id of a variant is written with
+#[derive(Clone, Copy)]
+enum IdTag {
+    Byte,
+    Short,
+    Int,
+}
+
+impl IdTag {
+    fn parse(lit: &LitStr) -> Result<Self> {
+        match lit.value().as_str() {
+            "byte" => Ok(IdTag::Byte),
+            "short" => Ok(IdTag::Short),
+            "int" => Ok(IdTag::Int),
+            value => Err(Error::new(
+                lit.span(),
+                format!("unknown tag `{}`, expected one of byte, short, int", value),
+            )),
+        }
+    }
+
+    fn rust_type(self) -> Ident {
+        match self {
+            IdTag::Byte => format_ident!("i8"),
+            IdTag::Short => format_ident!("i16"),
+            IdTag::Int => format_ident!("i32"),
+        }
+    }
+
+    fn tag(self) -> Ident {
+        match self {
+            IdTag::Byte => format_ident!("Byte"),
+            IdTag::Short => format_ident!("Short"),
+            IdTag::Int => format_ident!("Int"),
+        }
+    }
+
+    /// Checks the id fits and gives it the suffix of the type
+    fn literal(self, id: &LitInt) -> Result<LitInt> {
+        let value: i64 = id.base10_parse()?;
+        let fits = match self {
+            IdTag::Byte => i8::try_from(value).is_ok(),
+            IdTag::Short => i16::try_from(value).is_ok(),
+            IdTag::Int => i32::try_from(value).is_ok(),
+        };
+        if !fits {
+            return Err(Error::new(
+                id.span(),
+                format!("{} does not fit in a {}", value, self.rust_type()),
+            ));
+        }
+        Ok(LitInt::new(
+            &format!("{}{}", value, self.rust_type()),
+            id.span(),
+        ))
+    }
+}
+
+/// The key of a variant
+enum Key {
+    String(LitStr),
+    Id(LitInt),
+}
+
+impl Key {
+    fn span(&self) -> Span {
+        match self {
+            Key::String(lit) => lit.span(),
+            Key::Id(lit) => lit.span(),
+        }
+    }
+}
+
+/// If the type is `ident` without a path or generics
+fn holds(ty: &Type, ident: &Ident) -> bool {
+    match ty {
+        Type::Path(path) => path.qself.is_none() && path.path.is_ident(ident),
+        _ => false,
+    }
+}
+
+fn parse_key(attributes: &[syn::Attribute]) -> Result<Option<Key>> {
+    let mut key = None;
+    for meta in metas(attributes, "nbt")? {
+        let path = meta.path();
+        if path.is_ident("string") {
+            set(&mut key, Key::String(string(&meta)?), path)?;
+        } else if path.is_ident("id") {
+            let id = match &meta {
+                Meta::NameValue(name_value) => match &name_value.lit {
+                    Lit::Int(id) => id.clone(),
+                    lit => return Err(Error::new(lit.span(), "expected `id = <integer>`")),
+                },
+                _ => return Err(Error::new_spanned(&meta, "expected `id = <integer>`")),
+            };
+            set(&mut key, Key::Id(id), path)?;
+        } else {
+            return Err(unknown(&meta, "string, id"));
+        }
+    }
+    Ok(key)
+}
+
+fn parse_id_tag(input: &DeriveInput) -> Result<Option<(IdTag, Span)>> {
+    let mut tag = None;
+    for meta in metas(&input.attrs, "nbt")? {
+        let path = meta.path();
+        if path.is_ident("tag") {
+            let lit = string(&meta)?;
+            set(&mut tag, (IdTag::parse(&lit)?, lit.span()), path)?;
+        } else {
+            return Err(unknown(&meta, "tag"));
+        }
+    }
+    Ok(tag)
+}
+
+pub(crate) fn parse_enum(input: DeriveInput) -> Result<TokenStream> {
+    let type_ident = &input.ident;
+    let data = match &input.data {
+        Data::Enum(data) => data,
+        _ => {
+            return Err(Error::new_spanned(
+                type_ident,
+                "NbtEnum can only be used on enums",
+            ))
+        }
+    };
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "NbtEnum can not be used on generic enums",
+        ));
+    }
+    let id_tag = parse_id_tag(&input)?;
+
+    let mut variants: Vec<(&Ident, Key)> = Vec::new();
+    let mut fallback: Option<(&Ident, &Type)> = None;
+    for variant in &data.variants {
+        let key = parse_key(&variant.attrs)?;
+        match (&variant.fields, key) {
+            (Fields::Unit, Some(key)) => variants.push((&variant.ident, key)),
+            (Fields::Unit, None) => {
+                return Err(Error::new_spanned(
+                    &variant.ident,
+                    "every variant needs `#[nbt(string = \"...\")]` or `#[nbt(id = ...)]`",
+                ))
+            }
+            (Fields::Unnamed(fields), None) if fields.unnamed.len() == 1 => {
+                if fallback.is_some() {
+                    return Err(Error::new_spanned(
+                        &variant.ident,
+                        "only one variant can hold the unknown values",
+                    ));
+                }
+                fallback = Some((&variant.ident, &fields.unnamed[0].ty));
+            }
+            (Fields::Unnamed(fields), Some(key)) if fields.unnamed.len() == 1 => {
+                return Err(Error::new(
+                    key.span(),
+                    "the variant holding unknown values can not have a key",
+                ))
+            }
+            _ => {
+                return Err(Error::new_spanned(
+                    &variant.fields,
+                    "NbtEnum variants must be unit variants or a single unknown variant like `Unknown(String)`",
+                ))
+            }
+        }
+    }
+
+    let by_id = match variants.first() {
+        Some((_, Key::Id(_))) => true,
+        Some((_, Key::String(_))) => false,
+        None => {
+            return Err(Error::new_spanned(
+                type_ident,
+                "NbtEnum needs at least one variant with a key",
+            ))
+        }
+    };
+    let mut seen: HashMap<String, Span> = HashMap::new();
+    for (_, key) in &variants {
+        let (value, span) = match (key, by_id) {
+            (Key::String(lit), false) => (lit.value(), lit.span()),
+            (Key::Id(lit), true) => (lit.base10_digits().to_string(), lit.span()),
+            (key, _) => {
+                return Err(Error::new(
+                    key.span(),
+                    "every variant must use `string` or every variant must use `id`",
+                ))
+            }
+        };
+        if let Some(first) = seen.insert(value.clone(), span) {
+            let mut error = Error::new(span, format!("duplicate key `{}`", value));
+            error.combine(Error::new(first, "first used here"));
+            return Err(error);
+        }
+    }
+    if let (false, Some((_, span))) = (by_id, id_tag) {
+        return Err(Error::new(span, "`tag` is only used with `id` variants"));
+    }
+    // The unknown values are kept as they were read, so the variant must hold the type of the key
+    let key_type = if by_id {
+        id_tag.map(|(tag, _)| tag).unwrap_or(IdTag::Int).rust_type()
+    } else {
+        format_ident!("String")
+    };
+    let fallback = match fallback {
+        Some((_, ty)) if !holds(ty, &key_type) => {
+            return Err(Error::new_spanned(
+                ty,
+                format!("the unknown variant must hold `{}`", key_type),
+            ))
+        }
+        fallback => fallback.map(|(ident, _)| ident),
+    };
+
+    let idents: Vec<&Ident> = variants.iter().map(|(ident, _)| *ident).collect();
+    let expecting = if by_id {
+        format!("an id of {}", type_ident)
+    } else {
+        format!("a name of {}", type_ident)
+    };
+    let tokens = if by_id {
+        let id_tag = id_tag.map(|(tag, _)| tag).unwrap_or(IdTag::Int);
+        let ids = variants
+            .iter()
+            .map(|(_, key)| match key {
+                Key::Id(id) => id_tag.literal(id),
+                Key::String(_) => unreachable!(),
+            })
+            .collect::<Result<Vec<LitInt>>>()?;
+        let id_type = id_tag.rust_type();
+        let tag = id_tag.tag();
+        let serialize = format_ident!("serialize_{}", id_type);
+        let deserialize = format_ident!("deserialize_{}", id_type);
+        let (read_fallback, id_fallback, visit_fallback) = match fallback {
+            Some(fallback) => (
+                quote! { id => Self::#fallback(id) },
+                quote! { Self::#fallback(id) => *id },
+                quote! { id => #type_ident::#fallback(id) },
+            ),
+            None => (
+                quote! { id => return ::std::result::Result::Err(::axolotl_nbt::NBTError::UnknownVariant(id.to_string())) },
+                quote! {},
+                quote! {
+                    _ => return ::std::result::Result::Err(E::invalid_value(serde::de::Unexpected::Signed(value), &self))
+                },
+            ),
+        };
+        quote! {
+            impl ::axolotl_nbt::NBTDataType<::axolotl_nbt::binary::Binary> for #type_ident {
+                fn read<R: ::std::io::Read>(reader: &mut R) -> ::std::result::Result<Self, ::axolotl_nbt::NBTError> {
+                    let id = <#id_type as ::axolotl_nbt::NBTDataType<::axolotl_nbt::binary::Binary>>::read(reader)?;
+                    ::std::result::Result::Ok(match id {
+                        #(#ids => Self::#idents,)*
+                        #read_fallback
+                    })
+                }
+
+                fn write<W: ::std::io::Write, Name: ::std::convert::AsRef<[u8]>>(
+                    self,
+                    name: Name,
+                    writer: &mut W,
+                ) -> ::std::result::Result<(), ::axolotl_nbt::NBTError> {
+                    <#id_type as ::axolotl_nbt::NBTDataType<::axolotl_nbt::binary::Binary>>::write(self.id(), name, writer)
+                }
+
+                fn write_alone<W: ::std::io::Write>(self, writer: &mut W) -> ::std::result::Result<(), ::axolotl_nbt::NBTError> {
+                    <#id_type as ::axolotl_nbt::NBTDataType<::axolotl_nbt::binary::Binary>>::write_alone(self.id(), writer)
+                }
+
+                fn get_tag() -> ::axolotl_nbt::Tag {
+                    ::axolotl_nbt::Tag::#tag
+                }
+            }
+
+            impl #type_ident {
+                /// The id written to NBT
+                pub fn id(&self) -> #id_type {
+                    match self {
+                        #(Self::#idents => #ids,)*
+                        #id_fallback
+                    }
+                }
+            }
+
+            const _: () = {
+                use ::axolotl_nbt::__private::serde;
+
+                impl serde::Serialize for #type_ident {
+                    fn serialize<S>(&self, serializer: S) -> ::std::result::Result<S::Ok, S::Error> where S: serde::Serializer {
+                        serializer.#serialize(self.id())
+                    }
+                }
+
+                struct __Visitor;
+
+                impl<'de> serde::de::Visitor<'de> for __Visitor {
+                    type Value = #type_ident;
+
+                    fn expecting(&self, formatter: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                        formatter.write_str(#expecting)
+                    }
+
+                    fn visit_i64<E>(self, value: i64) -> ::std::result::Result<Self::Value, E> where E: serde::de::Error {
+                        let id = <#id_type as ::std::convert::TryFrom<i64>>::try_from(value)
+                            .map_err(|_| E::invalid_value(serde::de::Unexpected::Signed(value), &self))?;
+                        ::std::result::Result::Ok(match id {
+                            #(#ids => #type_ident::#idents,)*
+                            #visit_fallback
+                        })
+                    }
+
+                    fn visit_u64<E>(self, value: u64) -> ::std::result::Result<Self::Value, E> where E: serde::de::Error {
+                        let value = <i64 as ::std::convert::TryFrom<u64>>::try_from(value)
+                            .map_err(|_| E::invalid_value(serde::de::Unexpected::Unsigned(value), &self))?;
+                        self.visit_i64(value)
+                    }
+                }
+
+                impl<'de> serde::Deserialize<'de> for #type_ident {
+                    fn deserialize<D>(deserializer: D) -> ::std::result::Result<Self, D::Error> where D: serde::Deserializer<'de> {
+                        deserializer.#deserialize(__Visitor)
+                    }
+                }
+            };
+        }
+    } else {
+        let names: Vec<&LitStr> = variants
+            .iter()
+            .map(|(_, key)| match key {
+                Key::String(name) => name,
+                Key::Id(_) => unreachable!(),
+            })
+            .collect();
+        let (read_fallback, as_str_fallback, visit_fallback) = match fallback {
+            Some(fallback) => (
+                quote! { _ => Self::#fallback(name) },
+                quote! { Self::#fallback(name) => name.as_str() },
+                quote! { _ => #type_ident::#fallback(::std::string::ToString::to_string(name)) },
+            ),
+            None => (
+                quote! { _ => return ::std::result::Result::Err(::axolotl_nbt::NBTError::UnknownVariant(name)) },
+                quote! {},
+                quote! { _ => return ::std::result::Result::Err(E::unknown_variant(name, NAMES)) },
+            ),
+        };
+        quote! {
+            impl ::axolotl_nbt::NBTDataType<::axolotl_nbt::binary::Binary> for #type_ident {
+                fn read<R: ::std::io::Read>(reader: &mut R) -> ::std::result::Result<Self, ::axolotl_nbt::NBTError> {
+                    let name = <::std::string::String as ::axolotl_nbt::NBTDataType<::axolotl_nbt::binary::Binary>>::read(reader)?;
+                    ::std::result::Result::Ok(match name.as_str() {
+                        #(#names => Self::#idents,)*
+                        #read_fallback
+                    })
+                }
+
+                fn write<W: ::std::io::Write, Name: ::std::convert::AsRef<[u8]>>(
+                    self,
+                    name: Name,
+                    writer: &mut W,
+                ) -> ::std::result::Result<(), ::axolotl_nbt::NBTError> {
+                    <&str as ::axolotl_nbt::NBTDataType<::axolotl_nbt::binary::Binary>>::write(self.as_str(), name, writer)
+                }
+
+                fn write_alone<W: ::std::io::Write>(self, writer: &mut W) -> ::std::result::Result<(), ::axolotl_nbt::NBTError> {
+                    <&str as ::axolotl_nbt::NBTDataType<::axolotl_nbt::binary::Binary>>::write_alone(self.as_str(), writer)
+                }
+
+                fn get_tag() -> ::axolotl_nbt::Tag {
+                    ::axolotl_nbt::Tag::String
+                }
+            }
+
+            impl #type_ident {
+                /// The name written to NBT
+                pub fn as_str(&self) -> &str {
+                    match self {
+                        #(Self::#idents => #names,)*
+                        #as_str_fallback
+                    }
+                }
+            }
+
+            const _: () = {
+                use ::axolotl_nbt::__private::serde;
+
+                const NAMES: &[&str] = &[#(#names),*];
+
+                impl serde::Serialize for #type_ident {
+                    fn serialize<S>(&self, serializer: S) -> ::std::result::Result<S::Ok, S::Error> where S: serde::Serializer {
+                        serializer.serialize_str(self.as_str())
+                    }
+                }
+
+                struct __Visitor;
+
+                impl<'de> serde::de::Visitor<'de> for __Visitor {
+                    type Value = #type_ident;
+
+                    fn expecting(&self, formatter: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                        formatter.write_str(#expecting)
+                    }
+
+                    fn visit_str<E>(self, name: &str) -> ::std::result::Result<Self::Value, E> where E: serde::de::Error {
+                        ::std::result::Result::Ok(match name {
+                            #(#names => #type_ident::#idents,)*
+                            #visit_fallback
+                        })
+                    }
+                }
+
+                impl<'de> serde::Deserialize<'de> for #type_ident {
+                    fn deserialize<D>(deserializer: D) -> ::std::result::Result<Self, D::Error> where D: serde::Deserializer<'de> {
+                        deserializer.deserialize_str(__Visitor)
+                    }
+                }
+            };
+        }
+    };
+    Ok(tokens)
+}
@@ -0,0 +1,55 @@
+//! Structures that are read and written as the fields of a compound.
+//!
+//! `#[derive(NbtCompound)]` implements [NBTCompound] and uses it for `NBTDataType<Binary>`.
+//! A field marked `#[nbt(flatten)]` hands the keys it does not know to the [NBTCompound] of the field.
+use crate::binary::{BinaryCompoundReader, BinaryCompoundWriter};
+use crate::{NBTDataType, NBTError, Value};
+use std::io::{Read, Write};
+
+pub trait NBTCompound: Sized {
+    /// Holds the fields read so far
+    type Builder: Default;
+
+    /// Reads the value of the next tag if `name` belongs to this structure.
+    /// Returns false without reading anything otherwise
+    fn read_field<R: Read>(
+        builder: &mut Self::Builder,
+        name: &str,
+        compound: &mut BinaryCompoundReader<R>,
+    ) -> Result<bool, NBTError>;
+
+    /// Called once the end of the compound is reached
+    fn build(builder: Self::Builder) -> Result<Self, NBTError>;
+
+    /// Writes every field. The start and the end of the compound are written by the caller
+    fn write_fields<W: Write>(self, compound: &mut BinaryCompoundWriter<W>)
+        -> Result<(), NBTError>;
+}
+
+/// Keeps every key it is given. Flatten it into a structure to keep the keys the structure does not know
+impl NBTCompound for Vec<Value> {
+    type Builder = Vec<Value>;
+
+    fn read_field<R: Read>(
+        builder: &mut Self::Builder,
+        name: &str,
+        compound: &mut BinaryCompoundReader<R>,
+    ) -> Result<bool, NBTError> {
+        builder.push(compound.read_next_entry(name)?);
+        Ok(true)
+    }
+
+    fn build(builder: Self::Builder) -> Result<Self, NBTError> {
+        Ok(builder)
+    }
+
+    fn write_fields<W: Write>(
+        self,
+        compound: &mut BinaryCompoundWriter<W>,
+    ) -> Result<(), NBTError> {
+        for value in self {
+            value.write_alone(compound.writer)?;
+        }
+        Ok(())
+    }
+}
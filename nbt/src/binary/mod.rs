@@ -7,6 +7,7 @@ use std::fmt::Debug;
 use std::io::{Read, Write};
 
 pub mod binary_uuid;
+pub mod compound;
 pub mod sync;
 
 #[derive(Debug)]
@@ -31,6 +32,58 @@ impl Binary {
             _ => None,
         }
     }
+    /// Skips the payload of a tag without reading it into memory
+    pub fn skip_value<R: Read>(tag: Tag, reader: &mut R) -> Result<(), NBTError> {
+        match tag {
+            Tag::End => Ok(()),
+            Tag::ByteArray | Tag::IntArray | Tag::LongArray => {
+                let element = match tag {
+                    Tag::ByteArray => Tag::Byte,
+                    Tag::IntArray => Tag::Int,
+                    _ => Tag::Long,
+                };
+                let length = reader.read_i32::<BigEndian>()?.max(0) as u64;
+                Self::skip_bytes(reader, length * element.get_size() as u64)
+            }
+            Tag::String => {
+                let length = reader.read_u16::<BigEndian>()?;
+                Self::skip_bytes(reader, length as u64)
+            }
+            Tag::List => {
+                let element = Tag::read(reader)?;
+                let length = reader.read_i32::<BigEndian>()?.max(0) as u64;
+                match element {
+                    // Only empty lists are lists of End
+                    Tag::End => Ok(()),
+                    element if element.get_size() == 0 => {
+                        for _ in 0..length {
+                            Self::skip_value(element, reader)?;
+                        }
+                        Ok(())
+                    }
+                    element => Self::skip_bytes(reader, length * element.get_size() as u64),
+                }
+            }
+            Tag::Compound => loop {
+                let tag = Tag::read(reader)?;
+                if tag == Tag::End {
+                    return Ok(());
+                }
+                let length = reader.read_u16::<BigEndian>()?;
+                Self::skip_bytes(reader, length as u64)?;
+                Self::skip_value(tag, reader)?;
+            },
+            tag => Self::skip_bytes(reader, tag.get_size() as u64),
+        }
+    }
+
+    fn skip_bytes<R: Read>(reader: &mut R, length: u64) -> Result<(), NBTError> {
+        let skipped = std::io::copy(&mut reader.take(length), &mut std::io::sink())?;
+        if skipped != length {
+            return Err(NBTError::UnexpectedEnd);
+        }
+        Ok(())
+    }
     pub fn get_list_type_id(typ: ListType) -> i8 {
         match typ {
             ListType::ByteArray => 7,
@@ -76,9 +129,9 @@ impl NBTType for Binary {
 /// Order:
 ///   - List, ByteList, IntList, LongList
 ///   - If List:
-///         Name, Tag, Length, Elements of Tag
+///     Name, Tag, Length, Elements of Tag
 ///   - Else:
-///         Name, Length, Elements just written
+///     Name, Length, Elements just written
 pub struct BinaryListReader<'reader, R: Read> {
     reader: &'reader mut R,
     tag: ListType,
@@ -92,7 +145,7 @@ impl<'reader, Reader: Read> ListReader<'reader, Binary, Reader>
     where
         Self: Sized,
     {
-        let length = reader.read_i32::<BigEndian>()?;
+        let length = reader.read_i32::<BigEndian>()?.max(0);
         Ok(Self {
             reader,
             tag: list_type,
@@ -105,7 +158,7 @@ impl<'reader, Reader: Read> ListReader<'reader, Binary, Reader>
         Self: Sized,
     {
         let tag = Tag::read(reader)?;
-        let length = reader.read_i32::<BigEndian>()?;
+        let length = reader.read_i32::<BigEndian>()?.max(0);
         Ok(Self {
             reader,
             tag: ListType::List(tag),
@@ -150,6 +203,28 @@ pub struct BinaryCompoundReader<'reader, R: Read> {
     next_tag: Option<Tag>,
 }
 
+impl<R: Read> BinaryCompoundReader<'_, R> {
+    /// Reads the value of the key `name`. Its tag must be the tag of the type unless the type
+    /// takes any tag
+    pub fn read_next_entry<DataType: NBTDataType<Binary>>(
+        &mut self,
+        name: &str,
+    ) -> Result<DataType, NBTError> {
+        let tag = match self.next_tag.take() {
+            Some(tag) => tag,
+            None => Tag::read(self.reader)?,
+        };
+        if tag == Tag::End {
+            return Err(NBTError::UnexpectedEnd);
+        }
+        if !DataType::ANY_TAG && tag != DataType::get_tag() {
+            self.next_tag = Some(tag);
+            return Err(NBTError::ExpectedTag(DataType::get_tag(), tag));
+        }
+        DataType::read_entry(name, tag, self.reader)
+    }
+}
+
 impl<'reader, R: Read> CompoundReader<'reader, Binary, R> for BinaryCompoundReader<'reader, R> {
     fn new(reader: &'reader mut R) -> Result<Self, NBTError>
     where
@@ -188,29 +263,7 @@ impl<'reader, R: Read> CompoundReader<'reader, Binary, R> for BinaryCompoundRead
     }
 
     fn read_next_tag_value<DataType: NBTDataType<Binary>>(&mut self) -> Result<DataType, NBTError> {
-        if let Some(tag) = self.next_tag.take() {
-            if tag == Tag::End {
-                return Err(NBTError::UnexpectedEnd);
-            }
-            if tag != DataType::get_tag() {
-                self.next_tag = Some(tag);
-                return Err(NBTError::ExpectedTag(DataType::get_tag(), tag));
-            }
-
-            let data = DataType::read(self.reader)?;
-            Ok(data)
-        } else {
-            let tag = Tag::read(self.reader)?;
-            if tag == Tag::End {
-                return Err(NBTError::UnexpectedEnd);
-            }
-            if tag != DataType::get_tag() {
-                self.next_tag = Some(tag);
-                return Err(NBTError::ExpectedTag(DataType::get_tag(), tag));
-            }
-            let data = DataType::read(self.reader)?;
-            Ok(data)
-        }
+        self.read_next_entry("")
     }
 
     fn read_next_tag<DataType: NBTDataType<Binary>>(
@@ -241,6 +294,17 @@ impl<'reader, R: Read> CompoundReader<'reader, Binary, R> for BinaryCompoundRead
             Ok((string, data))
         }
     }
+
+    fn skip_next_tag_value(&mut self) -> Result<(), NBTError> {
+        let tag = match self.next_tag.take() {
+            Some(tag) => tag,
+            None => Tag::read(self.reader)?,
+        };
+        if tag == Tag::End {
+            return Err(NBTError::UnexpectedEnd);
+        }
+        Binary::skip_value(tag, self.reader)
+    }
     #[cfg(feature = "value")]
     fn read_to_end(self) -> Result<Vec<Value>, NBTError> {
         let mut result = Vec::new();
@@ -296,7 +360,7 @@ impl<'writer, W: Write> CompoundWriter<'writer, Binary, W> for BinaryCompoundWri
         name: impl AsRef<[u8]>,
         value: DataType,
     ) -> Result<(), NBTError> {
-        value.write(name, self.writer)
+        value.write_entry(name, self.writer)
     }
 
     fn end(self) -> Result<(), NBTError> {
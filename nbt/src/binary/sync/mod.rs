@@ -1,8 +1,8 @@
 use byteorder::{ReadBytesExt, WriteBytesExt};
 
-use crate::binary::Binary;
+use crate::binary::{Binary, BinaryListWriter};
 use crate::NBTError::InvalidTag;
-use crate::{ListType, NBTDataType, NBTError, NBTType, Tag};
+use crate::{ListType, ListWriter, NBTDataType, NBTError, NBTType, Tag};
 use byteorder::BigEndian;
 use std::io::{Read, Write};
 
@@ -337,3 +337,124 @@ impl NBTDataType<Binary> for usize {
         Tag::Int
     }
 }
+
+fn sequence_tag(list_type: ListType) -> Tag {
+    match list_type {
+        ListType::ByteArray => Tag::ByteArray,
+        ListType::IntArray => Tag::IntArray,
+        ListType::LongArray => Tag::LongArray,
+        ListType::List(_) => Tag::List,
+    }
+}
+
+fn read_sequence<T: NBTDataType<Binary>, R: Read>(
+    list_type: ListType,
+    reader: &mut R,
+) -> Result<Vec<T>, NBTError> {
+    if let ListType::List(expected) = list_type {
+        let tag = Tag::read(reader)?;
+        let length = reader.read_i32::<BigEndian>()?;
+        if length <= 0 {
+            return Ok(Vec::new());
+        }
+        if tag != expected {
+            return Err(NBTError::ExpectedTag(expected, tag));
+        }
+        (0..length).map(|_| T::read(reader)).collect()
+    } else {
+        let length = reader.read_i32::<BigEndian>()?;
+        (0..length.max(0)).map(|_| T::read(reader)).collect()
+    }
+}
+
+fn write_sequence<T: NBTDataType<Binary>, W: Write>(
+    list_type: ListType,
+    values: Vec<T>,
+    writer: &mut W,
+) -> Result<(), NBTError> {
+    BinaryListWriter::<W>::write_sub_sequence_header(writer, list_type, values.len() as i32)?;
+    for value in values {
+        value.write_alone(writer)?;
+    }
+    Ok(())
+}
+
+/// Written as a TAG_Byte_Array, TAG_Int_Array or TAG_Long_Array when [NBTDataType::get_list_tag] of `T` says so.
+/// Otherwise a TAG_List of `T`
+impl<T: NBTDataType<Binary>> NBTDataType<Binary> for Vec<T> {
+    fn read<R: Read>(reader: &mut R) -> Result<Self, NBTError>
+        where
+            Self: Sized,
+    {
+        read_sequence(T::get_list_tag(), reader)
+    }
+
+    fn write<W: Write, Name: AsRef<[u8]>>(
+        self,
+        name: Name,
+        writer: &mut W,
+    ) -> Result<(), NBTError> {
+        Self::get_tag().write_alone(writer)?;
+        Binary::write_tag_name(writer, name)?;
+        self.write_alone(writer)
+    }
+
+    fn write_alone<W: Write>(self, writer: &mut W) -> Result<(), NBTError> {
+        write_sequence(T::get_list_tag(), self, writer)
+    }
+
+    fn get_tag() -> Tag {
+        sequence_tag(T::get_list_tag())
+    }
+}
+
+/// A sequence written with the tag `TAG` instead of the one [NBTDataType::get_list_tag] picks.
+///
+/// `TAG` is `Tag::List`, `Tag::ByteArray`, `Tag::IntArray` or `Tag::LongArray` as an i8.
+/// Used by `#[nbt(tag = "...")]` on a field
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sequence<T, const TAG: i8>(pub Vec<T>);
+
+impl<T: NBTDataType<Binary>, const TAG: i8> Sequence<T, TAG> {
+    fn list_type() -> Result<ListType, NBTError> {
+        let (list_type, element) = match Binary::tag_from_i8(TAG) {
+            Some(Tag::List) => return Ok(ListType::List(T::get_tag())),
+            Some(Tag::ByteArray) => (ListType::ByteArray, Tag::Byte),
+            Some(Tag::IntArray) => (ListType::IntArray, Tag::Int),
+            Some(Tag::LongArray) => (ListType::LongArray, Tag::Long),
+            _ => return Err(InvalidTag(TAG)),
+        };
+        if T::get_tag() != element {
+            return Err(NBTError::ListTypeMismatch(element, T::get_tag()));
+        }
+        Ok(list_type)
+    }
+}
+
+impl<T: NBTDataType<Binary>, const TAG: i8> NBTDataType<Binary> for Sequence<T, TAG> {
+    fn read<R: Read>(reader: &mut R) -> Result<Self, NBTError>
+        where
+            Self: Sized,
+    {
+        read_sequence(Self::list_type()?, reader).map(Sequence)
+    }
+
+    fn write<W: Write, Name: AsRef<[u8]>>(
+        self,
+        name: Name,
+        writer: &mut W,
+    ) -> Result<(), NBTError> {
+        let list_type = Self::list_type()?;
+        sequence_tag(list_type).write_alone(writer)?;
+        Binary::write_tag_name(writer, name)?;
+        write_sequence(list_type, self.0, writer)
+    }
+
+    fn write_alone<W: Write>(self, writer: &mut W) -> Result<(), NBTError> {
+        write_sequence(Self::list_type()?, self.0, writer)
+    }
+
+    fn get_tag() -> Tag {
+        Binary::tag_from_i8(TAG).unwrap_or(Tag::End)
+    }
+}
@@ -1,10 +1,10 @@
 use crate::binary::{Binary, BinaryCompoundReader, BinaryCompoundWriter, BinaryListWriter};
-use crate::value::NameLessValue;
+use crate::value::{List, NameLessValue};
 use crate::{
     CompoundReader, CompoundWriter, ListType, ListWriter, NBTDataType, NBTError, NBTType, Tag,
     Value,
 };
-use byteorder::{BigEndian, ReadBytesExt};
+use byteorder::{BigEndian, ByteOrder, ReadBytesExt};
 use std::io::{Read, Write};
 
 impl NBTDataType<Binary> for Value {
@@ -50,65 +50,30 @@ impl NBTDataType<Binary> for Value {
                 name: tag_name,
                 value: f64::read(reader)?,
             }),
-            Tag::ByteArray => {
-                let length = i32::read(reader)?;
-                let mut bytes = vec![0; length as usize];
-                reader.read_i8_into(&mut bytes).map_err(NBTError::IO)?;
-                Ok(Value::ByteArray {
-                    name: tag_name,
-                    value: bytes,
-                })
-            }
-            Tag::String => {
-                let length = i16::read(reader)?;
-                let mut bytes = Vec::with_capacity(length as usize);
-                reader.take(length as u64).read_to_end(&mut bytes)?;
-                Ok(Value::String {
-                    name: tag_name,
-                    value: String::from_utf8(bytes).map_err(NBTError::NotAString)?,
-                })
-            }
-            Tag::IntArray => {
-                let length = i32::read(reader)?;
-                let mut ints = vec![0; length as usize];
-                reader
-                    .read_i32_into::<BigEndian>(&mut ints)
-                    .map_err(NBTError::IO)?;
-                Ok(Value::IntArray {
-                    name: tag_name,
-                    value: ints,
-                })
-            }
-            Tag::LongArray => {
-                let length = i32::read(reader)?;
-                let mut longs = vec![0; length as usize];
-                reader
-                    .read_i64_into::<BigEndian>(&mut longs)
-                    .map_err(NBTError::IO)?;
-                Ok(Value::LongArray {
-                    name: tag_name,
-                    value: longs,
-                })
-            }
-            Tag::List => {
-                let list_type = Tag::read(reader)?;
-                let length = i32::read(reader)?;
-                let mut list = Vec::with_capacity(length as usize);
-                for _ in 0..length {
-                    list.push(NameLessValue::read(list_type, reader)?);
-                }
-                Ok(Value::List {
-                    name: tag_name,
-                    value: list,
-                })
-            }
-            Tag::Compound => {
-                let result = BinaryCompoundReader::new(reader)?;
-                Ok(Value::Compound {
-                    name: tag_name,
-                    value: result.read_to_end()?,
-                })
-            }
+            Tag::ByteArray => Ok(Value::ByteArray {
+                name: tag_name,
+                value: read_byte_array(reader)?,
+            }),
+            Tag::String => Ok(Value::String {
+                name: tag_name,
+                value: read_string(reader)?,
+            }),
+            Tag::IntArray => Ok(Value::IntArray {
+                name: tag_name,
+                value: read_int_array(reader)?,
+            }),
+            Tag::LongArray => Ok(Value::LongArray {
+                name: tag_name,
+                value: read_long_array(reader)?,
+            }),
+            Tag::List => Ok(Value::List {
+                name: tag_name,
+                value: List::read(reader)?,
+            }),
+            Tag::Compound => Ok(Value::Compound {
+                name: tag_name,
+                value: read_compound(reader)?,
+            }),
         }
     }
 
@@ -150,16 +115,7 @@ impl NBTDataType<Binary> for Value {
                 value.write(name, writer)?;
             }
             Value::List { name, value } => {
-                let tag = if let Some(first) = value.first() {
-                    first.tag()
-                } else {
-                    return Ok(());
-                };
-                let mut writer =
-                    BinaryListWriter::new(writer, value.len() as i32, ListType::List(tag), name)?;
-                for i in value {
-                    writer.write_next_tag(i)?;
-                }
+                value.write(name, writer)?;
             }
             Value::Compound { name, value } => {
                 BinaryCompoundWriter::write_start(writer, name)?;
@@ -192,6 +148,22 @@ impl NBTDataType<Binary> for Value {
     fn get_tag() -> Tag {
         Tag::Compound
     }
+
+    const ANY_TAG: bool = true;
+
+    /// The value is named after the key
+    fn read_entry<R: Read>(name: &str, tag: Tag, reader: &mut R) -> Result<Self, NBTError> {
+        Ok(NameLessValue::read(tag, reader)?.into_named(name))
+    }
+
+    /// The value is written under the key whatever its own name is
+    fn write_entry<W: Write, Name: AsRef<[u8]>>(
+        self,
+        name: Name,
+        writer: &mut W,
+    ) -> Result<(), NBTError> {
+        self.into_nameless().write_entry(name, writer)
+    }
 }
 
 impl NameLessValue {
@@ -204,50 +176,48 @@ impl NameLessValue {
             Tag::Long => Ok(NameLessValue::Long(i64::read(reader)?)),
             Tag::Float => Ok(NameLessValue::Float(f32::read(reader)?)),
             Tag::Double => Ok(NameLessValue::Double(f64::read(reader)?)),
-            Tag::ByteArray => {
-                let length = i32::read(reader)?;
-                let mut bytes = vec![0; length as usize];
-                reader.read_i8_into(&mut bytes).map_err(NBTError::IO)?;
-                Ok(NameLessValue::ByteArray(bytes))
-            }
-            Tag::String => {
-                let length = i16::read(reader)?;
-                let mut bytes = Vec::with_capacity(length as usize);
-                reader.take(length as u64).read_to_end(&mut bytes)?;
-                Ok(NameLessValue::String(
-                    String::from_utf8(bytes).map_err(NBTError::NotAString)?,
-                ))
-            }
-            Tag::List => {
-                let list_type = Tag::read(reader)?;
-                let length = i32::read(reader)?;
-                let mut list = Vec::with_capacity(length as usize);
-                for _ in 0..length {
-                    list.push(NameLessValue::read(list_type, reader)?);
+            Tag::ByteArray => Ok(NameLessValue::ByteArray(read_byte_array(reader)?)),
+            Tag::String => Ok(NameLessValue::String(read_string(reader)?)),
+            Tag::List => Ok(NameLessValue::List(List::read(reader)?)),
+            Tag::Compound => Ok(NameLessValue::Compound(read_compound(reader)?)),
+            Tag::IntArray => Ok(NameLessValue::IntArray(read_int_array(reader)?)),
+            Tag::LongArray => Ok(NameLessValue::LongArray(read_long_array(reader)?)),
+        }
+    }
+}
+
+impl<W: Write> BinaryListWriter<'_, W> {
+    /// Writes the elements of the list after its header. Numeric elements are written in one go
+    fn write_elements(mut self, list: List) -> Result<(), NBTError> {
+        match list {
+            List::Byte(bytes) => {
+                let bytes: Vec<u8> = bytes.into_iter().map(|byte| byte as u8).collect();
+                self.writer.write_all(&bytes)?;
+            }
+            List::Short(shorts) => self.write_numbers(&shorts, 2, BigEndian::write_i16_into)?,
+            List::Int(ints) => self.write_numbers(&ints, 4, BigEndian::write_i32_into)?,
+            List::Long(longs) => self.write_numbers(&longs, 8, BigEndian::write_i64_into)?,
+            List::Float(floats) => self.write_numbers(&floats, 4, BigEndian::write_f32_into)?,
+            List::Double(doubles) => self.write_numbers(&doubles, 8, BigEndian::write_f64_into)?,
+            list => {
+                for value in list {
+                    self.write_next_tag(value)?;
                 }
-                Ok(NameLessValue::List(list))
-            }
-            Tag::Compound => {
-                let result = BinaryCompoundReader::new(reader)?;
-                Ok(NameLessValue::Compound(result.read_to_end()?))
-            }
-            Tag::IntArray => {
-                let length = i32::read(reader)?;
-                let mut ints = vec![0; length as usize];
-                reader
-                    .read_i32_into::<BigEndian>(&mut ints)
-                    .map_err(NBTError::IO)?;
-                Ok(NameLessValue::IntArray(ints))
-            }
-            Tag::LongArray => {
-                let length = i32::read(reader)?;
-                let mut longs = vec![0; length as usize];
-                reader
-                    .read_i64_into::<BigEndian>(&mut longs)
-                    .map_err(NBTError::IO)?;
-                Ok(NameLessValue::LongArray(longs))
             }
         }
+        Ok(())
+    }
+
+    fn write_numbers<T>(
+        &mut self,
+        values: &[T],
+        size: usize,
+        into: fn(&[T], &mut [u8]),
+    ) -> Result<(), NBTError> {
+        let mut bytes = vec![0; values.len() * size];
+        into(values, &mut bytes);
+        self.writer.write_all(&bytes)?;
+        Ok(())
     }
 }
 
@@ -304,19 +274,7 @@ impl NBTDataType<Binary> for NameLessValue {
                 data.write_alone(writer)?;
             }
             NameLessValue::List(data) => {
-                let tag = if let Some(first) = data.first() {
-                    first.tag()
-                } else {
-                    return Ok(());
-                };
-                let mut writer = BinaryListWriter::new_sub_sequence(
-                    writer,
-                    data.len() as i32,
-                    ListType::List(tag),
-                )?;
-                for i in data {
-                    writer.write_next_tag(i)?;
-                }
+                data.write_alone(writer)?;
             }
             NameLessValue::Compound(data) => {
                 for i in data {
@@ -354,4 +312,143 @@ impl NBTDataType<Binary> for NameLessValue {
     fn get_tag() -> Tag {
         Tag::Compound
     }
+
+    const ANY_TAG: bool = true;
+
+    fn read_entry<R: Read>(_name: &str, tag: Tag, reader: &mut R) -> Result<Self, NBTError> {
+        NameLessValue::read(tag, reader)
+    }
+
+    /// Written with the tag of the value
+    fn write_entry<W: Write, Name: AsRef<[u8]>>(
+        self,
+        name: Name,
+        writer: &mut W,
+    ) -> Result<(), NBTError> {
+        if matches!(self, NameLessValue::End) {
+            return Err(NBTError::UnexpectedEnd);
+        }
+        self.tag().write_alone(writer)?;
+        Binary::write_tag_name(writer, name)?;
+        self.write_alone(writer)
+    }
+}
+
+fn read_byte_array<R: Read>(reader: &mut R) -> Result<Vec<i8>, NBTError> {
+    let length = i32::read(reader)?;
+    let mut bytes = vec![0; length.max(0) as usize];
+    reader.read_i8_into(&mut bytes).map_err(NBTError::IO)?;
+    Ok(bytes)
+}
+
+fn read_int_array<R: Read>(reader: &mut R) -> Result<Vec<i32>, NBTError> {
+    let length = i32::read(reader)?;
+    let mut ints = vec![0; length.max(0) as usize];
+    reader
+        .read_i32_into::<BigEndian>(&mut ints)
+        .map_err(NBTError::IO)?;
+    Ok(ints)
+}
+
+fn read_long_array<R: Read>(reader: &mut R) -> Result<Vec<i64>, NBTError> {
+    let length = i32::read(reader)?;
+    let mut longs = vec![0; length.max(0) as usize];
+    reader
+        .read_i64_into::<BigEndian>(&mut longs)
+        .map_err(NBTError::IO)?;
+    Ok(longs)
+}
+
+fn read_string<R: Read>(reader: &mut R) -> Result<String, NBTError> {
+    let length = i16::read(reader)? as u16;
+    let mut bytes = Vec::with_capacity(length as usize);
+    reader.take(length as u64).read_to_end(&mut bytes)?;
+    String::from_utf8(bytes).map_err(NBTError::NotAString)
+}
+
+fn read_compound<R: Read>(reader: &mut R) -> Result<Vec<Value>, NBTError> {
+    BinaryCompoundReader::new(reader)?.read_to_end()
+}
+
+fn read_elements<R: Read, T>(
+    reader: &mut R,
+    length: i32,
+    read: impl Fn(&mut R) -> Result<T, NBTError>,
+) -> Result<Vec<T>, NBTError> {
+    let mut list = Vec::with_capacity(length.max(0) as usize);
+    for _ in 0..length {
+        list.push(read(reader)?);
+    }
+    Ok(list)
+}
+
+/// Order:
+///   - Tag of the elements, Length, Elements
+impl NBTDataType<Binary> for List {
+    fn read<R: Read>(reader: &mut R) -> Result<Self, NBTError>
+    where
+        Self: Sized,
+    {
+        let tag = Tag::read(reader)?;
+        let length = i32::read(reader)?;
+        let size = length.max(0) as usize;
+        let list = match tag {
+            Tag::End => List::End,
+            Tag::Byte => {
+                let mut bytes = vec![0; size];
+                reader.read_i8_into(&mut bytes)?;
+                List::Byte(bytes)
+            }
+            Tag::Short => {
+                let mut shorts = vec![0; size];
+                reader.read_i16_into::<BigEndian>(&mut shorts)?;
+                List::Short(shorts)
+            }
+            Tag::Int => {
+                let mut ints = vec![0; size];
+                reader.read_i32_into::<BigEndian>(&mut ints)?;
+                List::Int(ints)
+            }
+            Tag::Long => {
+                let mut longs = vec![0; size];
+                reader.read_i64_into::<BigEndian>(&mut longs)?;
+                List::Long(longs)
+            }
+            Tag::Float => {
+                let mut floats = vec![0.0; size];
+                reader.read_f32_into::<BigEndian>(&mut floats)?;
+                List::Float(floats)
+            }
+            Tag::Double => {
+                let mut doubles = vec![0.0; size];
+                reader.read_f64_into::<BigEndian>(&mut doubles)?;
+                List::Double(doubles)
+            }
+            Tag::ByteArray => List::ByteArray(read_elements(reader, length, read_byte_array)?),
+            Tag::String => List::String(read_elements(reader, length, read_string)?),
+            Tag::List => List::List(read_elements(reader, length, List::read)?),
+            Tag::Compound => List::Compound(read_elements(reader, length, read_compound)?),
+            Tag::IntArray => List::IntArray(read_elements(reader, length, read_int_array)?),
+            Tag::LongArray => List::LongArray(read_elements(reader, length, read_long_array)?),
+        };
+        Ok(list)
+    }
+
+    fn write<W: Write, Name: AsRef<[u8]>>(
+        self,
+        name: Name,
+        writer: &mut W,
+    ) -> Result<(), NBTError> {
+        BinaryListWriter::new(writer, self.len() as i32, ListType::List(self.tag()), name)?
+            .write_elements(self)
+    }
+
+    fn write_alone<W: Write>(self, writer: &mut W) -> Result<(), NBTError> {
+        BinaryListWriter::new_sub_sequence(writer, self.len() as i32, ListType::List(self.tag()))?
+            .write_elements(self)
+    }
+
+    fn get_tag() -> Tag {
+        Tag::List
+    }
 }
@@ -15,4 +15,34 @@ pub enum NBTError {
     UnexpectedEnd,
     #[error("Invalid NBT data")]
     KeyMustBeString,
+    #[error("A list of {0:?} can not contain {1:?}")]
+    ListTypeMismatch(Tag, Tag),
+    #[error("Invalid NBT path {0:?}")]
+    InvalidPath(String),
+    #[error("Index {0} is out of bounds for a length of {1}")]
+    IndexOutOfBounds(usize, usize),
+    #[error("No value at {0}")]
+    PathNotFound(String),
+    #[error("Test failed at {0}")]
+    TestFailed(String),
+    #[error("Missing field {0}")]
+    MissingField(String),
+    #[error("Unknown field {0}")]
+    UnknownField(String),
+    #[error("Unknown variant {0}")]
+    UnknownVariant(String),
+    #[error("Data version {0} is newer than {1}")]
+    FutureDataVersion(i32, i32),
+    #[error("Fix {1} for data version {0} failed: {2}")]
+    FixFailed(i32, String, Box<NBTError>),
+    #[error("Invalid schema at {0}: {1}")]
+    InvalidSchema(String, String),
+    #[error("{0} bits per entry is not within 1 to 64")]
+    InvalidBitsPerEntry(u32),
+    #[error("Expected {0} longs of packed data, but got {1}")]
+    PackedLengthMismatch(usize, usize),
+    #[error("{0} does not fit in {1} bits")]
+    ValueTooLarge(u64, u32),
+    #[error("{0} is not supported by this format")]
+    Unsupported(&'static str),
 }
@@ -1,10 +1,17 @@
 #![allow(dead_code)]
+// Lets the derive macros refer to `::axolotl_nbt` inside this crate
+extern crate self as axolotl_nbt;
 
 use std::fmt::Debug;
 use std::io::{Read, Write};
 
 pub mod binary;
 mod error;
+#[cfg(feature = "value")]
+pub mod migrate;
+#[cfg(feature = "minecraft")]
+pub mod minecraft;
+pub mod packed;
 #[cfg(feature = "serde")]
 pub mod serde_impl;
 pub mod snbt;
@@ -16,6 +23,13 @@ use crate::value::{NameLessValue, Value};
 pub use axolotl_nbt_macros;
 pub use error::NBTError;
 
+/// Used by the code the derive macros generate. Not public API
+#[doc(hidden)]
+pub mod __private {
+    #[cfg(feature = "serde")]
+    pub use serde;
+}
+
 #[repr(i8)]
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
 pub enum Tag {
@@ -65,7 +79,6 @@ pub trait NBTType: Debug + Sized {
     /// Will read the tag name and the tag
     fn read_tag_name<R: Read>(reader: &mut R) -> Result<String, NBTError>;
     /// Will read the tag name and the tag
-
     fn read_tag_name_raw<R: Read>(reader: &mut R, value: &mut Vec<u8>) -> Result<(), NBTError>;
 
     /// Will Write the tag name then any separator
@@ -107,9 +120,33 @@ pub trait NBTDataType<Type: NBTType>: Debug {
 
     /// Gets the tag for this type
     fn get_tag() -> Tag;
+
+    /// Set by types that hold a value of any tag, like [value::NameLessValue].
+    /// A compound reads them whatever the tag of the key is
+    const ANY_TAG: bool = false;
+
+    /// Reads the value of the key `name` of a compound. `tag` is the tag of the key
+    fn read_entry<R: Read>(_name: &str, _tag: Tag, reader: &mut R) -> Result<Self, NBTError>
+    where
+        Self: Sized,
+    {
+        Self::read(reader)
+    }
+
+    /// Writes the value as the key `name` of a compound
+    fn write_entry<W: Write, Name: AsRef<[u8]>>(
+        self,
+        name: Name,
+        writer: &mut W,
+    ) -> Result<(), NBTError>
+    where
+        Self: Sized,
+    {
+        self.write(name, writer)
+    }
 }
 
-pub trait ListReader<'reader, Type: NBTType + ?Sized, Reader: Read + 'reader> {
+pub trait ListReader<'reader, Type: NBTType, Reader: Read + 'reader> {
     fn new(reader: &'reader mut Reader, list_type: ListType) -> Result<Self, NBTError>
     where
         Self: Sized;
@@ -143,6 +180,11 @@ pub trait CompoundReader<'reader, Type: NBTType, Reader: Read + 'reader> {
         &mut self,
     ) -> Result<(String, DataType), NBTError>;
 
+    /// Skips the value of the next tag. Used to ignore unknown keys
+    fn skip_next_tag_value(&mut self) -> Result<(), NBTError> {
+        Err(NBTError::Unsupported("Skipping a value"))
+    }
+
     #[cfg(feature = "value")]
     fn read_to_end(self) -> Result<Vec<Value>, NBTError>;
 
@@ -0,0 +1,290 @@
+//! DataFixer style migrations of [Value] trees.
+//!
+//! Minecraft stores the `DataVersion` of the game that wrote the data. A [Fix] is registered for
+//! the DataVersion that changed the layout and the [Migrator] applies every fix between the
+//! version of the data and the target version, oldest first.
+//! ```rust ignore
+//! let migrator = Migrator::new()
+//!     .register(Fix::rename(1451, "Riding".parse()?, "RootVehicle".parse()?))
+//!     .register(Fix::rename_ids(1451, "Flattening", [("minecraft:grass", "minecraft:grass_block")]));
+//! migrator.migrate(&mut player, 3465)?;
+//! ```
+use crate::value::{List, NBTPath, NameLessValue, Operation, Patch, Value, ValueRef};
+use crate::NBTError;
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+
+/// The key Minecraft stores the version under
+pub const DATA_VERSION: &str = "DataVersion";
+
+/// A structure tied to a DataVersion. Implemented by `#[nbt(data_version = N)]`
+pub trait Versioned {
+    /// The DataVersion the layout of the structure matches
+    const DATA_VERSION: i32;
+}
+
+type FixFn = dyn Fn(&mut Value) -> Result<(), NBTError> + Send + Sync;
+
+/// Brings data written before `version` to the layout of `version`
+pub struct Fix {
+    pub version: i32,
+    /// Shown when the fix fails
+    pub name: String,
+    apply: Box<FixFn>,
+}
+
+impl Debug for Fix {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Fix")
+            .field("version", &self.version)
+            .field("name", &self.name)
+            .finish()
+    }
+}
+
+impl Fix {
+    pub fn new(
+        version: i32,
+        name: impl Into<String>,
+        apply: impl Fn(&mut Value) -> Result<(), NBTError> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            version,
+            name: name.into(),
+            apply: Box::new(apply),
+        }
+    }
+
+    /// Applies the patch. Fails if any operation fails
+    pub fn patch(version: i32, name: impl Into<String>, patch: Patch) -> Self {
+        Self::new(version, name, move |value| value.apply_in_place(&patch))
+    }
+
+    /// Moves the value at `from` to `to`. Does nothing if there is no value at `from`
+    pub fn rename(version: i32, from: NBTPath, to: NBTPath) -> Self {
+        let name = format!("rename {} to {}", from, to);
+        Self::new(version, name, move |value| {
+            if value.get_path(&from).is_none() {
+                return Ok(());
+            }
+            value.apply_in_place(&Patch::new().push(Operation::Move {
+                from: from.clone(),
+                path: to.clone(),
+            }))
+        })
+    }
+
+    /// Replaces every string named `id` anywhere in the tree. Used for item, block and entity ids
+    pub fn rename_ids<'a>(
+        version: i32,
+        name: impl Into<String>,
+        ids: impl IntoIterator<Item = (&'a str, &'a str)>,
+    ) -> Self {
+        let ids: HashMap<String, String> = ids
+            .into_iter()
+            .map(|(old, new)| (old.to_string(), new.to_string()))
+            .collect();
+        Self::new(version, name, move |value| {
+            rename_ids(value, &ids);
+            Ok(())
+        })
+    }
+
+    /// A failed fix can leave the value partly changed. [Migrator::migrate] works on a copy
+    pub fn apply(&self, value: &mut Value) -> Result<(), NBTError> {
+        (self.apply)(value)
+    }
+}
+
+fn rename_ids(value: &mut Value, ids: &HashMap<String, String>) {
+    match value {
+        Value::String { name, value } if name == "id" => {
+            if let Some(new) = ids.get(value.as_str()) {
+                *value = new.clone();
+            }
+        }
+        Value::Compound { value, .. } => {
+            for entry in value {
+                rename_ids(entry, ids);
+            }
+        }
+        Value::List { value, .. } => rename_ids_in_list(value, ids),
+        _ => {}
+    }
+}
+
+fn rename_ids_in_list(list: &mut List, ids: &HashMap<String, String>) {
+    match list {
+        List::Compound(compounds) => {
+            for entry in compounds.iter_mut().flatten() {
+                rename_ids(entry, ids);
+            }
+        }
+        List::List(lists) => {
+            for list in lists {
+                rename_ids_in_list(list, ids);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// An ordered set of fixes
+#[derive(Debug)]
+pub struct Migrator {
+    fixes: Vec<Fix>,
+    version_path: NBTPath,
+}
+
+impl Default for Migrator {
+    fn default() -> Self {
+        Self {
+            fixes: Vec::new(),
+            version_path: NBTPath::root().key(DATA_VERSION),
+        }
+    }
+}
+
+impl Migrator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fixes are kept ordered by version. Fixes of the same version run in the order they were registered
+    pub fn register(mut self, fix: Fix) -> Self {
+        let index = self.fixes.partition_point(|f| f.version <= fix.version);
+        self.fixes.insert(index, fix);
+        self
+    }
+
+    /// Where the DataVersion is stored. `DataVersion` by default, `Data.DataVersion` in level.dat
+    pub fn version_path(mut self, path: NBTPath) -> Self {
+        self.version_path = path;
+        self
+    }
+
+    pub fn fixes(&self) -> &[Fix] {
+        &self.fixes
+    }
+
+    /// The DataVersion of the value. Data written before DataVersion existed is version 0
+    pub fn data_version(&self, value: &Value) -> Result<i32, NBTError> {
+        match value.get_path(&self.version_path) {
+            None => Ok(0),
+            Some(ValueRef::Int(version)) => Ok(version),
+            Some(other) => Err(NBTError::ExpectedTag(crate::Tag::Int, other.tag())),
+        }
+    }
+
+    /// Applies every fix newer than the DataVersion of the value up to `target` and stores `target` as the DataVersion.
+    ///
+    /// Returns the version the value had. If a fix fails the value is left unchanged
+    pub fn migrate(&self, value: &mut Value, target: i32) -> Result<i32, NBTError> {
+        let from = self.data_version(value)?;
+        if from > target {
+            return Err(NBTError::FutureDataVersion(from, target));
+        }
+        let mut migrated = value.clone();
+        for fix in self
+            .fixes
+            .iter()
+            .filter(|fix| fix.version > from && fix.version <= target)
+        {
+            fix.apply(&mut migrated).map_err(|error| {
+                NBTError::FixFailed(fix.version, fix.name.clone(), Box::new(error))
+            })?;
+        }
+        migrated.apply_in_place(&Patch::new().push(Operation::Add {
+            path: self.version_path.clone(),
+            value: NameLessValue::Int(target),
+        }))?;
+        *value = migrated;
+        Ok(from)
+    }
+
+    /// Migrates the value to the DataVersion of `T`
+    pub fn migrate_to<T: Versioned>(&self, value: &mut Value) -> Result<i32, NBTError> {
+        self.migrate(value, T::DATA_VERSION)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Fix, Migrator, DATA_VERSION};
+    use crate::value::{List, NBTPath, NameLessValue, Value, ValueRef};
+    use crate::NBTError;
+
+    fn item(id: &str) -> Vec<Value> {
+        vec![NameLessValue::String(id.to_string()).into_named("id")]
+    }
+
+    fn player(version: Option<i32>) -> Value {
+        let mut entries = vec![
+            NameLessValue::List(List::Compound(vec![item("minecraft:grass")]))
+                .into_named("Inventory"),
+            NameLessValue::Compound(item("minecraft:pig")).into_named("Riding"),
+        ];
+        if let Some(version) = version {
+            entries.push(NameLessValue::Int(version).into_named(DATA_VERSION));
+        }
+        NameLessValue::Compound(entries).into_named("")
+    }
+
+    fn migrator() -> Migrator {
+        Migrator::new()
+            .register(Fix::rename_ids(
+                1451,
+                "The Flattening",
+                [("minecraft:grass", "minecraft:grass_block")],
+            ))
+            .register(Fix::rename(
+                1000,
+                "Riding".parse().unwrap(),
+                "RootVehicle".parse().unwrap(),
+            ))
+            .register(Fix::new(2000, "fail", |_| {
+                Err(NBTError::PathNotFound("Fail".to_string()))
+            }))
+    }
+
+    #[test]
+    fn migrates_in_order() {
+        let migrator = migrator();
+        let versions: Vec<i32> = migrator.fixes().iter().map(|fix| fix.version).collect();
+        assert_eq!(versions, [1000, 1451, 2000]);
+
+        let mut value = player(None);
+        assert_eq!(migrator.migrate(&mut value, 1500).unwrap(), 0);
+        let get = |path: &str| value.get_path(&path.parse::<NBTPath>().unwrap());
+        assert_eq!(
+            get("Inventory[0].id"),
+            Some(ValueRef::String("minecraft:grass_block"))
+        );
+        assert_eq!(
+            get("RootVehicle.id"),
+            Some(ValueRef::String("minecraft:pig"))
+        );
+        assert_eq!(get("Riding"), None);
+        assert_eq!(get("DataVersion"), Some(ValueRef::Int(1500)));
+
+        // Only the fixes after 1451 run
+        let mut value = player(Some(1451));
+        migrator.migrate(&mut value, 1500).unwrap();
+        assert_eq!(
+            value.get_path(&"Inventory[0].id".parse().unwrap()),
+            Some(ValueRef::String("minecraft:grass"))
+        );
+    }
+
+    #[test]
+    fn failures() {
+        let migrator = migrator();
+        let mut value = player(Some(1500));
+        let error = migrator.migrate(&mut value, 2500).unwrap_err();
+        assert!(matches!(error, NBTError::FixFailed(2000, ref name, _) if name == "fail"));
+        assert_eq!(value, player(Some(1500)));
+
+        let error = migrator.migrate(&mut value, 1000).unwrap_err();
+        assert!(matches!(error, NBTError::FutureDataVersion(1500, 1000)));
+    }
+}
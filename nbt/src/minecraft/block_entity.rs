@@ -0,0 +1,219 @@
+use crate::minecraft::item::ItemStack;
+use crate::minecraft::Extra;
+use crate::serde_impl::from_nameless_value;
+use crate::value::NameLessValue;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// A block entity of a chunk or a structure. Ids without a variant are [BlockEntity::Other]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum BlockEntity {
+    Known(KnownBlockEntity),
+    Other(OtherBlockEntity),
+}
+
+impl BlockEntity {
+    pub fn id(&self) -> &str {
+        match self {
+            BlockEntity::Known(known) => known.id(),
+            BlockEntity::Other(other) => &other.id,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "id")]
+pub enum KnownBlockEntity {
+    #[serde(rename = "minecraft:chest")]
+    Chest(Container),
+    #[serde(rename = "minecraft:trapped_chest")]
+    TrappedChest(Container),
+    #[serde(rename = "minecraft:barrel")]
+    Barrel(Container),
+    #[serde(rename = "minecraft:shulker_box")]
+    ShulkerBox(Container),
+    #[serde(rename = "minecraft:dispenser")]
+    Dispenser(Container),
+    #[serde(rename = "minecraft:dropper")]
+    Dropper(Container),
+    #[serde(rename = "minecraft:hopper")]
+    Hopper(Container),
+    #[serde(rename = "minecraft:furnace")]
+    Furnace(Furnace),
+    #[serde(rename = "minecraft:blast_furnace")]
+    BlastFurnace(Furnace),
+    #[serde(rename = "minecraft:smoker")]
+    Smoker(Furnace),
+    #[serde(rename = "minecraft:sign")]
+    Sign(Sign),
+    #[serde(rename = "minecraft:hanging_sign")]
+    HangingSign(Sign),
+}
+
+/// Not untagged, serde would buffer the block entity
+impl<'de> Deserialize<'de> for BlockEntity {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = NameLessValue::deserialize(deserializer)?;
+        match from_nameless_value(value.clone()) {
+            Ok(known) => Ok(BlockEntity::Known(known)),
+            Err(_) => from_nameless_value(value)
+                .map(BlockEntity::Other)
+                .map_err(D::Error::custom),
+        }
+    }
+}
+
+/// Not internally tagged, serde would buffer the block entity. The `id` is taken out of the compound
+/// and the rest is read as the variant
+impl<'de> Deserialize<'de> for KnownBlockEntity {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let NameLessValue::Compound(mut entries) = NameLessValue::deserialize(deserializer)? else {
+            return Err(D::Error::custom("Expected a compound"));
+        };
+        let Some(index) = entries.iter().position(|entry| entry.get_name() == "id") else {
+            return Err(D::Error::missing_field("id"));
+        };
+        let id = match entries.remove(index).into_nameless() {
+            NameLessValue::String(id) => id,
+            other => {
+                return Err(D::Error::custom(format!(
+                    "Expected a string id, found {}",
+                    other
+                )))
+            }
+        };
+        let value = NameLessValue::Compound(entries);
+        match id.as_str() {
+            "minecraft:chest" => from_nameless_value(value).map(KnownBlockEntity::Chest),
+            "minecraft:trapped_chest" => {
+                from_nameless_value(value).map(KnownBlockEntity::TrappedChest)
+            }
+            "minecraft:barrel" => from_nameless_value(value).map(KnownBlockEntity::Barrel),
+            "minecraft:shulker_box" => from_nameless_value(value).map(KnownBlockEntity::ShulkerBox),
+            "minecraft:dispenser" => from_nameless_value(value).map(KnownBlockEntity::Dispenser),
+            "minecraft:dropper" => from_nameless_value(value).map(KnownBlockEntity::Dropper),
+            "minecraft:hopper" => from_nameless_value(value).map(KnownBlockEntity::Hopper),
+            "minecraft:furnace" => from_nameless_value(value).map(KnownBlockEntity::Furnace),
+            "minecraft:blast_furnace" => {
+                from_nameless_value(value).map(KnownBlockEntity::BlastFurnace)
+            }
+            "minecraft:smoker" => from_nameless_value(value).map(KnownBlockEntity::Smoker),
+            "minecraft:sign" => from_nameless_value(value).map(KnownBlockEntity::Sign),
+            "minecraft:hanging_sign" => {
+                from_nameless_value(value).map(KnownBlockEntity::HangingSign)
+            }
+            _ => return Err(D::Error::custom(format!("Unknown block entity {}", id))),
+        }
+        .map_err(D::Error::custom)
+    }
+}
+
+impl KnownBlockEntity {
+    pub fn id(&self) -> &'static str {
+        match self {
+            KnownBlockEntity::Chest(_) => "minecraft:chest",
+            KnownBlockEntity::TrappedChest(_) => "minecraft:trapped_chest",
+            KnownBlockEntity::Barrel(_) => "minecraft:barrel",
+            KnownBlockEntity::ShulkerBox(_) => "minecraft:shulker_box",
+            KnownBlockEntity::Dispenser(_) => "minecraft:dispenser",
+            KnownBlockEntity::Dropper(_) => "minecraft:dropper",
+            KnownBlockEntity::Hopper(_) => "minecraft:hopper",
+            KnownBlockEntity::Furnace(_) => "minecraft:furnace",
+            KnownBlockEntity::BlastFurnace(_) => "minecraft:blast_furnace",
+            KnownBlockEntity::Smoker(_) => "minecraft:smoker",
+            KnownBlockEntity::Sign(_) => "minecraft:sign",
+            KnownBlockEntity::HangingSign(_) => "minecraft:hanging_sign",
+        }
+    }
+}
+
+/// The position of a block entity in a chunk. Missing in structures
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct Position {
+    pub x: Option<i32>,
+    pub y: Option<i32>,
+    pub z: Option<i32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(remote = "Self")]
+pub struct OtherBlockEntity {
+    pub id: String,
+    #[serde(flatten)]
+    pub position: Position,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+keep_extra!(OtherBlockEntity);
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(remote = "Self")]
+pub struct Container {
+    #[serde(flatten)]
+    pub position: Position,
+    /// A JSON string before 1.21.5 and a text component after
+    #[serde(rename = "CustomName")]
+    pub custom_name: Option<NameLessValue>,
+    #[serde(rename = "Items")]
+    pub items: Option<Vec<ItemStack>>,
+    #[serde(rename = "LootTable")]
+    pub loot_table: Option<String>,
+    #[serde(rename = "LootTableSeed")]
+    pub loot_table_seed: Option<i64>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+keep_extra!(Container);
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(remote = "Self")]
+pub struct Furnace {
+    #[serde(flatten)]
+    pub position: Position,
+    #[serde(rename = "Items")]
+    pub items: Option<Vec<ItemStack>>,
+    #[serde(rename = "BurnTime")]
+    pub burn_time: Option<i16>,
+    #[serde(rename = "CookTime")]
+    pub cook_time: Option<i16>,
+    #[serde(rename = "CookTimeTotal")]
+    pub cook_time_total: Option<i16>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+keep_extra!(Furnace);
+
+/// A sign written since 1.20
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(remote = "Self")]
+pub struct Sign {
+    #[serde(flatten)]
+    pub position: Position,
+    pub front_text: Option<SignText>,
+    pub back_text: Option<SignText>,
+    #[serde(default, with = "crate::minecraft::byte_bool::option")]
+    pub is_waxed: Option<bool>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+keep_extra!(Sign);
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(remote = "Self")]
+pub struct SignText {
+    /// Four text components
+    #[serde(default)]
+    pub messages: Vec<NameLessValue>,
+    pub color: Option<String>,
+    #[serde(default, with = "crate::minecraft::byte_bool::option")]
+    pub has_glowing_text: Option<bool>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+keep_extra!(SignText);
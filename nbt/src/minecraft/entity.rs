@@ -0,0 +1,130 @@
+use crate::binary::binary_uuid::BinaryUUID;
+use crate::minecraft::item::ItemStack;
+use crate::minecraft::{Extra, Rotation, Vec3};
+use crate::serde_impl::value::deserialize::NamelessValueDeserializer;
+use crate::value::NameLessValue;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// The fields every entity has
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(remote = "Self")]
+pub struct Entity {
+    /// Missing for players
+    pub id: Option<String>,
+    /// Removed from the entities of structures
+    #[serde(rename = "UUID")]
+    pub uuid: Option<BinaryUUID>,
+    #[serde(rename = "Pos")]
+    pub pos: Vec3,
+    #[serde(rename = "Motion")]
+    pub motion: Vec3,
+    #[serde(rename = "Rotation")]
+    pub rotation: Rotation,
+    #[serde(rename = "FallDistance")]
+    pub fall_distance: Option<f32>,
+    #[serde(rename = "Fire")]
+    pub fire: Option<i16>,
+    #[serde(rename = "Air")]
+    pub air: Option<i16>,
+    #[serde(
+        rename = "OnGround",
+        default,
+        with = "crate::minecraft::byte_bool::option"
+    )]
+    pub on_ground: Option<bool>,
+    #[serde(
+        rename = "Invulnerable",
+        default,
+        with = "crate::minecraft::byte_bool::option"
+    )]
+    pub invulnerable: Option<bool>,
+    #[serde(rename = "PortalCooldown")]
+    pub portal_cooldown: Option<i32>,
+    /// A JSON string before 1.21.5 and a text component after
+    #[serde(rename = "CustomName")]
+    pub custom_name: Option<NameLessValue>,
+    #[serde(rename = "Tags")]
+    pub tags: Option<Vec<String>>,
+    #[serde(rename = "Passengers")]
+    pub passengers: Option<Vec<Entity>>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+keep_extra!(Entity);
+
+/// The contents of `playerdata/<uuid>.dat` and the `Player` of a single player level.dat
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(remote = "Self")]
+pub struct Player {
+    #[serde(rename = "DataVersion")]
+    pub data_version: Option<i32>,
+    #[serde(rename = "Health")]
+    pub health: f32,
+    #[serde(rename = "foodLevel")]
+    pub food_level: i32,
+    #[serde(rename = "XpLevel")]
+    pub xp_level: i32,
+    #[serde(rename = "XpP")]
+    pub xp_progress: f32,
+    #[serde(rename = "XpTotal")]
+    pub xp_total: i32,
+    #[serde(rename = "playerGameType")]
+    pub game_type: i32,
+    #[serde(rename = "SelectedItemSlot")]
+    pub selected_item_slot: Option<i32>,
+    #[serde(rename = "Inventory", default)]
+    pub inventory: Vec<ItemStack>,
+    #[serde(rename = "EnderItems")]
+    pub ender_items: Option<Vec<ItemStack>>,
+    pub abilities: Option<Abilities>,
+    #[serde(flatten)]
+    pub entity: Entity,
+}
+
+impl Serialize for Player {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Player::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Player {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = NameLessValue::deserialize(deserializer)?;
+        let mut player = Player::deserialize(NamelessValueDeserializer(value.clone()))
+            .map_err(D::Error::custom)?;
+        // serde buffers `entity`. It is read again from the compound, the keys of the player
+        // are the ones missing from the buffered `extra`
+        let mut entity =
+            crate::serde_impl::from_nameless_value::<Entity>(value).map_err(D::Error::custom)?;
+        entity
+            .extra
+            .retain(|key, _| player.entity.extra.contains_key(key));
+        player.entity = entity;
+        Ok(player)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(remote = "Self")]
+pub struct Abilities {
+    #[serde(with = "crate::minecraft::byte_bool")]
+    pub flying: bool,
+    #[serde(with = "crate::minecraft::byte_bool")]
+    pub mayfly: bool,
+    #[serde(with = "crate::minecraft::byte_bool")]
+    pub instabuild: bool,
+    #[serde(with = "crate::minecraft::byte_bool")]
+    pub invulnerable: bool,
+    #[serde(rename = "mayBuild", with = "crate::minecraft::byte_bool")]
+    pub may_build: bool,
+    #[serde(rename = "flySpeed")]
+    pub fly_speed: f32,
+    #[serde(rename = "walkSpeed")]
+    pub walk_speed: f32,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+keep_extra!(Abilities);
@@ -0,0 +1,156 @@
+//! Item stacks. 1.20.5 replaced the `tag` compound with data components
+use crate::minecraft::Extra;
+use crate::serde_impl::from_nameless_value;
+use crate::value::NameLessValue;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum ItemStack {
+    /// Written before 1.20.5. Told apart by `Count`
+    Legacy(LegacyItemStack),
+    Components(ComponentItemStack),
+}
+
+/// Not untagged, serde would buffer the item. The form is picked from the compound instead
+impl<'de> Deserialize<'de> for ItemStack {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = NameLessValue::deserialize(deserializer)?;
+        let legacy = match &value {
+            NameLessValue::Compound(entries) => {
+                entries.iter().any(|entry| entry.get_name() == "Count")
+            }
+            _ => false,
+        };
+        if legacy {
+            from_nameless_value(value).map(ItemStack::Legacy)
+        } else {
+            from_nameless_value(value).map(ItemStack::Components)
+        }
+        .map_err(D::Error::custom)
+    }
+}
+
+impl ItemStack {
+    pub fn id(&self) -> &str {
+        match self {
+            ItemStack::Legacy(item) => &item.id,
+            ItemStack::Components(item) => &item.id,
+        }
+    }
+
+    /// A missing count is one
+    pub fn count(&self) -> i32 {
+        match self {
+            ItemStack::Legacy(item) => item.count as i32,
+            ItemStack::Components(item) => item.count.unwrap_or(1),
+        }
+    }
+
+    /// The inventory slot. Only set in inventories
+    pub fn slot(&self) -> Option<i8> {
+        match self {
+            ItemStack::Legacy(item) => item.slot,
+            ItemStack::Components(item) => item.slot,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(remote = "Self")]
+pub struct LegacyItemStack {
+    pub id: String,
+    #[serde(rename = "Count")]
+    pub count: i8,
+    #[serde(rename = "Slot")]
+    pub slot: Option<i8>,
+    pub tag: Option<ItemTag>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+keep_extra!(LegacyItemStack);
+
+/// The `tag` of an item written before 1.20.5
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(remote = "Self")]
+pub struct ItemTag {
+    #[serde(rename = "Damage")]
+    pub damage: Option<i32>,
+    #[serde(
+        rename = "Unbreakable",
+        default,
+        with = "crate::minecraft::byte_bool::option"
+    )]
+    pub unbreakable: Option<bool>,
+    #[serde(rename = "RepairCost")]
+    pub repair_cost: Option<i32>,
+    pub display: Option<Display>,
+    #[serde(rename = "Enchantments")]
+    pub enchantments: Option<Vec<Enchantment>>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+keep_extra!(ItemTag);
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(remote = "Self")]
+pub struct Display {
+    /// A JSON text component
+    #[serde(rename = "Name")]
+    pub name: Option<String>,
+    /// JSON text components
+    #[serde(rename = "Lore")]
+    pub lore: Option<Vec<String>>,
+    pub color: Option<i32>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+keep_extra!(Display);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(remote = "Self")]
+pub struct Enchantment {
+    pub id: String,
+    pub lvl: i16,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+keep_extra!(Enchantment);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(remote = "Self")]
+pub struct ComponentItemStack {
+    pub id: String,
+    pub count: Option<i32>,
+    #[serde(rename = "Slot")]
+    pub slot: Option<i8>,
+    pub components: Option<Components>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+keep_extra!(ComponentItemStack);
+
+/// The data components of an item written since 1.20.5
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(remote = "Self")]
+pub struct Components {
+    #[serde(rename = "minecraft:damage")]
+    pub damage: Option<i32>,
+    #[serde(rename = "minecraft:max_damage")]
+    pub max_damage: Option<i32>,
+    #[serde(rename = "minecraft:repair_cost")]
+    pub repair_cost: Option<i32>,
+    /// A JSON string before 1.21.5 and a text component after
+    #[serde(rename = "minecraft:custom_name")]
+    pub custom_name: Option<NameLessValue>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+keep_extra!(Components);
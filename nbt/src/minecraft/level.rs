@@ -0,0 +1,91 @@
+use crate::minecraft::entity::Player;
+use crate::minecraft::Extra;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// The contents of `level.dat`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(remote = "Self")]
+pub struct LevelDat {
+    #[serde(rename = "Data")]
+    pub data: LevelData,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+keep_extra!(LevelDat);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(remote = "Self")]
+pub struct LevelData {
+    #[serde(rename = "DataVersion")]
+    pub data_version: Option<i32>,
+    #[serde(rename = "LevelName")]
+    pub level_name: String,
+    /// The version of the format. 19133 since 1.9
+    pub version: Option<i32>,
+    #[serde(rename = "Version")]
+    pub game_version: Option<GameVersion>,
+    #[serde(rename = "GameType")]
+    pub game_type: i32,
+    #[serde(rename = "Difficulty")]
+    pub difficulty: Option<i8>,
+    #[serde(default, with = "crate::minecraft::byte_bool::option")]
+    pub hardcore: Option<bool>,
+    #[serde(
+        rename = "allowCommands",
+        default,
+        with = "crate::minecraft::byte_bool::option"
+    )]
+    pub allow_commands: Option<bool>,
+    #[serde(rename = "SpawnX")]
+    pub spawn_x: Option<i32>,
+    #[serde(rename = "SpawnY")]
+    pub spawn_y: Option<i32>,
+    #[serde(rename = "SpawnZ")]
+    pub spawn_z: Option<i32>,
+    #[serde(rename = "Time")]
+    pub time: i64,
+    #[serde(rename = "DayTime")]
+    pub day_time: Option<i64>,
+    #[serde(rename = "LastPlayed")]
+    pub last_played: i64,
+    /// Moved to `WorldGenSettings.seed` in 1.16
+    #[serde(rename = "RandomSeed")]
+    pub random_seed: Option<i64>,
+    #[serde(default, with = "crate::minecraft::byte_bool::option")]
+    pub raining: Option<bool>,
+    #[serde(rename = "rainTime")]
+    pub rain_time: Option<i32>,
+    #[serde(default, with = "crate::minecraft::byte_bool::option")]
+    pub thundering: Option<bool>,
+    #[serde(rename = "thunderTime")]
+    pub thunder_time: Option<i32>,
+    /// Every rule is stored as a string
+    #[serde(rename = "GameRules")]
+    pub game_rules: Option<BTreeMap<String, String>>,
+    /// The player of a single player world
+    #[serde(rename = "Player")]
+    pub player: Option<Player>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+keep_extra!(LevelData);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(remote = "Self")]
+pub struct GameVersion {
+    #[serde(rename = "Id")]
+    pub id: i32,
+    #[serde(rename = "Name")]
+    pub name: String,
+    #[serde(rename = "Series")]
+    pub series: Option<String>,
+    #[serde(rename = "Snapshot", with = "crate::minecraft::byte_bool")]
+    pub snapshot: bool,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+keep_extra!(GameVersion);
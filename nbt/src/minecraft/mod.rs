@@ -0,0 +1,135 @@
+//! Serde structures for common vanilla formats. Enabled by the `minecraft` feature.
+//!
+//! Every compound keeps the keys it does not know in `extra` so data written by other versions
+//! round-trips, with the tag each value was read with
+//! ```rust ignore
+//! let player: Player = serde_impl::from_reader_binary(GzDecoder::new(File::open("player.dat")?))?;
+//! println!("{:?} at {:?}", player.entity.uuid, player.entity.pos);
+//! ```
+use crate::serde_impl::value::deserialize::NamelessValueDeserializer;
+use crate::serde_impl::Located;
+use crate::value::NameLessValue;
+use axolotl_nbt_macros::ListSerialize;
+use std::collections::BTreeMap;
+
+/// Implements `Serialize` and `Deserialize` for a model derived with `#[serde(remote = "Self")]`.
+/// The model is read with [with_extra]
+macro_rules! keep_extra {
+    ($model:ident) => {
+        impl serde::Serialize for $model {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                $model::serialize(self, serializer)
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $model {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let value =
+                    <crate::value::NameLessValue as serde::Deserialize>::deserialize(deserializer)?;
+                crate::minecraft::with_extra(value, $model::deserialize, |model| &mut model.extra)
+                    .map_err(serde::de::Error::custom)
+            }
+        }
+    };
+}
+
+pub mod block_entity;
+pub mod entity;
+pub mod item;
+pub mod level;
+pub mod structure;
+
+pub use block_entity::BlockEntity;
+pub use entity::{Entity, Player};
+pub use item::ItemStack;
+pub use level::LevelDat;
+pub use structure::Structure;
+
+/// The keys of a compound that are not a field
+pub type Extra = BTreeMap<String, NameLessValue>;
+
+/// Reads a model from a compound. serde buffers the keys the model does not know before they
+/// reach `extra`, and the buffer can not tell a list of bytes, ints or longs apart from an array.
+/// So `extra` takes its values from the compound again
+fn with_extra<T>(
+    value: NameLessValue,
+    derived: impl FnOnce(NamelessValueDeserializer) -> Result<T, Located>,
+    extra: impl FnOnce(&mut T) -> &mut Extra,
+) -> Result<T, Located> {
+    let mut model = derived(NamelessValueDeserializer(value.clone()))?;
+    let extra = extra(&mut model);
+    if let NameLessValue::Compound(entries) = value {
+        for entry in entries {
+            if let Some(kept) = extra.get_mut(entry.get_name()) {
+                *kept = entry.into_nameless();
+            }
+        }
+    }
+    Ok(model)
+}
+
+/// A list of three doubles. Such as `Pos` and `Motion`
+#[derive(Debug, Clone, Copy, PartialEq, Default, ListSerialize)]
+pub struct Vec3 {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+/// A list of two floats
+#[derive(Debug, Clone, Copy, PartialEq, Default, ListSerialize)]
+pub struct Rotation {
+    pub yaw: f32,
+    pub pitch: f32,
+}
+
+/// A list of three ints
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, ListSerialize)]
+#[list(encoding = "list")]
+pub struct BlockPos {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+}
+
+/// A boolean stored as a TAG_Byte.
+///
+/// serde can not read a bool from a byte once it has been buffered by `flatten` or an enum
+pub(crate) mod byte_bool {
+    use crate::value::NameLessValue;
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &bool, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i8(i8::from(*value))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<bool, D::Error> {
+        let value = NameLessValue::deserialize(deserializer)?;
+        value
+            .as_bool()
+            .ok_or_else(|| D::Error::custom(format!("Expected a boolean, found {}", value)))
+    }
+
+    pub mod option {
+        use serde::{Deserialize, Deserializer, Serializer};
+
+        pub fn serialize<S: Serializer>(
+            value: &Option<bool>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            match value {
+                Some(value) => super::serialize(value, serializer),
+                None => serializer.serialize_none(),
+            }
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Option<bool>, D::Error> {
+            #[derive(Deserialize)]
+            struct Wrapper(#[serde(with = "super")] bool);
+            Ok(Option::<Wrapper>::deserialize(deserializer)?.map(|Wrapper(value)| value))
+        }
+    }
+}
@@ -0,0 +1,65 @@
+//! Structure templates as saved by structure blocks
+use crate::minecraft::block_entity::BlockEntity;
+use crate::minecraft::entity::Entity;
+use crate::minecraft::{BlockPos, Extra, Vec3};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(remote = "Self")]
+pub struct Structure {
+    #[serde(rename = "DataVersion")]
+    pub data_version: i32,
+    pub size: BlockPos,
+    /// Set unless the structure has several palettes
+    pub palette: Option<Vec<BlockState>>,
+    /// Used by structures such as shipwrecks that pick one of several palettes
+    pub palettes: Option<Vec<Vec<BlockState>>>,
+    #[serde(default)]
+    pub blocks: Vec<StructureBlock>,
+    #[serde(default)]
+    pub entities: Vec<StructureEntity>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+keep_extra!(Structure);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(remote = "Self")]
+pub struct BlockState {
+    #[serde(rename = "Name")]
+    pub name: String,
+    #[serde(rename = "Properties")]
+    pub properties: Option<BTreeMap<String, String>>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+keep_extra!(BlockState);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(remote = "Self")]
+pub struct StructureBlock {
+    /// The index into the palette
+    pub state: i32,
+    pub pos: BlockPos,
+    pub nbt: Option<BlockEntity>,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+keep_extra!(StructureBlock);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(remote = "Self")]
+pub struct StructureEntity {
+    pub pos: Vec3,
+    #[serde(rename = "blockPos")]
+    pub block_pos: BlockPos,
+    pub nbt: Entity,
+    #[serde(flatten)]
+    pub extra: Extra,
+}
+
+keep_extra!(StructureEntity);
@@ -0,0 +1,269 @@
+//! Entries of a fixed number of bits packed into longs.
+//!
+//! Used by the `block_states` and `biomes` of chunk sections and by heightmaps
+//! ```rust ignore
+//! let states = PackedArray::new(&data[..], 5, 4096, Layout::for_data_version(data_version))?;
+//! let block = &palette[states.get(index).unwrap() as usize];
+//! ```
+use crate::NBTError;
+
+/// How entries are laid out in the longs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Layout {
+    /// Before 1.16. An entry can start in one long and end in the next
+    Spanning,
+    /// Since 1.16. Entries never cross a long. The unused high bits of each long are padding
+    Padded,
+}
+
+impl Layout {
+    /// The DataVersion of 20w17a. The first version that pads the longs
+    pub const PADDED_SINCE: i32 = 2529;
+
+    pub fn for_data_version(data_version: i32) -> Self {
+        if data_version >= Self::PADDED_SINCE {
+            Layout::Padded
+        } else {
+            Layout::Spanning
+        }
+    }
+
+    /// The number of longs `len` entries of `bits` need
+    pub fn longs_needed(self, bits: u32, len: usize) -> usize {
+        match self {
+            Layout::Spanning => (len * bits as usize).div_ceil(64),
+            Layout::Padded => len.div_ceil((64 / bits) as usize),
+        }
+    }
+}
+
+/// The number of bits needed to store `max`. At least one.
+///
+/// Minecraft uses at least 4 bits for block states
+pub fn bits_needed(max: u64) -> u32 {
+    (u64::BITS - max.leading_zeros()).max(1)
+}
+
+/// A view of packed entries. `S` is usually the `Vec<i64>` or `&[i64]` of a LongArray
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackedArray<S> {
+    data: S,
+    bits: u32,
+    len: usize,
+    layout: Layout,
+}
+
+impl<S: AsRef<[i64]>> PackedArray<S> {
+    /// Fails if `bits` is not within 1 to 64 or `data` does not have the number of longs `len` entries need
+    pub fn new(data: S, bits: u32, len: usize, layout: Layout) -> Result<Self, NBTError> {
+        if !(1..=64).contains(&bits) {
+            return Err(NBTError::InvalidBitsPerEntry(bits));
+        }
+        let needed = layout.longs_needed(bits, len);
+        if data.as_ref().len() != needed {
+            return Err(NBTError::PackedLengthMismatch(needed, data.as_ref().len()));
+        }
+        Ok(Self {
+            data,
+            bits,
+            len,
+            layout,
+        })
+    }
+
+    pub fn bits(&self) -> u32 {
+        self.bits
+    }
+    pub fn layout(&self) -> Layout {
+        self.layout
+    }
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    pub fn as_slice(&self) -> &[i64] {
+        self.data.as_ref()
+    }
+    pub fn into_inner(self) -> S {
+        self.data
+    }
+
+    fn mask(&self) -> u64 {
+        u64::MAX >> (64 - self.bits)
+    }
+
+    /// The long and the bit offset of the entry
+    fn position(&self, index: usize) -> (usize, u32) {
+        match self.layout {
+            Layout::Spanning => {
+                let bit = index * self.bits as usize;
+                (bit / 64, (bit % 64) as u32)
+            }
+            Layout::Padded => {
+                let per_long = (64 / self.bits) as usize;
+                (index / per_long, (index % per_long) as u32 * self.bits)
+            }
+        }
+    }
+
+    pub fn get(&self, index: usize) -> Option<u64> {
+        if index >= self.len {
+            return None;
+        }
+        let data = self.data.as_ref();
+        let (long, offset) = self.position(index);
+        let mut value = data[long] as u64 >> offset;
+        if offset + self.bits > 64 {
+            value |= (data[long + 1] as u64) << (64 - offset);
+        }
+        Some(value & self.mask())
+    }
+
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = u64> + '_ {
+        (0..self.len).map(|index| self.get(index).expect("index is within the length"))
+    }
+
+    /// Copies the entries into a new array with a different number of bits or layout
+    pub fn repack(&self, bits: u32, layout: Layout) -> Result<PackedArray<Vec<i64>>, NBTError> {
+        PackedArray::from_values(self.iter(), bits, layout)
+    }
+}
+
+impl<S: AsRef<[i64]> + AsMut<[i64]>> PackedArray<S> {
+    /// Fails if the index is out of bounds or the value does not fit in the bits
+    pub fn set(&mut self, index: usize, value: u64) -> Result<(), NBTError> {
+        if index >= self.len {
+            return Err(NBTError::IndexOutOfBounds(index, self.len));
+        }
+        let mask = self.mask();
+        if value & !mask != 0 {
+            return Err(NBTError::ValueTooLarge(value, self.bits));
+        }
+        let (long, offset) = self.position(index);
+        let bits = self.bits;
+        let data = self.data.as_mut();
+        let first = data[long] as u64 & !(mask << offset) | (value << offset);
+        data[long] = first as i64;
+        if offset + bits > 64 {
+            let shift = 64 - offset;
+            let second = data[long + 1] as u64 & !(mask >> shift) | (value >> shift);
+            data[long + 1] = second as i64;
+        }
+        Ok(())
+    }
+}
+
+impl PackedArray<Vec<i64>> {
+    /// `len` entries of zero
+    pub fn zeroed(bits: u32, len: usize, layout: Layout) -> Result<Self, NBTError> {
+        if !(1..=64).contains(&bits) {
+            return Err(NBTError::InvalidBitsPerEntry(bits));
+        }
+        Self::new(vec![0; layout.longs_needed(bits, len)], bits, len, layout)
+    }
+
+    pub fn from_values(
+        values: impl ExactSizeIterator<Item = u64>,
+        bits: u32,
+        layout: Layout,
+    ) -> Result<Self, NBTError> {
+        let mut packed = Self::zeroed(bits, values.len(), layout)?;
+        for (index, value) in values.enumerate() {
+            packed.set(index, value)?;
+        }
+        Ok(packed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::packed::{bits_needed, Layout, PackedArray};
+    use crate::NBTError;
+
+    #[test]
+    fn layouts() {
+        // 16 entries of 4 bits fill a long the same way in both layouts
+        let packed =
+            PackedArray::from_values((0..16u32).map(u64::from), 4, Layout::Padded).unwrap();
+        assert_eq!(packed.as_slice(), [0xFEDCBA9876543210u64 as i64]);
+        let spanning = packed.repack(4, Layout::Spanning).unwrap();
+        assert_eq!(spanning.as_slice(), packed.as_slice());
+
+        // 13 entries of 5 bits. Padded puts the 13th into the second long
+        let values: Vec<u64> = (0..13).map(|v| 31 - v).collect();
+        let padded = PackedArray::from_values(values.iter().copied(), 5, Layout::Padded).unwrap();
+        assert_eq!(padded.as_slice().len(), 2);
+        assert_eq!(padded.as_slice()[1], 19);
+        assert_eq!(padded.as_slice()[0] as u64 >> 60, 0);
+        // Spanning puts the low 4 bits of the 13th into the first long and its high bit into the second
+        let spanning = padded.repack(5, Layout::Spanning).unwrap();
+        assert_eq!(spanning.as_slice().len(), 2);
+        assert_eq!(spanning.as_slice()[0] as u64 >> 60, 19 & 0b1111);
+        assert_eq!(spanning.as_slice()[1], 1);
+        assert_eq!(spanning.iter().collect::<Vec<_>>(), values);
+        assert_eq!(padded.iter().collect::<Vec<_>>(), values);
+    }
+
+    #[test]
+    fn get_set() {
+        for layout in [Layout::Spanning, Layout::Padded] {
+            for bits in [1, 5, 9, 15, 33, 64] {
+                let mut packed = PackedArray::zeroed(bits, 100, layout).unwrap();
+                let max = u64::MAX >> (64 - bits);
+                for index in 0..100 {
+                    packed.set(index, (index as u64 * 7919) & max).unwrap();
+                }
+                // Neighbours are not overwritten
+                packed.set(50, max).unwrap();
+                packed.set(50, 0).unwrap();
+                for index in 0..100 {
+                    let expected = if index == 50 {
+                        0
+                    } else {
+                        (index as u64 * 7919) & max
+                    };
+                    assert_eq!(packed.get(index), Some(expected), "{:?} {}", layout, bits);
+                }
+                assert_eq!(packed.get(100), None);
+            }
+        }
+    }
+
+    #[test]
+    fn errors() {
+        let mut packed = PackedArray::zeroed(4, 10, Layout::Padded).unwrap();
+        assert!(matches!(
+            packed.set(0, 16),
+            Err(NBTError::ValueTooLarge(16, 4))
+        ));
+        assert!(matches!(
+            packed.set(10, 0),
+            Err(NBTError::IndexOutOfBounds(10, 10))
+        ));
+        assert!(matches!(
+            packed.repack(3, Layout::Padded),
+            Ok(repacked) if repacked.as_slice().len() == 1
+        ));
+        packed.set(0, 15).unwrap();
+        assert!(matches!(
+            packed.repack(3, Layout::Padded),
+            Err(NBTError::ValueTooLarge(15, 3))
+        ));
+        assert!(matches!(
+            PackedArray::new(vec![0i64; 3], 4, 10, Layout::Padded),
+            Err(NBTError::PackedLengthMismatch(1, 3))
+        ));
+        assert!(matches!(
+            PackedArray::new(&[][..], 0, 0, Layout::Padded),
+            Err(NBTError::InvalidBitsPerEntry(0))
+        ));
+
+        assert_eq!(bits_needed(0), 1);
+        assert_eq!(bits_needed(15), 4);
+        assert_eq!(bits_needed(16), 5);
+        assert_eq!(Layout::for_data_version(2230), Layout::Spanning);
+        assert_eq!(Layout::for_data_version(3465), Layout::Padded);
+    }
+}
@@ -0,0 +1,216 @@
+//! Control if a sequence is written as a TAG_List or as one of the typed arrays.
+//!
+//! By default sequences of i8, i32 and i64 become arrays and everything else becomes a list.
+//! ```rust ignore
+//! #[derive(Serialize, Deserialize)]
+//! pub struct Entity {
+//!     #[serde(with = "axolotl_nbt::serde_impl::as_list")]
+//!     pub pos: Vec<i32>,
+//!     #[serde(with = "axolotl_nbt::serde_impl::as_int_array")]
+//!     pub uuid: [u32; 4],
+//!     pub data: LongArray,
+//! }
+//! ```
+use crate::serde_impl::Error;
+use crate::{ListType, Tag};
+use serde::de::{SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt::Formatter;
+
+pub(crate) const LIST_TOKEN: &str = "$axolotl_nbt::List";
+pub(crate) const BYTE_ARRAY_TOKEN: &str = "$axolotl_nbt::ByteArray";
+pub(crate) const INT_ARRAY_TOKEN: &str = "$axolotl_nbt::IntArray";
+pub(crate) const LONG_ARRAY_TOKEN: &str = "$axolotl_nbt::LongArray";
+
+/// How a sequence is written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SequenceEncoding {
+    /// Sequences of Byte, Int and Long become arrays. Everything else becomes a list
+    #[default]
+    Default,
+    List,
+    ByteArray,
+    IntArray,
+    LongArray,
+}
+
+impl SequenceEncoding {
+    pub(crate) fn from_token(name: &str) -> Option<Self> {
+        match name {
+            LIST_TOKEN => Some(Self::List),
+            BYTE_ARRAY_TOKEN => Some(Self::ByteArray),
+            INT_ARRAY_TOKEN => Some(Self::IntArray),
+            LONG_ARRAY_TOKEN => Some(Self::LongArray),
+            _ => None,
+        }
+    }
+
+    /// The tag the elements must have. None if any tag is allowed
+    pub fn element_tag(self) -> Option<Tag> {
+        match self {
+            Self::Default | Self::List => None,
+            Self::ByteArray => Some(Tag::Byte),
+            Self::IntArray => Some(Tag::Int),
+            Self::LongArray => Some(Tag::Long),
+        }
+    }
+
+    /// Picks the list type for a sequence of `element`
+    pub(crate) fn list_type(self, element: Tag, default: ListType) -> Result<ListType, Error> {
+        match self {
+            Self::Default => Ok(default),
+            Self::List => Ok(ListType::List(element)),
+            Self::ByteArray if element == Tag::Byte => Ok(ListType::ByteArray),
+            Self::IntArray if element == Tag::Int => Ok(ListType::IntArray),
+            Self::LongArray if element == Tag::Long => Ok(ListType::LongArray),
+            array => Err(Error::IncorrectTagError(
+                array.element_tag().unwrap_or(element),
+                element,
+            )),
+        }
+    }
+
+    /// Fails if the elements of this encoding can not be `element`
+    pub(crate) fn check(self, element: Tag) -> Result<(), Error> {
+        match self.element_tag() {
+            Some(tag) if tag != element => Err(Error::IncorrectTagError(tag, element)),
+            _ => Ok(()),
+        }
+    }
+
+    /// The array tag used instead of a list
+    pub(crate) fn array_tag(self) -> Option<Tag> {
+        match self {
+            Self::Default | Self::List => None,
+            Self::ByteArray => Some(Tag::ByteArray),
+            Self::IntArray => Some(Tag::IntArray),
+            Self::LongArray => Some(Tag::LongArray),
+        }
+    }
+}
+
+macro_rules! adapter {
+    ($module:ident, $token:ident, $doc:literal) => {
+        #[doc = $doc]
+        pub mod $module {
+            use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+            pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                T: ?Sized + Serialize,
+                S: Serializer,
+            {
+                serializer.serialize_newtype_struct(super::$token, value)
+            }
+
+            pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+            where
+                T: Deserialize<'de>,
+                D: Deserializer<'de>,
+            {
+                T::deserialize(deserializer)
+            }
+        }
+    };
+}
+adapter!(as_list, LIST_TOKEN, "Writes the sequence as a TAG_List");
+adapter!(
+    as_byte_array,
+    BYTE_ARRAY_TOKEN,
+    "Writes the sequence as a TAG_Byte_Array. The elements must be bytes"
+);
+adapter!(
+    as_int_array,
+    INT_ARRAY_TOKEN,
+    "Writes the sequence as a TAG_Int_Array. The elements must be ints"
+);
+adapter!(
+    as_long_array,
+    LONG_ARRAY_TOKEN,
+    "Writes the sequence as a TAG_Long_Array. The elements must be longs"
+);
+
+macro_rules! array {
+    ($name:ident, $inner:ty, $token:ident) => {
+        #[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+        pub struct $name(pub Vec<$inner>);
+
+        impl From<Vec<$inner>> for $name {
+            fn from(value: Vec<$inner>) -> Self {
+                Self(value)
+            }
+        }
+
+        impl From<$name> for Vec<$inner> {
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+
+        impl std::ops::Deref for $name {
+            type Target = Vec<$inner>;
+
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+
+        impl std::ops::DerefMut for $name {
+            fn deref_mut(&mut self) -> &mut Self::Target {
+                &mut self.0
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                serializer.serialize_newtype_struct($token, &self.0)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                struct ArrayVisitor;
+
+                impl<'de> Visitor<'de> for ArrayVisitor {
+                    type Value = $name;
+
+                    fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+                        formatter.write_str(stringify!($name))
+                    }
+
+                    fn visit_newtype_struct<D>(
+                        self,
+                        deserializer: D,
+                    ) -> Result<Self::Value, D::Error>
+                    where
+                        D: Deserializer<'de>,
+                    {
+                        Vec::deserialize(deserializer).map($name)
+                    }
+
+                    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+                    where
+                        A: SeqAccess<'de>,
+                    {
+                        let mut values = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+                        while let Some(value) = seq.next_element()? {
+                            values.push(value);
+                        }
+                        Ok($name(values))
+                    }
+                }
+
+                deserializer.deserialize_newtype_struct($token, ArrayVisitor)
+            }
+        }
+    };
+}
+array!(ByteArray, i8, BYTE_ARRAY_TOKEN);
+array!(IntArray, i32, INT_ARRAY_TOKEN);
+array!(LongArray, i64, LONG_ARRAY_TOKEN);
@@ -1,11 +1,18 @@
-use crate::serde_impl::Error;
+use crate::serde_impl::key::parse_key;
+use crate::serde_impl::unsigned::read_unsigned_with;
+use crate::serde_impl::value::VALUE_TOKEN;
+use crate::serde_impl::{Error, Located};
+use crate::value::PathSegment;
 use crate::{NBTDataType, NBTType, Tag};
 
-use serde::de::{DeserializeSeed, MapAccess, SeqAccess, Visitor};
-use serde::{forward_to_deserialize_any, Deserializer};
+use serde::de::value::SeqAccessDeserializer;
+use serde::de::{
+    DeserializeSeed, EnumAccess, IgnoredAny, IntoDeserializer, MapAccess, SeqAccess, VariantAccess,
+    Visitor,
+};
+use serde::{forward_to_deserialize_any, Deserialize, Deserializer};
 
 use std::io::{BufRead, Read};
-use std::mem;
 
 pub struct NBTDeserializer<Reader: Read + BufRead, Type: NBTType> {
     pub(crate) src: Reader,
@@ -21,16 +28,55 @@ impl<Reader: Read + BufRead, Type: NBTType> NBTDeserializer<Reader, Type> {
     }
 }
 
+/// Counts the bytes read so errors can tell where they happened
+pub(crate) struct OffsetReader<R> {
+    pub(crate) inner: R,
+    pub(crate) offset: u64,
+}
+
+impl<R> OffsetReader<R> {
+    pub(crate) fn new(inner: R) -> Self {
+        Self { inner, offset: 0 }
+    }
+}
+
+impl<R: Read> Read for OffsetReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.offset += read as u64;
+        Ok(read)
+    }
+}
+
+impl<R: BufRead> BufRead for OffsetReader<R> {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.offset += amt as u64;
+        self.inner.consume(amt)
+    }
+}
+
 impl<'de, 'reader, Reader: Read + BufRead, Type: NBTType> Deserializer<'de>
 for &'reader mut NBTDeserializer<Reader, Type>
 {
-    type Error = super::Error;
+    type Error = Located;
 
-    fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
+    /// Reads the root tag and visits it based on its type
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
         where
             V: Visitor<'de>,
     {
-        Err(Error::Custom("deserialize_any not implemented".to_string()))
+        let (_, tag) = Tag::read_with_name(&mut self.src)?;
+        InnerDeserializer::<'_, Reader, Type> {
+            reader: &mut self.src,
+            tag,
+            phantom: Default::default(),
+        }
+        .deserialize_any(visitor)
+        .map_err(|error| error.with_found(tag))
     }
 
     fn deserialize_unit_struct<V>(
@@ -46,18 +92,69 @@ for &'reader mut NBTDeserializer<Reader, Type>
 
     forward_to_deserialize_any! {
         bool u8 u16 u32 u64 i8 i16 i32 i64 f32 f64 char str string bytes byte_buf
-        unit seq tuple_struct tuple option enum identifier ignored_any
+        seq tuple_struct tuple identifier ignored_any
+    }
+
+    /// There is always a root tag. So this is always `Some`
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+    {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+    {
+        let (_, tag) = Tag::read_with_name(&mut self.src)?;
+        InnerDeserializer::<'_, Reader, Type> {
+            reader: &mut self.src,
+            tag,
+            phantom: Default::default(),
+        }
+        .deserialize_unit(visitor)
+        .map_err(|error| error.with_found(tag))
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+    {
+        let (_, tag) = Tag::read_with_name(&mut self.src)?;
+        InnerDeserializer::<'_, Reader, Type> {
+            reader: &mut self.src,
+            tag,
+            phantom: Default::default(),
+        }
+        .deserialize_enum(name, variants, visitor)
+        .map_err(|error| error.with_found(tag))
     }
 
     fn deserialize_newtype_struct<V>(
         self,
-        _name: &'static str,
+        name: &'static str,
         visitor: V,
     ) -> Result<V::Value, Self::Error>
         where
             V: Visitor<'de>,
     {
-        visitor.visit_newtype_struct(self)
+        if name != VALUE_TOKEN {
+            return visitor.visit_newtype_struct(self);
+        }
+        let (_, tag) = Tag::read_with_name(&mut self.src)?;
+        InnerDeserializer::<'_, Reader, Type> {
+            reader: &mut self.src,
+            tag,
+            phantom: Default::default(),
+        }
+        .deserialize_newtype_struct(name, visitor)
+        .map_err(|error| error.with_found(tag))
     }
 
     fn deserialize_map<V>(self, visitor: V) -> Result<V::Value, Self::Error>
@@ -67,14 +164,16 @@ for &'reader mut NBTDeserializer<Reader, Type>
         let (_, tag) = Tag::read_with_name(&mut self.src)?;
 
         if Tag::Compound == tag {
-            visitor.visit_map(CompoundMap::<'reader, Reader, Type> {
-                reader: &mut self.src,
-                key: vec![],
-                next_entry: None,
-                phantom: Default::default(),
-            })
+            visitor
+                .visit_map(CompoundMap::<'reader, Reader, Type> {
+                    reader: &mut self.src,
+                    key: vec![],
+                    next_entry: None,
+                    phantom: Default::default(),
+                })
+                .map_err(|error| error.with_found(tag))
         } else {
-            Err(Error::IncorrectTagError(Tag::Compound, tag))
+            Err(Located::incorrect_tag(Tag::Compound, tag, &visitor))
         }
     }
 
@@ -101,7 +200,7 @@ struct CompoundMap<'reader, Reader: Read + BufRead, Type: NBTType> {
 impl<'de, 'reader, Reader: Read + BufRead, Type: NBTType> MapAccess<'de>
 for CompoundMap<'reader, Reader, Type>
 {
-    type Error = super::Error;
+    type Error = Located;
 
     fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
         where
@@ -115,7 +214,7 @@ for CompoundMap<'reader, Reader, Type>
         Type::read_tag_name_raw(&mut self.reader, &mut self.key)?;
         self.next_entry = Some(tag);
         let inner = NameDeserializer {
-            content: &mut self.key,
+            content: &self.key,
         };
         seed.deserialize(inner).map(Some)
     }
@@ -127,48 +226,71 @@ for CompoundMap<'reader, Reader, Type>
         match self.next_entry.take() {
             None => Err(Error::Custom(
                 "next_value_seed called before next_key_seed".to_string(),
-            )),
+            ).into()),
             Some(value) => {
                 let inner = InnerDeserializer::<'_, Reader, Type> {
                     reader: self.reader,
                     tag: value,
                     phantom: Default::default(),
                 };
-                seed.deserialize(inner)
+                seed.deserialize(inner).map_err(|error| {
+                    let key = String::from_utf8_lossy(&self.key).into_owned();
+                    error.with_found(value).at(PathSegment::Key(key))
+                })
             }
         }
     }
 }
 
 struct NameDeserializer<'string> {
-    pub(crate) content: &'string mut Vec<u8>,
+    pub(crate) content: &'string [u8],
 }
 
 impl<'de, 'string> Deserializer<'de> for NameDeserializer<'string> {
-    type Error = super::Error;
+    type Error = Located;
 
     forward_to_deserialize_any! {
-        u8 u16 u32 u64 i8 i16 i32 i64 f32 f64 char bytes byte_buf seq
-        map tuple_struct struct tuple enum identifier ignored_any option unit newtype_struct bool unit_struct
+        f32 f64 bytes byte_buf seq
+        map tuple_struct struct tuple identifier ignored_any option unit newtype_struct unit_struct
     }
+    parse_key!(self => std::str::from_utf8(self.content).map_err(|e| Error::Custom(e.to_string()))?);
     fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
         where
             V: Visitor<'de>,
     {
-        visitor.visit_bytes(self.content)
+        match std::str::from_utf8(self.content) {
+            Ok(name) => visitor.visit_str(name),
+            Err(_) => visitor.visit_bytes(self.content),
+        }
     }
     fn deserialize_str<V>(self, visitor: V) -> Result<V::Value, Self::Error>
         where
             V: Visitor<'de>,
     {
-        visitor.visit_str(std::str::from_utf8(self.content).unwrap())
+        visitor.visit_str(std::str::from_utf8(self.content).map_err(|e| Error::Custom(e.to_string()))?)
     }
 
     fn deserialize_string<V>(self, visitor: V) -> Result<V::Value, Self::Error>
         where
             V: Visitor<'de>,
     {
-        visitor.visit_string(String::from_utf8(mem::take(self.content))?)
+        // Borrowed so the key is still there if the value fails
+        self.deserialize_str(visitor)
+    }
+
+    /// Unit variant keys
+    fn deserialize_enum<V>(
+        self,
+        _: &'static str,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+    {
+        let variant =
+            std::str::from_utf8(self.content).map_err(|e| Error::Custom(e.to_string()))?;
+        visitor.visit_enum(IntoDeserializer::<Located>::into_deserializer(variant))
     }
 }
 
@@ -178,13 +300,116 @@ struct InnerDeserializer<'reader, Reader: Read + BufRead, Type: NBTType> {
     pub(crate) phantom: std::marker::PhantomData<Type>,
 }
 
+impl<'reader, Reader: Read + BufRead, Type: NBTType> InnerDeserializer<'reader, Reader, Type> {
+    /// Reads any integer tag. `expected` is reported if this is not an integer
+    fn read_integer(self, expected: Tag) -> Result<(Tag, i64), Error> {
+        let value = match self.tag {
+            Tag::Byte => i8::read(self.reader)? as i64,
+            Tag::Short => i16::read(self.reader)? as i64,
+            Tag::Int => i32::read(self.reader)? as i64,
+            Tag::Long => i64::read(self.reader)?,
+            tag => return Err(Error::IncorrectTagError(expected, tag)),
+        };
+        Ok((self.tag, value))
+    }
+}
+
 impl<'de, 'reader, Reader: Read + BufRead, Type: NBTType> Deserializer<'de>
 for InnerDeserializer<'reader, Reader, Type>
 {
-    type Error = super::Error;
+    type Error = Located;
     forward_to_deserialize_any! {
-        u8 u16 u32 u64 i8 i16 i32 i64 f32 f64 char str string bytes byte_buf seq
-        map tuple_struct struct tuple enum identifier ignored_any
+        i8 i16 i32 i64 f32 f64 char str string seq
+        map struct identifier ignored_any
+    }
+
+    read_unsigned_with!(read_integer);
+
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+    {
+        self.deserialize_byte_buf(visitor)
+    }
+
+    /// A TAG_Byte_Array is read in one go. Anything else is visited like deserialize_any
+    fn deserialize_byte_buf<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+    {
+        match self.tag {
+            Tag::ByteArray => {
+                let length = i32::read(&mut self.reader)?;
+                let mut bytes = vec![0; length.max(0) as usize];
+                self.reader.read_exact(&mut bytes)?;
+                visitor.visit_byte_buf(bytes)
+            }
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    /// Tuples are lists or arrays of exactly `len` elements
+    fn deserialize_tuple<V>(mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+    {
+        let tag = match self.tag {
+            Tag::List => Tag::read(&mut self.reader)?,
+            Tag::ByteArray => Tag::Byte,
+            Tag::IntArray => Tag::Int,
+            Tag::LongArray => Tag::Long,
+            tag => return Err(Located::incorrect_tag(Tag::List, tag, &visitor)),
+        };
+        let deserializer = SequenceDeserializer::<'reader, Reader, Type>::read(self.reader, tag)?;
+        if deserializer.len != len {
+            return Err(Error::IncorrectLengthError(len, deserializer.len).into());
+        }
+        visitor.visit_seq(deserializer)
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+    {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    /// Unit variants are strings. Other variants are a compound with a single entry named after the variant
+    fn deserialize_enum<V>(
+        mut self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+    {
+        match self.tag {
+            Tag::String => visitor.visit_enum(IntoDeserializer::<Located>::into_deserializer(
+                String::read(&mut self.reader)?,
+            )),
+            Tag::Compound => {
+                let tag = Tag::read(&mut self.reader)?;
+                if tag == Tag::End {
+                    return Err(Error::Custom(
+                        "An empty compound is not an enum variant".to_string(),
+                    ).into());
+                }
+                let variant = Type::read_tag_name(&mut self.reader)?;
+                visitor.visit_enum(CompoundVariant::<'reader, Reader, Type> {
+                    reader: self.reader,
+                    tag,
+                    variant,
+                    phantom: Default::default(),
+                })
+            }
+            tag => Err(Located::incorrect_tag(Tag::Compound, tag, &visitor)),
+        }
     }
 
     fn deserialize_any<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
@@ -199,27 +424,15 @@ for InnerDeserializer<'reader, Reader, Type>
             Tag::Float => visitor.visit_f32(f32::read(&mut self.reader)?),
             Tag::Double => visitor.visit_f64(f64::read(&mut self.reader)?),
             Tag::ByteArray => {
-                let length = i32::read(&mut self.reader)?;
-                let deserializer = SequenceDeserializer::<'reader, Reader, Type> {
-                    reader: self.reader,
-                    tag: Tag::Byte,
-                    len: length as usize,
-                    current: 0,
-                    phantom: Default::default(),
-                };
+                let deserializer =
+                    SequenceDeserializer::<'reader, Reader, Type>::read(self.reader, Tag::Byte)?;
                 visitor.visit_seq(deserializer)
             }
             Tag::String => visitor.visit_string(String::read(&mut self.reader)?),
             Tag::List => {
                 let tag = Tag::read(&mut self.reader)?;
-                let length = i32::read(&mut self.reader)?;
-                let deserializer = SequenceDeserializer::<'reader, Reader, Type> {
-                    reader: self.reader,
-                    tag,
-                    len: length as usize,
-                    current: 0,
-                    phantom: Default::default(),
-                };
+                let deserializer =
+                    SequenceDeserializer::<'reader, Reader, Type>::read(self.reader, tag)?;
                 visitor.visit_seq(deserializer)
             }
             Tag::Compound => {
@@ -232,31 +445,16 @@ for InnerDeserializer<'reader, Reader, Type>
                 visitor.visit_map(deserializer)
             }
             Tag::IntArray => {
-                let length = i32::read(&mut self.reader)?;
-                let deserializer = SequenceDeserializer::<'reader, Reader, Type> {
-                    reader: self.reader,
-                    tag: Tag::Int,
-                    len: length as usize,
-                    current: 0,
-                    phantom: Default::default(),
-                };
+                let deserializer =
+                    SequenceDeserializer::<'reader, Reader, Type>::read(self.reader, Tag::Int)?;
                 visitor.visit_seq(deserializer)
             }
             Tag::LongArray => {
-                let length = i32::read(&mut self.reader)?;
-                let deserializer = SequenceDeserializer::<'reader, Reader, Type> {
-                    reader: self.reader,
-                    tag: Tag::Long,
-                    len: length as usize,
-                    current: 0,
-                    phantom: Default::default(),
-                };
+                let deserializer =
+                    SequenceDeserializer::<'reader, Reader, Type>::read(self.reader, Tag::Long)?;
                 visitor.visit_seq(deserializer)
             }
-            t => Err(Error::Custom(format!(
-                "deserialize_any not implemented for {:?}",
-                t
-            ))),
+            Tag::End => visitor.visit_unit(),
         }
     }
 
@@ -267,30 +465,39 @@ for InnerDeserializer<'reader, Reader, Type>
         match self.tag {
             Tag::Byte => {
                 let value = i8::read(&mut self.reader)?;
-                match value {
-                    0 => visitor.visit_bool(false),
-                    1 => visitor.visit_bool(true),
-                    b => Err(Error::Custom(format!("Invalid byte value for bool: {}", b))),
-                }
+                visitor.visit_bool(value != 0)
             }
-            _ => Err(Error::IncorrectTagError(self.tag, Tag::Byte)),
+            _ => Err(Located::incorrect_tag(Tag::Byte, self.tag, &visitor)),
         }
     }
 
+    /// A value that is present is always `Some`. Missing compound entries become `None`
     fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
         where
             V: Visitor<'de>,
     {
-        visitor.visit_some(self)
+        match self.tag {
+            Tag::End => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
     }
 
-    fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    /// Unit is written as an empty compound
+    fn deserialize_unit<V>(mut self, visitor: V) -> Result<V::Value, Self::Error>
         where
             V: Visitor<'de>,
     {
-        visitor.visit_unit()
+        match self.tag {
+            Tag::End => visitor.visit_unit(),
+            Tag::Compound => match Tag::read(&mut self.reader)? {
+                Tag::End => visitor.visit_unit(),
+                tag => Err(Located::incorrect_tag(Tag::End, tag, &visitor)),
+            },
+            tag => Err(Located::incorrect_tag(Tag::Compound, tag, &visitor)),
+        }
     }
 
+    /// Unit structs are written as their name. The value is skipped
     fn deserialize_unit_struct<V>(
         self,
         _name: &'static str,
@@ -299,18 +506,35 @@ for InnerDeserializer<'reader, Reader, Type>
         where
             V: Visitor<'de>,
     {
+        self.deserialize_ignored_any(IgnoredAny)?;
         visitor.visit_unit()
     }
 
+    /// [VALUE_TOKEN] is visited like deserialize_any, except lists of bytes, ints and longs are
+    /// given as `Some` to tell them apart from the arrays
     fn deserialize_newtype_struct<V>(
-        self,
-        _name: &'static str,
+        mut self,
+        name: &'static str,
         visitor: V,
     ) -> Result<V::Value, Self::Error>
         where
             V: Visitor<'de>,
     {
-        visitor.visit_newtype_struct(self)
+        match (name, self.tag) {
+            (VALUE_TOKEN, Tag::List) => {
+                let tag = Tag::read(&mut self.reader)?;
+                let deserializer =
+                    SequenceDeserializer::<'reader, Reader, Type>::read(self.reader, tag)?;
+                match tag {
+                    Tag::Byte | Tag::Int | Tag::Long => {
+                        visitor.visit_some(SeqAccessDeserializer::new(deserializer))
+                    }
+                    _ => visitor.visit_seq(deserializer),
+                }
+            }
+            (VALUE_TOKEN, _) => self.deserialize_any(visitor),
+            _ => visitor.visit_newtype_struct(self),
+        }
     }
 }
 
@@ -322,10 +546,30 @@ struct SequenceDeserializer<'reader, Reader: Read + BufRead, Type: NBTType> {
     pub(crate) phantom: std::marker::PhantomData<Type>,
 }
 
+impl<'reader, Reader: Read + BufRead, Type: NBTType> SequenceDeserializer<'reader, Reader, Type> {
+    /// Reads the length of a list or array of `tag`. A negative length is an empty sequence.
+    /// A list of End can not have elements, they would not take up any bytes
+    fn read(reader: &'reader mut Reader, tag: Tag) -> Result<Self, Error> {
+        let len = i32::read(reader)?.max(0) as usize;
+        if tag == Tag::End && len != 0 {
+            return Err(Error::Custom(format!(
+                "A list of End can not have {len} elements"
+            )));
+        }
+        Ok(Self {
+            reader,
+            tag,
+            len,
+            current: 0,
+            phantom: Default::default(),
+        })
+    }
+}
+
 impl<'de, 'reader, Reader: Read + BufRead, Type: NBTType> SeqAccess<'de>
 for SequenceDeserializer<'reader, Reader, Type>
 {
-    type Error = super::Error;
+    type Error = Located;
     fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
         where
             T: DeserializeSeed<'de>,
@@ -338,7 +582,11 @@ for SequenceDeserializer<'reader, Reader, Type>
             tag: self.tag,
             phantom: Default::default(),
         };
-        let value = seed.deserialize(de)?;
+        let value = seed.deserialize(de).map_err(|error| {
+            error
+                .with_found(self.tag)
+                .at(PathSegment::Index(self.current))
+        })?;
 
         self.current += 1;
 
@@ -346,6 +594,99 @@ for SequenceDeserializer<'reader, Reader, Type>
     }
 
     fn size_hint(&self) -> Option<usize> {
-        Some(self.len as usize)
+        Some(self.len)
+    }
+}
+
+struct CompoundVariant<'reader, Reader: Read + BufRead, Type: NBTType> {
+    pub(crate) reader: &'reader mut Reader,
+    pub(crate) tag: Tag,
+    pub(crate) variant: String,
+    pub(crate) phantom: std::marker::PhantomData<Type>,
+}
+
+impl<'reader, Reader: Read + BufRead, Type: NBTType> CompoundVariant<'reader, Reader, Type> {
+    fn inner(&mut self) -> InnerDeserializer<'_, Reader, Type> {
+        InnerDeserializer {
+            reader: self.reader,
+            tag: self.tag,
+            phantom: Default::default(),
+        }
+    }
+    /// Errors within the variant are located at the variant
+    fn locate(&self, error: Located) -> Located {
+        error
+            .with_found(self.tag)
+            .at(PathSegment::Key(self.variant.clone()))
+    }
+    /// The compound holding the variant may only have one entry
+    fn end<T>(mut self, value: T) -> Result<T, Located> {
+        match Tag::read(&mut self.reader)? {
+            Tag::End => Ok(value),
+            tag => Err(Error::IncorrectTagError(Tag::End, tag).into()),
+        }
+    }
+}
+
+impl<'de, 'reader, Reader: Read + BufRead, Type: NBTType> EnumAccess<'de>
+for CompoundVariant<'reader, Reader, Type>
+{
+    type Error = Located;
+    type Variant = Self;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error>
+        where
+            V: DeserializeSeed<'de>,
+    {
+        let variant = IntoDeserializer::<Located>::into_deserializer(self.variant.as_str());
+        let value = seed.deserialize(variant)?;
+        Ok((value, self))
+    }
+}
+
+impl<'de, 'reader, Reader: Read + BufRead, Type: NBTType> VariantAccess<'de>
+for CompoundVariant<'reader, Reader, Type>
+{
+    type Error = Located;
+
+    fn unit_variant(mut self) -> Result<(), Self::Error> {
+        IgnoredAny::deserialize(self.inner())?;
+        self.end(())
+    }
+
+    fn newtype_variant_seed<T>(mut self, seed: T) -> Result<T::Value, Self::Error>
+        where
+            T: DeserializeSeed<'de>,
+    {
+        let value = seed
+            .deserialize(self.inner())
+            .map_err(|error| self.locate(error))?;
+        self.end(value)
+    }
+
+    fn tuple_variant<V>(mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+    {
+        let value = self
+            .inner()
+            .deserialize_tuple(len, visitor)
+            .map_err(|error| self.locate(error))?;
+        self.end(value)
+    }
+
+    fn struct_variant<V>(
+        mut self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+    {
+        let value = self
+            .inner()
+            .deserialize_map(visitor)
+            .map_err(|error| self.locate(error))?;
+        self.end(value)
     }
 }
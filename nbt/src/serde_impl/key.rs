@@ -0,0 +1,163 @@
+//! Map keys. NBT names are always strings, so integer, bool and char keys are written with
+//! `to_string` and parsed back when read. This is how vanilla stores maps such as scoreboards
+use crate::serde_impl::serialize::macros::{gen_method_body, method_body, name_impossible};
+use crate::serde_impl::{Error, Located};
+use serde::de::{IntoDeserializer, Visitor};
+use serde::{forward_to_deserialize_any, ser, Deserializer, Serialize, Serializer};
+
+/// Generates the `serialize_*` methods of keys that are written with `to_string`.
+/// The serializer must have a `key(self, &str)` method
+macro_rules! stringify_key {
+    ($($func:ident($ty:ty)),*) => {
+        $(
+            fn $func(self, v: $ty) -> Result<Self::Ok, Self::Error> {
+                self.key(&v.to_string())
+            }
+        )*
+    };
+}
+
+/// Generates the `deserialize_*` methods of keys that are parsed back from a string
+macro_rules! parse_key {
+    ($self:ident => $key:expr; $($func:ident => $visit:ident),*) => {
+        $(
+            fn $func<V>($self, visitor: V) -> Result<V::Value, Self::Error>
+            where
+                V: serde::de::Visitor<'de>,
+            {
+                let key: &str = $key;
+                match key.parse() {
+                    Ok(value) => visitor.$visit(value),
+                    Err(_) => Err($crate::serde_impl::Error::Custom(format!(
+                        "Invalid map key {:?}",
+                        key
+                    )).into()),
+                }
+            }
+        )*
+    };
+    ($self:ident => $key:expr) => {
+        parse_key!($self => $key;
+            deserialize_bool => visit_bool,
+            deserialize_i8 => visit_i8,
+            deserialize_i16 => visit_i16,
+            deserialize_i32 => visit_i32,
+            deserialize_i64 => visit_i64,
+            deserialize_u8 => visit_u8,
+            deserialize_u16 => visit_u16,
+            deserialize_u32 => visit_u32,
+            deserialize_u64 => visit_u64,
+            deserialize_char => visit_char
+        );
+    };
+}
+pub(crate) use parse_key;
+
+/// Turns a map key into the name of the entry
+pub struct MapKey;
+
+impl MapKey {
+    fn key(self, key: &str) -> Result<String, Error> {
+        Ok(key.to_string())
+    }
+}
+
+impl Serializer for MapKey {
+    type Ok = String;
+    type Error = Error;
+    type SerializeSeq = ser::Impossible<Self::Ok, Self::Error>;
+    type SerializeTuple = ser::Impossible<Self::Ok, Self::Error>;
+    type SerializeTupleStruct = ser::Impossible<Self::Ok, Self::Error>;
+    type SerializeTupleVariant = ser::Impossible<Self::Ok, Self::Error>;
+    type SerializeMap = ser::Impossible<Self::Ok, Self::Error>;
+    type SerializeStruct = ser::Impossible<Self::Ok, Self::Error>;
+    type SerializeStructVariant = ser::Impossible<Self::Ok, Self::Error>;
+
+    stringify_key!(
+        serialize_bool(bool),
+        serialize_i8(i8),
+        serialize_i16(i16),
+        serialize_i32(i32),
+        serialize_i64(i64),
+        serialize_u8(u8),
+        serialize_u16(u16),
+        serialize_u32(u32),
+        serialize_u64(u64),
+        serialize_char(char)
+    );
+
+    fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
+        self.key(v)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok, Self::Error> {
+        self.key(variant)
+    }
+
+    fn serialize_newtype_struct<T>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    name_impossible!(
+        none,
+        some,
+        f32,
+        f64,
+        bytes,
+        unit,
+        unit_struct,
+        newtype_variant,
+        seq,
+        tuple,
+        tuple_struct,
+        tuple_variant,
+        struct_variant,
+        map,
+        struct
+    );
+}
+
+/// Reads a map key back from the name of the entry
+pub struct MapKeyDeserializer<'a>(pub &'a str);
+
+impl<'de> Deserializer<'de> for MapKeyDeserializer<'_> {
+    type Error = Located;
+
+    forward_to_deserialize_any! {
+        f32 f64 str string bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_str(self.0)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _: &'static str,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_enum(IntoDeserializer::<Located>::into_deserializer(self.0))
+    }
+
+    parse_key!(self => self.0);
+}
@@ -1,14 +1,21 @@
 pub use crate::serde_impl::deserializer::NBTDeserializer;
+use crate::serde_impl::deserializer::OffsetReader;
 use crate::{NBTDataType, NBTError, NBTType, Tag};
 
 use crate::binary::Binary;
 use crate::serde_impl::serialize::NBTSerializer;
-use crate::value::{NameLessValue, Value};
-use std::fmt::{Debug, Display};
+pub use arrays::{as_byte_array, as_int_array, as_list, as_long_array, ByteArray, IntArray, LongArray};
+pub use unsigned::UnsignedPolicy;
+use crate::value::{NBTPath, NameLessValue, PathSegment, Value};
+use serde::de::{Expected, Unexpected};
+use std::fmt::{Debug, Display, Formatter};
 use std::io::{BufRead, BufReader, Read, Write};
 use std::string::FromUtf8Error;
 use thiserror::Error;
 
+pub mod arrays;
+pub mod key;
+pub mod unsigned;
 pub mod deserializer;
 pub mod serialize;
 #[cfg(feature = "value")]
@@ -18,6 +25,8 @@ pub mod value;
 pub enum Error {
     #[error("Expected tag {0:?} got {1:?}")]
     IncorrectTagError(Tag, Tag),
+    #[error("Expected a list of length {0} got {1}")]
+    IncorrectLengthError(usize, usize),
     #[error("{0}")]
     Custom(String),
     #[error("{0}")]
@@ -32,12 +41,135 @@ pub enum Error {
     FromStrError(#[from] FromUtf8Error),
 }
 
-impl serde::de::Error for Error {
+/// A deserialization error with where it happened.
+///
+/// The cause is kept as a plain [Error] in `error` so it can be matched
+#[derive(Debug)]
+pub struct Located {
+    pub error: Error,
+    /// The path of the value that failed. Like `player.Inventory[2].Count`
+    pub path: NBTPath,
+    /// The Rust type that was expected. Like `u8` or `struct Item`
+    pub expected: Option<String>,
+    /// The tag of the value that failed
+    pub found: Option<Tag>,
+    /// How many bytes were read when the error happened. Only known when reading bytes
+    pub offset: Option<u64>,
+}
+
+impl Display for Located {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.error)?;
+        if !self.path.is_root() {
+            write!(f, " at {}", self.path)?;
+        }
+        if let Some(found) = self.found {
+            write!(f, ", found TAG_{:?}", found)?;
+        }
+        if let Some(offset) = self.offset {
+            write!(f, ", byte offset {}", offset)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Located {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl From<Error> for Located {
+    fn from(error: Error) -> Self {
+        let found = match &error {
+            Error::IncorrectTagError(_, found) => Some(*found),
+            _ => None,
+        };
+        Located {
+            error,
+            path: NBTPath::root(),
+            expected: None,
+            found,
+            offset: None,
+        }
+    }
+}
+
+impl From<std::io::Error> for Located {
+    fn from(error: std::io::Error) -> Self {
+        Error::IO(error).into()
+    }
+}
+
+impl From<NBTError> for Located {
+    fn from(error: NBTError) -> Self {
+        Error::NBTErr(error).into()
+    }
+}
+
+impl From<FromUtf8Error> for Located {
+    fn from(error: FromUtf8Error) -> Self {
+        Error::FromStrError(error).into()
+    }
+}
+
+impl Located {
+    /// The error happened within `segment`
+    pub(crate) fn at(mut self, segment: PathSegment) -> Self {
+        self.path.push_front(segment);
+        self
+    }
+
+    /// The error happened while reading a value of `tag`. Inner values take precedence
+    pub(crate) fn with_found(mut self, tag: Tag) -> Self {
+        self.found.get_or_insert(tag);
+        self
+    }
+
+    pub(crate) fn with_offset(mut self, offset: u64) -> Self {
+        self.offset.get_or_insert(offset);
+        self
+    }
+
+    pub(crate) fn with_expected(error: Error, expected: &dyn Expected) -> Self {
+        let mut located = Located::from(error);
+        located.expected = Some(expected.to_string());
+        located
+    }
+
+    /// `visitor` can not read a value of `found`
+    pub(crate) fn incorrect_tag(expected: Tag, found: Tag, visitor: &dyn Expected) -> Self {
+        Self::with_expected(Error::IncorrectTagError(expected, found), visitor)
+    }
+}
+
+impl serde::de::Error for Located {
     fn custom<T>(msg: T) -> Self
     where
         T: Display,
     {
-        Self::Custom(format!("{}", msg))
+        Error::Custom(format!("{}", msg)).into()
+    }
+
+    fn invalid_type(unexpected: Unexpected, expected: &dyn Expected) -> Self {
+        let error = Error::Custom(format!(
+            "invalid type: {}, expected {}",
+            unexpected, expected
+        ));
+        Self::with_expected(error, expected)
+    }
+
+    fn invalid_value(unexpected: Unexpected, expected: &dyn Expected) -> Self {
+        let error = Error::Custom(format!(
+            "invalid value: {}, expected {}",
+            unexpected, expected
+        ));
+        Self::with_expected(error, expected)
+    }
+
+    fn invalid_length(len: usize, expected: &dyn Expected) -> Self {
+        let error = Error::Custom(format!("invalid length {}, expected {}", len, expected));
+        Self::with_expected(error, expected)
     }
 }
 
@@ -65,60 +197,66 @@ where
     for<'str> &'str str: NBTDataType<Type>,
     bool: NBTDataType<Type>,
 {
-    let ser: NBTSerializer<'_, W, Type> = NBTSerializer {
-        writer,
-        phantom: Default::default(),
-    };
-    value.serialize(ser)
+    value.serialize(NBTSerializer::<'_, W, Type>::new(writer))
 }
 
 pub fn from_reader<'de, Type: NBTType, R: Read, T: serde::Deserialize<'de>>(
     reader: R,
-) -> Result<T, Error> {
-    let mut der = NBTDeserializer::<BufReader<R>, Type> {
-        src: BufReader::new(reader),
-        phantom: Default::default(),
-    };
-    T::deserialize(&mut der)
+) -> Result<T, Located> {
+    from_buf_reader::<Type, _, T>(BufReader::new(reader))
 }
 
+/// Errors report how many bytes were read when they happened
 pub fn from_buf_reader<'de, Type: NBTType, R: Read + BufRead, T: serde::Deserialize<'de>>(
     reader: R,
-) -> Result<T, Error> {
-    let mut der = NBTDeserializer::<R, Type> {
-        src: reader,
+) -> Result<T, Located> {
+    let mut der = NBTDeserializer::<OffsetReader<R>, Type> {
+        src: OffsetReader::new(reader),
         phantom: Default::default(),
     };
-    T::deserialize(&mut der)
+    T::deserialize(&mut der).map_err(|error| error.with_offset(der.src.offset))
 }
 
-pub fn from_reader_binary<'de, R: Read, T: serde::Deserialize<'de>>(reader: R) -> Result<T, Error> {
-    let mut der = NBTDeserializer::<BufReader<R>, Binary> {
-        src: BufReader::new(reader),
-        phantom: Default::default(),
-    };
-    T::deserialize(&mut der)
+pub fn from_reader_binary<'de, R: Read, T: serde::Deserialize<'de>>(reader: R) -> Result<T, Located> {
+    from_reader::<Binary, R, T>(reader)
 }
 
 pub fn from_buf_reader_binary<'de, R: Read + BufRead, T: serde::Deserialize<'de>>(
     reader: R,
-) -> Result<T, Error> {
-    let mut der = NBTDeserializer::<R, Binary> {
-        src: reader,
-        phantom: Default::default(),
-    };
-    T::deserialize(&mut der)
+) -> Result<T, Located> {
+    from_buf_reader::<Binary, R, T>(reader)
 }
 
 /// Takes a Value and deserializes it into a type
 #[cfg(feature = "value")]
-pub fn from_value<'de, T: serde::Deserialize<'de>>(value: Value) -> Result<T, Error> {
-    T::deserialize(value::deserialize::ValueDeserializer(value))
+pub fn from_value<'de, T: serde::Deserialize<'de>>(value: Value) -> Result<T, Located> {
+    let tag = value.tag();
+    T::deserialize(value::deserialize::ValueDeserializer(value)).map_err(|error| error.with_found(tag))
 }
 
 #[cfg(feature = "value")]
 pub fn from_nameless_value<'de, T: serde::Deserialize<'de>>(
     value: NameLessValue,
-) -> Result<T, Error> {
+) -> Result<T, Located> {
+    let tag = value.tag();
     T::deserialize(value::deserialize::NamelessValueDeserializer(value))
+        .map_err(|error| error.with_found(tag))
+}
+
+/// Serializes a type into a Value
+///
+/// A struct at the root is named after the struct
+#[cfg(feature = "value")]
+pub fn to_value<T: serde::Serialize + ?Sized>(value: &T) -> Result<Value, Error> {
+    match value.serialize(value::serialize::ValueSerializer::default())? {
+        Value::End => Err(Error::UnrepresentableValueError(
+            "None can not be the root of a NBT document",
+        )),
+        value => Ok(value),
+    }
+}
+
+#[cfg(feature = "value")]
+pub fn to_nameless_value<T: serde::Serialize + ?Sized>(value: &T) -> Result<NameLessValue, Error> {
+    to_value(value).map(Value::into_nameless)
 }
@@ -9,9 +9,9 @@ macro_rules! gen_method_body {
     };
     ($retur:expr, $func:ident($($arg:ty),*), result: $result_ty:path, where: $where_v:path) => {
         #[inline]
-        fn $func<__T: ?Sized>(self, $(_: $arg,)*)
+        fn $func<__T>(self, $(_: $arg,)*)
                               -> ::std::result::Result<$result_ty, Self::Error>
-            where __T: $where_v
+            where __T: ?Sized + $where_v
         {
             $retur
         }
@@ -1,7 +1,10 @@
-mod macros;
+pub(crate) mod macros;
 pub mod named;
 pub mod sequence;
+pub mod variant;
 
+use crate::serde_impl::key::MapKey;
+use crate::serde_impl::unsigned::UnsignedPolicy;
 use crate::serde_impl::Error;
 use crate::{NBTDataType, NBTType, Tag};
 
@@ -22,30 +25,38 @@ where
     for<'str> &'str str: NBTDataType<Type>,
 {
     pub(crate) writer: &'writer mut W,
+    pub(crate) unsigned: UnsignedPolicy,
     pub(crate) phantom: std::marker::PhantomData<Type>,
 }
-macro_rules! cast_and_write {
-    () => {
-        fn serialize_u8(self, v: u8) -> Result<Self::Ok, Self::Error> {
-            self.serialize_i8(v as i8)
-        }
-
-        fn serialize_u16(self, v: u16) -> Result<Self::Ok, Self::Error> {
-            self.serialize_i16(v as i16)
-        }
 
-        fn serialize_u32(self, v: u32) -> Result<Self::Ok, Self::Error> {
-            self.serialize_i32(v as i32)
+impl<'writer, W: Write, Type: NBTType> NBTSerializer<'writer, W, Type>
+where
+    i8: NBTDataType<Type>,
+    i16: NBTDataType<Type>,
+    i32: NBTDataType<Type>,
+    i64: NBTDataType<Type>,
+    f32: NBTDataType<Type>,
+    f64: NBTDataType<Type>,
+    String: NBTDataType<Type>,
+    for<'str> &'str str: NBTDataType<Type>,
+{
+    pub fn new(writer: &'writer mut W) -> Self {
+        Self {
+            writer,
+            unsigned: UnsignedPolicy::default(),
+            phantom: Default::default(),
         }
+    }
 
-        fn serialize_u64(self, v: u64) -> Result<Self::Ok, Self::Error> {
-            self.serialize_i64(v as i64)
-        }
-    };
+    /// Picks how unsigned integers are written. See [UnsignedPolicy]
+    pub fn unsigned(mut self, policy: UnsignedPolicy) -> Self {
+        self.unsigned = policy;
+        self
+    }
 }
 use crate::serde_impl::serialize::macros::{gen_method_body, impossible, method_body};
 use crate::serde_impl::serialize::named::{NamedValueSerializer, StringOrSerializer};
-pub(crate) use cast_and_write;
+use crate::serde_impl::serialize::variant::{StructVariant, TupleVariant};
 
 impl<'writer, W: Write, Type: NBTType> Serializer for NBTSerializer<'writer, W, Type>
 where
@@ -64,17 +75,14 @@ where
     type SerializeSeq = ser::Impossible<(), Self::Error>;
     type SerializeTuple = ser::Impossible<(), Self::Error>;
     type SerializeTupleStruct = ser::Impossible<(), Self::Error>;
-    type SerializeTupleVariant = ser::Impossible<(), Self::Error>;
+    type SerializeTupleVariant = TupleVariant<'writer, W, Type>;
     type SerializeMap = Compound<'writer, W, Type>;
     type SerializeStruct = Compound<'writer, W, Type>;
-    type SerializeStructVariant = ser::Impossible<(), Self::Error>;
+    type SerializeStructVariant = StructVariant<'writer, W, Type>;
     fn serialize_map(mut self, _: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
         Tag::Compound.write_alone(&mut self.writer)?;
         Type::write_tag_name(&mut self.writer, b"")?;
-        Ok(Compound {
-            writer: self.writer,
-            phantom: Default::default(),
-        })
+        Ok(Compound::new(self.writer, self.unsigned))
     }
 
     fn serialize_struct(
@@ -84,11 +92,89 @@ where
     ) -> Result<Self::SerializeStruct, Self::Error> {
         Tag::Compound.write_alone(&mut self.writer)?;
         Type::write_tag_name(&mut self.writer, name)?;
-        Ok(Compound {
-            writer: self.writer,
-            phantom: Default::default(),
-        })
+        Ok(Compound::new(self.writer, self.unsigned))
+    }
+
+    fn serialize_newtype_struct<T>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    /// Written as a root compound named after the enum with a single entry named after the variant
+    fn serialize_newtype_variant<T>(
+        mut self,
+        name: &'static str,
+        _: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        Tag::Compound.write_alone(&mut self.writer)?;
+        Type::write_tag_name(&mut self.writer, name)?;
+        let mut serializer: NamedValueSerializer<'_, '_, W, Type, str> = NamedValueSerializer::new(
+            self.writer,
+            StringOrSerializer::String(Cow::Borrowed(variant.as_bytes())),
+            self.unsigned,
+        );
+        value.serialize(&mut serializer)?;
+        Tag::End.write_alone(&mut self.writer)?;
+        Ok(())
+    }
+
+    fn serialize_tuple_variant(
+        mut self,
+        name: &'static str,
+        _: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        Tag::Compound.write_alone(&mut self.writer)?;
+        Type::write_tag_name(&mut self.writer, name)?;
+        Ok(TupleVariant::new(self.writer, variant, len, self.unsigned))
+    }
+
+    fn serialize_struct_variant(
+        mut self,
+        name: &'static str,
+        _: u32,
+        variant: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        Tag::Compound.write_alone(&mut self.writer)?;
+        Type::write_tag_name(&mut self.writer, name)?;
+        Tag::Compound.write_alone(&mut self.writer)?;
+        Type::write_tag_name(&mut self.writer, variant)?;
+        Ok(StructVariant(Compound::new(self.writer, self.unsigned)))
+    }
+
+    /// A document needs a root tag. So there is nothing to write for `None`
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        Err(Error::UnrepresentableValueError(
+            "None can not be the root of a NBT document",
+        ))
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<Self::Ok, Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    /// Written as an empty root compound
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        let compound = self.serialize_map(Some(0))?;
+        ser::SerializeMap::end(compound)
     }
+
     impossible!(
         bool,
         i8,
@@ -99,22 +185,15 @@ where
         u16,
         u32,
         u64,
-        none,
-        some,
         f32,
         f64,
         char,
         str,
         bytes,
-        unit,
         unit_struct,
-        newtype_struct,
-        newtype_variant,
         seq,
         tuple,
         tuple_struct,
-        tuple_variant,
-        struct_variant,
         unit_variant
     );
 }
@@ -131,9 +210,33 @@ where
     for<'str> &'str str: NBTDataType<Type>,
 {
     pub writer: &'writer mut W,
+    /// The key given to `serialize_key` waiting for its value
+    pub key: Option<String>,
+    pub unsigned: UnsignedPolicy,
     pub phantom: std::marker::PhantomData<Type>,
 }
 
+impl<'writer, W: Write, Type: NBTType> Compound<'writer, W, Type>
+where
+    i8: NBTDataType<Type>,
+    i16: NBTDataType<Type>,
+    i32: NBTDataType<Type>,
+    i64: NBTDataType<Type>,
+    f32: NBTDataType<Type>,
+    f64: NBTDataType<Type>,
+    String: NBTDataType<Type>,
+    for<'str> &'str str: NBTDataType<Type>,
+{
+    pub fn new(writer: &'writer mut W, unsigned: UnsignedPolicy) -> Self {
+        Self {
+            writer,
+            key: None,
+            unsigned,
+            phantom: Default::default(),
+        }
+    }
+}
+
 impl<'writer, W: Write, Type: NBTType> ser::SerializeMap for Compound<'writer, W, Type>
 where
     i8: NBTDataType<Type>,
@@ -149,32 +252,42 @@ where
     type Ok = ();
     type Error = Error;
 
-    fn serialize_key<T: ?Sized>(&mut self, _key: &T) -> Result<(), Self::Error>
+    /// The key is buffered until its value is serialized
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), Self::Error>
     where
-        T: serde::Serialize,
+        T: ?Sized + serde::Serialize,
     {
-        unimplemented!()
+        self.key = Some(key.serialize(MapKey)?);
+        Ok(())
     }
 
-    fn serialize_value<T: ?Sized>(&mut self, _value: &T) -> Result<(), Self::Error>
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), Self::Error>
     where
-        T: serde::Serialize,
+        T: ?Sized + serde::Serialize,
     {
-        unimplemented!()
+        let key = self.key.take().ok_or_else(|| {
+            Error::Custom("serialize_value called before serialize_key".to_string())
+        })?;
+        let mut serializer: NamedValueSerializer<'_, '_, W, Type, str> = NamedValueSerializer::new(
+            self.writer,
+            StringOrSerializer::String(Cow::Owned(key.into_bytes())),
+            self.unsigned,
+        );
+        value.serialize(&mut serializer)
     }
 
-    fn serialize_entry<K: ?Sized, V: ?Sized>(
+    fn serialize_entry<K, V>(
         &mut self,
         key: &K,
         value: &V,
     ) -> Result<(), Self::Error>
     where
-        K: Serialize,
-        V: Serialize,
+        K: ?Sized + Serialize,
+        V: ?Sized + Serialize,
     {
         let serializer = StringOrSerializer::Serializer(key);
         let mut serializer1: NamedValueSerializer<'_, '_, W, Type, K> =
-            NamedValueSerializer::new(self.writer, serializer);
+            NamedValueSerializer::new(self.writer, serializer, self.unsigned);
         value.serialize(&mut serializer1)
     }
 
@@ -199,54 +312,18 @@ where
     type Ok = ();
     type Error = super::Error;
 
-    fn serialize_field<T: ?Sized>(
-        &mut self,
-        key: &'static str,
-        value: &T,
-    ) -> Result<(), Self::Error>
-    where
-        T: Serialize,
-    {
-        let serializer: StringOrSerializer<'static, &str> =
-            StringOrSerializer::String(Cow::Borrowed(key.as_bytes()));
-        let mut serializer1: NamedValueSerializer<'_, '_, W, Type, &str> =
-            NamedValueSerializer::new(self.writer, serializer);
-        value.serialize(&mut serializer1)
-    }
-
-    fn end(mut self) -> Result<Self::Ok, Self::Error> {
-        Tag::End.write_alone(&mut self.writer)?;
-        Ok(())
-    }
-}
-
-impl<'writer, W: Write, Type: NBTType> ser::SerializeStructVariant for Compound<'writer, W, Type>
-where
-    i8: NBTDataType<Type>,
-    i16: NBTDataType<Type>,
-    i32: NBTDataType<Type>,
-    i64: NBTDataType<Type>,
-    f32: NBTDataType<Type>,
-    f64: NBTDataType<Type>,
-    String: NBTDataType<Type>,
-    bool: NBTDataType<Type>,
-    for<'str> &'str str: NBTDataType<Type>,
-{
-    type Ok = ();
-    type Error = super::Error;
-
-    fn serialize_field<T: ?Sized>(
+    fn serialize_field<T>(
         &mut self,
         key: &'static str,
         value: &T,
     ) -> Result<(), Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         let serializer: StringOrSerializer<'static, &str> =
             StringOrSerializer::String(Cow::Borrowed(key.as_bytes()));
         let mut serializer1: NamedValueSerializer<'_, '_, W, Type, &str> =
-            NamedValueSerializer::new(self.writer, serializer);
+            NamedValueSerializer::new(self.writer, serializer, self.unsigned);
         value.serialize(&mut serializer1)
     }
 
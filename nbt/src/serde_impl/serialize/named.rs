@@ -1,14 +1,14 @@
-use crate::serde_impl::serialize::macros::{
-    gen_method_body, impossible, method_body, name_impossible,
-};
+use crate::serde_impl::arrays::SequenceEncoding;
+use crate::serde_impl::key::MapKey;
 use crate::serde_impl::serialize::sequence::SerializeSeq;
-use crate::serde_impl::serialize::{cast_and_write, Compound};
+use crate::serde_impl::serialize::variant::{StructVariant, TupleVariant};
+use crate::serde_impl::serialize::Compound;
+use crate::serde_impl::unsigned::{write_unsigned_with, UnsignedPolicy};
 use crate::serde_impl::Error;
 use crate::{NBTDataType, NBTType, Tag};
 use serde::{ser, Serialize, Serializer};
 use std::borrow::Cow;
 use std::io::Write;
-use std::marker::PhantomData;
 
 pub enum StringOrSerializer<'data, K: Serialize + ?Sized> {
     String(Cow<'data, [u8]>),
@@ -16,6 +16,19 @@ pub enum StringOrSerializer<'data, K: Serialize + ?Sized> {
     None,
 }
 
+impl<K: Serialize + ?Sized> StringOrSerializer<'_, K> {
+    /// The name of the entry. A map key is turned into a string by [MapKey]
+    pub(crate) fn name(&self) -> Result<Option<Cow<'_, [u8]>>, Error> {
+        Ok(match self {
+            StringOrSerializer::String(name) => Some(Cow::Borrowed(name.as_ref())),
+            StringOrSerializer::Serializer(key) => {
+                Some(Cow::Owned(key.serialize(MapKey)?.into_bytes()))
+            }
+            StringOrSerializer::None => None,
+        })
+    }
+}
+
 pub struct NamedValueSerializer<
     'writer,
     'name: 'writer,
@@ -27,6 +40,9 @@ pub struct NamedValueSerializer<
 {
     pub target: &'writer mut W,
     pub name: StringOrSerializer<'name, K>,
+    /// Set by the adapters in [crate::serde_impl::arrays]
+    pub encoding: SequenceEncoding,
+    pub unsigned: UnsignedPolicy,
     pub phantom: std::marker::PhantomData<Type>,
 }
 
@@ -36,52 +52,44 @@ where
     'writer: 'name,
     &'name str: NBTDataType<Type>,
 {
-    pub fn new(target: &'writer mut W, name: StringOrSerializer<'name, K>) -> Self {
+    pub fn new(
+        target: &'writer mut W,
+        name: StringOrSerializer<'name, K>,
+        unsigned: UnsignedPolicy,
+    ) -> Self {
         Self {
             target,
             name,
+            encoding: SequenceEncoding::Default,
+            unsigned,
             phantom: Default::default(),
         }
     }
+    /// The encoding of a sequence was picked for a value that is not a sequence
+    #[inline]
+    fn not_a_sequence(&self, tag: Tag) -> Result<(), Error> {
+        if self.encoding == SequenceEncoding::Default {
+            Ok(())
+        } else {
+            Err(Error::IncorrectTagError(Tag::List, tag))
+        }
+    }
     #[inline]
     pub fn write<Data: NBTDataType<Type>>(&mut self, value: Data) -> Result<(), Error> {
+        self.not_a_sequence(Data::get_tag())?;
         Data::get_tag().write_alone(self.target)?;
-        match &self.name {
-            StringOrSerializer::String(name) => {
-                Type::write_tag_name(self.target, name)?;
-            }
-            StringOrSerializer::Serializer(name) => {
-                let get_name = GetName::<'_, W, Type> {
-                    target: self.target,
-                    phantom: Default::default(),
-                };
-                name.serialize(get_name)?;
-            }
-            StringOrSerializer::None => {}
+        if let Some(name) = self.name.name()? {
+            Type::write_tag_name(self.target, name)?;
         }
         value.write_alone(self.target)?;
         Ok(())
     }
     #[inline]
     pub fn write_tag(&mut self, value: Tag) -> Result<(), Error> {
-        match &self.name {
-            StringOrSerializer::String(name) => {
-                value.write_alone(&mut self.target)?;
-                Type::write_tag_name(&mut self.target, name.as_ref())?;
-            }
-            StringOrSerializer::Serializer(name) => {
-                value.write_alone(&mut self.target)?;
-                let name_getter: GetName<'_, W, Type> = GetName {
-                    target: self.target,
-                    phantom: Default::default(),
-                };
-                name.serialize(name_getter)?;
-            }
-            _ => {
-                value.write_alone(&mut self.target)?;
-                Type::write_tag_name(&mut self.target, b"")?;
-            }
-        }
+        self.not_a_sequence(value)?;
+        value.write_alone(&mut self.target)?;
+        let name = self.name.name()?;
+        Type::write_tag_name(&mut self.target, name.as_deref().unwrap_or(b""))?;
         Ok(())
     }
 }
@@ -103,12 +111,12 @@ where
     type Ok = ();
     type Error = Error;
     type SerializeSeq = SerializeSeq<'writer, 'name, W, Type, K>;
-    type SerializeTuple = ser::Impossible<(), Self::Error>;
-    type SerializeTupleStruct = ser::Impossible<(), Self::Error>;
-    type SerializeTupleVariant = ser::Impossible<(), Self::Error>;
+    type SerializeTuple = SerializeSeq<'writer, 'name, W, Type, K>;
+    type SerializeTupleStruct = SerializeSeq<'writer, 'name, W, Type, K>;
+    type SerializeTupleVariant = TupleVariant<'writer, W, Type>;
     type SerializeMap = Compound<'writer, W, Type>;
     type SerializeStruct = Compound<'writer, W, Type>;
-    type SerializeStructVariant = ser::Impossible<(), Self::Error>;
+    type SerializeStructVariant = StructVariant<'writer, W, Type>;
 
     fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
         self.write(v)
@@ -130,7 +138,7 @@ where
         self.write(v)
     }
 
-    cast_and_write!();
+    write_unsigned_with!(unsigned);
 
     fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
         self.write(v)
@@ -140,16 +148,22 @@ where
         self.write(v)
     }
 
-    fn serialize_char(self, _v: char) -> Result<Self::Ok, Self::Error> {
-        todo!("serialize_char")
+    /// A string holding the single character
+    fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
+        self.write(&*v.encode_utf8(&mut [0; 4]))
     }
 
     fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
         self.write(v)
     }
 
-    fn serialize_bytes(self, _v: &[u8]) -> Result<Self::Ok, Self::Error> {
-        todo!("serialize_bytes")
+    /// Written like a sequence of i8. So a TAG_Byte_Array by default
+    fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
+        let mut seq = self.serialize_seq(Some(v.len()))?;
+        for byte in v {
+            ser::SerializeSeq::serialize_element(&mut seq, &(*byte as i8))?;
+        }
+        ser::SerializeSeq::end(seq)
     }
     fn serialize_unit_variant(
         self,
@@ -166,6 +180,9 @@ where
                 name: &mut self.name,
                 wrote_header: false,
                 length: len as i32,
+                encoding: self.encoding,
+                unsigned: self.unsigned,
+                element: None,
                 phantom: Default::default(),
             })
         } else {
@@ -177,10 +194,7 @@ where
 
     fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
         self.write_tag(Tag::Compound)?;
-        Ok(Compound {
-            writer: self.target,
-            phantom: Default::default(),
-        })
+        Ok(Compound::new(self.target, self.unsigned))
     }
 
     fn serialize_struct(
@@ -189,86 +203,101 @@ where
         _len: usize,
     ) -> Result<Self::SerializeStruct, Self::Error> {
         self.write_tag(Tag::Compound)?;
-        Ok(Compound {
-            writer: self.target,
-            phantom: Default::default(),
-        })
+        Ok(Compound::new(self.target, self.unsigned))
     }
 
-    impossible!(
-        unit,
-        newtype_struct,
-        newtype_variant,
-        tuple,
-        tuple_struct,
-        tuple_variant,
-        struct_variant
-    );
+    fn serialize_newtype_variant<T>(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.write_tag(Tag::Compound)?;
+        let mut serializer: NamedValueSerializer<'_, '_, W, Type, str> = NamedValueSerializer::new(
+            self.target,
+            StringOrSerializer::String(Cow::Borrowed(variant.as_bytes())),
+            self.unsigned,
+        );
+        value.serialize(&mut serializer)?;
+        Tag::End.write_alone(self.target)?;
+        Ok(())
+    }
 
-    fn serialize_unit_struct(self, name: &'static str) -> Result<Self::Ok, Self::Error> {
-        self.write(name)
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        self.write_tag(Tag::Compound)?;
+        Ok(TupleVariant::new(self.target, variant, len, self.unsigned))
     }
 
-    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
-        Ok(())
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        self.write_tag(Tag::Compound)?;
+        Tag::Compound.write_alone(self.target)?;
+        Type::write_tag_name(self.target, variant)?;
+        Ok(StructVariant(Compound::new(self.target, self.unsigned)))
     }
 
-    fn serialize_some<T: ?Sized>(self, value: &T) -> Result<Self::Ok, Self::Error>
-        where
-            T: Serialize,
+    /// Written as an empty compound
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        let compound = self.serialize_map(Some(0))?;
+        ser::SerializeMap::end(compound)
+    }
+
+    /// Transparent. Unless it is one of the adapters in [crate::serde_impl::arrays]
+    fn serialize_newtype_struct<T>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T: ?Sized + Serialize,
     {
+        if let Some(encoding) = SequenceEncoding::from_token(name) {
+            self.encoding = encoding;
+        }
         value.serialize(self)
     }
-}
 
-#[derive(Debug)]
-pub struct GetName<'writer, W: Write, Type: NBTType> {
-    pub(crate) target: &'writer mut W,
-    pub(crate) phantom: PhantomData<Type>,
-}
+    /// Tuples are written as lists. Just like sequences
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
 
-impl<'writer, W: Write, Type: NBTType> Serializer for GetName<'writer, W, Type> {
-    type Ok = ();
-    type Error = Error;
-    type SerializeSeq = ser::Impossible<Self::Ok, Self::Error>;
-    type SerializeTuple = ser::Impossible<Self::Ok, Self::Error>;
-    type SerializeTupleStruct = ser::Impossible<Self::Ok, Self::Error>;
-    type SerializeTupleVariant = ser::Impossible<Self::Ok, Self::Error>;
-    type SerializeMap = ser::Impossible<Self::Ok, Self::Error>;
-    type SerializeStruct = ser::Impossible<Self::Ok, Self::Error>;
-    type SerializeStructVariant = ser::Impossible<Self::Ok, Self::Error>;
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
 
-    fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
-        Type::write_tag_name(self.target, v)?;
+    fn serialize_unit_struct(self, name: &'static str) -> Result<Self::Ok, Self::Error> {
+        self.write(name)
+    }
+
+    /// The entry is left out of the compound
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
         Ok(())
     }
-    name_impossible!(
-        bool,
-        i8,
-        i16,
-        i32,
-        i64,
-        u8,
-        u16,
-        u32,
-        u64,
-        none,
-        some,
-        f32,
-        f64,
-        char,
-        bytes,
-        unit,
-        unit_struct,
-        newtype_struct,
-        newtype_variant,
-        seq,
-        tuple,
-        tuple_struct,
-        tuple_variant,
-        struct_variant,
-        unit_variant,
-        map,
-        struct
-    );
+
+    fn serialize_some<T>(self, value: &T) -> Result<Self::Ok, Self::Error>
+        where
+            T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
 }
@@ -1,12 +1,15 @@
 mod sub_list;
 
-use crate::serde_impl::serialize::macros::{gen_method_body, impossible, method_body};
-use crate::serde_impl::serialize::named::{GetName, StringOrSerializer};
-use crate::serde_impl::serialize::{cast_and_write, Compound};
+use crate::serde_impl::arrays::SequenceEncoding;
+use crate::serde_impl::serialize::named::{NamedValueSerializer, StringOrSerializer};
+use crate::serde_impl::serialize::variant::{StructVariant, TupleVariant};
+use crate::serde_impl::serialize::Compound;
+use crate::serde_impl::unsigned::{write_unsigned_with, UnsignedPolicy};
 use crate::serde_impl::Error;
 use crate::{ListWriter, NBTDataType, NBTError, NBTType, Tag};
 use serde::{ser, Serialize};
 
+use std::borrow::Cow;
 use std::io::Write;
 
 pub struct SerializeSeq<'writer, 'name: 'writer, W: Write, Type: NBTType, K: Serialize + ?Sized>
@@ -25,9 +28,24 @@ where
     pub(crate) name: &'name mut StringOrSerializer<'name, K>,
     pub(crate) wrote_header: bool,
     pub(crate) length: i32,
+    pub(crate) encoding: SequenceEncoding,
+    pub(crate) unsigned: UnsignedPolicy,
+    /// The tag of the elements once the first one is written
+    pub(crate) element: Option<Tag>,
     pub(crate) phantom: std::marker::PhantomData<Type>,
 }
 
+/// Records the tag of the first element of a list. Fails if a later element has another tag
+pub(crate) fn check_element(element: &mut Option<Tag>, tag: Tag) -> Result<(), Error> {
+    match *element {
+        Some(expected) if expected != tag => Err(NBTError::ListTypeMismatch(expected, tag).into()),
+        _ => {
+            *element = Some(tag);
+            Ok(())
+        }
+    }
+}
+
 impl<'writer, 'name: 'writer, W: Write, Type: NBTType, K: Serialize + ?Sized> ser::SerializeSeq
     for SerializeSeq<'writer, 'name, W, Type, K>
 where
@@ -44,9 +62,9 @@ where
     type Ok = ();
     type Error = super::Error;
 
-    fn serialize_element<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         if !self.wrote_header {
             let mut inner: SerializeSeqInner<'_, '_, W, Type, K> = SerializeSeqInner {
@@ -54,6 +72,10 @@ where
                 name: self.name,
                 length: self.length,
                 wrote_header: false,
+                encoding: self.encoding,
+                unsigned: self.unsigned,
+                element: &mut self.element,
+                nested: SequenceEncoding::Default,
                 phantom: Default::default(),
             };
             value.serialize(&mut inner)?;
@@ -66,6 +88,10 @@ where
                 name: self.name,
                 length: self.length,
                 wrote_header: true,
+                encoding: self.encoding,
+                unsigned: self.unsigned,
+                element: &mut self.element,
+                nested: SequenceEncoding::Default,
                 phantom: Default::default(),
             };
             value.serialize(&mut inner)?;
@@ -80,17 +106,85 @@ where
                 name: self.name,
                 length: 0,
                 wrote_header: false,
+                encoding: self.encoding,
+                unsigned: self.unsigned,
+                element: &mut None,
+                nested: SequenceEncoding::Default,
                 phantom: Default::default(),
             };
-            Tag::List.write_alone(inner.outer)?;
-            inner.write_name()?;
-            Tag::End.write_alone(self.outer)?;
+            match self.encoding.array_tag() {
+                Some(array) => {
+                    array.write_alone(inner.outer)?;
+                    inner.write_name()?;
+                }
+                None => {
+                    Tag::List.write_alone(inner.outer)?;
+                    inner.write_name()?;
+                    Tag::End.write_alone(self.outer)?;
+                }
+            }
             0.write_alone(self.outer)?;
         }
         Ok(())
     }
 }
 
+impl<'writer, 'name: 'writer, W: Write, Type: NBTType, K: Serialize + ?Sized> ser::SerializeTuple
+    for SerializeSeq<'writer, 'name, W, Type, K>
+where
+    i8: NBTDataType<Type>,
+    i16: NBTDataType<Type>,
+    i32: NBTDataType<Type>,
+    i64: NBTDataType<Type>,
+    f32: NBTDataType<Type>,
+    f64: NBTDataType<Type>,
+    String: NBTDataType<Type>,
+    bool: NBTDataType<Type>,
+    for<'str> &'str str: NBTDataType<Type>,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl<'writer, 'name: 'writer, W: Write, Type: NBTType, K: Serialize + ?Sized> ser::SerializeTupleStruct
+    for SerializeSeq<'writer, 'name, W, Type, K>
+where
+    i8: NBTDataType<Type>,
+    i16: NBTDataType<Type>,
+    i32: NBTDataType<Type>,
+    i64: NBTDataType<Type>,
+    f32: NBTDataType<Type>,
+    f64: NBTDataType<Type>,
+    String: NBTDataType<Type>,
+    bool: NBTDataType<Type>,
+    for<'str> &'str str: NBTDataType<Type>,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
 pub struct SerializeSeqInner<
     'writer,
     'name: 'writer,
@@ -104,6 +198,11 @@ pub struct SerializeSeqInner<
     pub(crate) name: &'name StringOrSerializer<'name, K>,
     pub(crate) length: i32,
     pub(crate) wrote_header: bool,
+    pub(crate) encoding: SequenceEncoding,
+    pub(crate) unsigned: UnsignedPolicy,
+    pub(crate) element: &'writer mut Option<Tag>,
+    /// The encoding of the element if it is a sequence. Set by the token of a newtype struct
+    pub(crate) nested: SequenceEncoding,
     pub(crate) phantom: std::marker::PhantomData<Type>,
 }
 
@@ -114,43 +213,19 @@ where
 {
     #[inline]
     pub fn write<Data: NBTDataType<Type>>(&mut self, data: Data) -> Result<(), Error> {
+        check_element(self.element, Data::get_tag())?;
         if !self.wrote_header {
+            let list_type = self
+                .encoding
+                .list_type(Data::get_tag(), Data::get_list_tag())?;
+            let name = self.name.name()?;
             Type::ListWriter::<'writer, W>::write_sequence_header_name_callback(
                 self.outer,
-                Data::get_list_tag(),
+                list_type,
                 self.length,
                 |w| {
-                    match &self.name {
-                        StringOrSerializer::String(name) => {
-                            Type::write_tag_name(w, name)?;
-                        }
-                        StringOrSerializer::Serializer(name) => {
-                            let name_getter: GetName<'_, W, Type> = GetName {
-                                target: w,
-                                phantom: Default::default(),
-                            };
-                            if let Err(error) = name.serialize(name_getter) {
-                                match error {
-                                    Error::IO(io) => {
-                                        return Err(NBTError::IO(io));
-                                    }
-                                    Error::KeyMustBeString => {
-                                        return Err(NBTError::KeyMustBeString);
-                                    }
-                                    Error::NBTErr(err) => {
-                                        return Err(err);
-                                    }
-                                    Error::FromStrError(v) => {
-                                        return Err(NBTError::NotAString(v));
-                                    }
-                                    _ => {
-                                        // These errors should never happen
-                                        panic!("Unexpected error: {:?}", error);
-                                    }
-                                }
-                            }
-                        }
-                        _ => {}
+                    if let Some(name) = &name {
+                        Type::write_tag_name(w, name)?;
                     }
                     Ok(())
                 },
@@ -160,38 +235,21 @@ where
 
         Ok(())
     }
-    pub(crate) fn write_name(&mut self) -> Result<(), NBTError> {
-        match &self.name {
-            StringOrSerializer::String(name) => {
-                Type::write_tag_name(&mut self.outer, name)?;
-            }
-            StringOrSerializer::Serializer(name) => {
-                let name_getter: GetName<'_, W, Type> = GetName {
-                    target: self.outer,
-                    phantom: Default::default(),
-                };
-                if let Err(error) = name.serialize(name_getter) {
-                    match error {
-                        Error::IO(io) => {
-                            return Err(NBTError::IO(io));
-                        }
-                        Error::KeyMustBeString => {
-                            return Err(NBTError::KeyMustBeString);
-                        }
-                        Error::NBTErr(err) => {
-                            return Err(err);
-                        }
-                        Error::FromStrError(v) => {
-                            return Err(NBTError::NotAString(v));
-                        }
-                        _ => {
-                            // These errors should never happen
-                            panic!("Unexpected error: {:?}", error);
-                        }
-                    }
-                }
-            }
-            _ => {}
+    /// Writes the header of a list of compounds if this is the first element
+    pub(crate) fn compound_header(&mut self) -> Result<(), Error> {
+        self.encoding.check(Tag::Compound)?;
+        check_element(self.element, Tag::Compound)?;
+        if !self.wrote_header {
+            Tag::List.write_alone(&mut self.outer)?;
+            self.write_name()?;
+            Tag::Compound.write_alone(&mut self.outer)?;
+            self.length.write_alone(&mut self.outer)?;
+        }
+        Ok(())
+    }
+    pub(crate) fn write_name(&mut self) -> Result<(), Error> {
+        if let Some(name) = self.name.name()? {
+            Type::write_tag_name(&mut self.outer, name)?;
         }
         Ok(())
     }
@@ -213,22 +271,90 @@ where
     type Ok = ();
     type Error = Error;
     type SerializeSeq = sub_list::SubList<'writer, W, Type>;
-    type SerializeTuple = ser::Impossible<(), Self::Error>;
-    type SerializeTupleStruct = ser::Impossible<(), Self::Error>;
-    type SerializeTupleVariant = ser::Impossible<(), Self::Error>;
+    type SerializeTuple = sub_list::SubList<'writer, W, Type>;
+    type SerializeTupleStruct = sub_list::SubList<'writer, W, Type>;
+    type SerializeTupleVariant = TupleVariant<'writer, W, Type>;
     type SerializeMap = Compound<'writer, W, Type>;
     type SerializeStruct = Compound<'writer, W, Type>;
-    type SerializeStructVariant = ser::Impossible<(), Self::Error>;
-
-    impossible!(
-        unit,
-        newtype_struct,
-        newtype_variant,
-        tuple,
-        tuple_struct,
-        tuple_variant,
-        struct_variant
-    );
+    type SerializeStructVariant = StructVariant<'writer, W, Type>;
+
+    /// Written as an empty compound
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        let compound = self.serialize_map(Some(0))?;
+        ser::SerializeMap::end(compound)
+    }
+
+    fn serialize_newtype_struct<T>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        if let Some(encoding) = SequenceEncoding::from_token(name) {
+            self.nested = encoding;
+        }
+        value.serialize(self)
+    }
+
+    /// Tuples are written as lists. Just like sequences
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.compound_header()?;
+        let mut serializer: NamedValueSerializer<'_, '_, W, Type, str> = NamedValueSerializer::new(
+            self.outer,
+            StringOrSerializer::String(Cow::Borrowed(variant.as_bytes())),
+            self.unsigned,
+        );
+        value.serialize(&mut serializer)?;
+        Tag::End.write_alone(self.outer)?;
+        Ok(())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        self.compound_header()?;
+        Ok(TupleVariant::new(self.outer, variant, len, self.unsigned))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        self.compound_header()?;
+        Tag::Compound.write_alone(self.outer)?;
+        Type::write_tag_name(self.outer, variant)?;
+        Ok(StructVariant(Compound::new(self.outer, self.unsigned)))
+    }
     fn serialize_unit_variant(
         self,
         _: &'static str,
@@ -237,13 +363,16 @@ where
     ) -> Result<Self::Ok, Self::Error> {
         self.write(variant)
     }
+    /// A list can not have holes. Filter the `None` values out before serializing
     fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
-        Ok(())
+        Err(Error::UnrepresentableValueError(
+            "None can not be an element of a list",
+        ))
     }
 
-    fn serialize_some<T: ?Sized>(self, value: &T) -> Result<Self::Ok, Self::Error>
+    fn serialize_some<T>(self, value: &T) -> Result<Self::Ok, Self::Error>
         where
-            T: Serialize,
+            T: ?Sized + Serialize,
     {
         value.serialize(self)
     }
@@ -270,7 +399,7 @@ where
     fn serialize_unit_struct(self, name: &'static str) -> Result<Self::Ok, Self::Error> {
         self.write(name)
     }
-    cast_and_write!();
+    write_unsigned_with!(unsigned);
     fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
         self.write(v)
     }
@@ -279,19 +408,26 @@ where
         self.write(v)
     }
 
-    fn serialize_char(self, _v: char) -> Result<Self::Ok, Self::Error> {
-        todo!("serialize_char")
+    /// A string holding the single character
+    fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
+        self.write(&*v.encode_utf8(&mut [0; 4]))
     }
 
     fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
         self.write(v)
     }
 
-    fn serialize_bytes(self, _v: &[u8]) -> Result<Self::Ok, Self::Error> {
-        todo!("serialize_bytes")
+    /// Written like a sequence of i8. So a TAG_Byte_Array by default
+    fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
+        let mut seq = self.serialize_seq(Some(v.len()))?;
+        for byte in v {
+            ser::SerializeSeq::serialize_element(&mut seq, &(*byte as i8))?;
+        }
+        ser::SerializeSeq::end(seq)
     }
 
     fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        self.encoding.check(Tag::List)?;
         if let Some(len) = len {
             if !self.wrote_header {
                 Tag::List.write_alone(&mut self.outer)?;
@@ -303,6 +439,10 @@ where
                 wrote_parent_header: self.wrote_header,
                 parent_size: self.length,
                 length: len as i32,
+                encoding: self.nested,
+                unsigned: self.unsigned,
+                element: None,
+                parent_element: self.element,
                 phantom: Default::default(),
             };
             Ok(list)
@@ -314,17 +454,9 @@ where
     }
 
     fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
-        if !self.wrote_header {
-            Tag::List.write_alone(&mut self.outer)?;
-            self.write_name()?;
-            Tag::Compound.write_alone(&mut self.outer)?;
-            self.length.write_alone(&mut self.outer)?;
-        }
+        self.compound_header()?;
 
-        Ok(Compound {
-            writer: self.outer,
-            phantom: Default::default(),
-        })
+        Ok(Compound::new(self.outer, self.unsigned))
     }
 
     fn serialize_struct(
@@ -332,16 +464,8 @@ where
         _name: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeStruct, Self::Error> {
-        if !self.wrote_header {
-            Tag::List.write_alone(&mut self.outer)?;
-            self.write_name()?;
-            Tag::Compound.write_alone(&mut self.outer)?;
-            self.length.write_alone(&mut self.outer)?;
-        }
+        self.compound_header()?;
 
-        Ok(Compound {
-            writer: self.outer,
-            phantom: Default::default(),
-        })
+        Ok(Compound::new(self.outer, self.unsigned))
     }
 }
@@ -1,10 +1,15 @@
-use crate::serde_impl::serialize::macros::{gen_method_body, impossible, method_body};
+use crate::serde_impl::arrays::SequenceEncoding;
+use crate::serde_impl::serialize::named::{NamedValueSerializer, StringOrSerializer};
+use crate::serde_impl::serialize::sequence::check_element;
+use crate::serde_impl::serialize::variant::{StructVariant, TupleVariant};
 
-use crate::serde_impl::serialize::{cast_and_write, Compound};
+use crate::serde_impl::serialize::Compound;
+use crate::serde_impl::unsigned::{write_unsigned_with, UnsignedPolicy};
 use crate::serde_impl::Error;
 use crate::{NBTDataType, NBTType, Tag};
 use serde::{ser, Serialize};
 
+use std::borrow::Cow;
 use std::io::Write;
 
 pub struct SubList<'writer, W: Write, Type: NBTType>
@@ -24,6 +29,12 @@ where
     pub(crate) length: i32,
     pub(crate) wrote_parent_header: bool,
     pub(crate) parent_size: i32,
+    pub(crate) encoding: SequenceEncoding,
+    pub(crate) unsigned: UnsignedPolicy,
+    /// The tag of the elements once the first one is written
+    pub(crate) element: Option<Tag>,
+    /// The tag of the elements of the parent list. This list is one of them
+    pub(crate) parent_element: &'writer mut Option<Tag>,
     pub(crate) phantom: std::marker::PhantomData<Type>,
 }
 
@@ -42,9 +53,9 @@ where
     type Ok = ();
     type Error = super::Error;
 
-    fn serialize_element<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         if !self.wrote_header {
             let mut inner: SerializeSeqInner<'_, W, Type> = SerializeSeqInner {
@@ -53,6 +64,11 @@ where
                 wrote_header: false,
                 wrote_parent_header: self.wrote_parent_header,
                 parent_size: self.parent_size,
+                encoding: self.encoding,
+                unsigned: self.unsigned,
+                element: &mut self.element,
+                parent_element: self.parent_element,
+                nested: SequenceEncoding::Default,
                 phantom: Default::default(),
             };
             value.serialize(&mut inner)?;
@@ -64,6 +80,11 @@ where
                 wrote_header: true,
                 wrote_parent_header: self.wrote_parent_header,
                 parent_size: self.parent_size,
+                encoding: self.encoding,
+                unsigned: self.unsigned,
+                element: &mut self.element,
+                parent_element: self.parent_element,
+                nested: SequenceEncoding::Default,
                 phantom: Default::default(),
             };
             value.serialize(&mut inner)?;
@@ -76,14 +97,85 @@ where
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
         if !self.wrote_header {
-            println!("Writing Empty List");
-            Tag::End.write_alone(self.outer)?;
+            // An empty array when the encoding or the earlier elements are arrays
+            let tag = match (self.encoding, *self.parent_element) {
+                (
+                    SequenceEncoding::Default,
+                    Some(array @ (Tag::ByteArray | Tag::IntArray | Tag::LongArray)),
+                ) => array,
+                (encoding, _) => encoding.array_tag().unwrap_or(Tag::List),
+            };
+            check_element(self.parent_element, tag)?;
+            // The parent list has not had its header written. So this list is the first element
+            if !self.wrote_parent_header {
+                tag.write_alone(self.outer)?;
+                self.parent_size.write_alone(self.outer)?;
+            }
+            if tag == Tag::List {
+                Tag::End.write_alone(self.outer)?;
+            }
             0.write_alone(self.outer)?;
         }
         Ok(())
     }
 }
 
+impl<'writer, W: Write, Type: NBTType> ser::SerializeTuple
+    for SubList<'writer, W, Type>
+where
+    i8: NBTDataType<Type>,
+    i16: NBTDataType<Type>,
+    i32: NBTDataType<Type>,
+    i64: NBTDataType<Type>,
+    f32: NBTDataType<Type>,
+    f64: NBTDataType<Type>,
+    String: NBTDataType<Type>,
+    bool: NBTDataType<Type>,
+    for<'str> &'str str: NBTDataType<Type>,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl<'writer, W: Write, Type: NBTType> ser::SerializeTupleStruct
+    for SubList<'writer, W, Type>
+where
+    i8: NBTDataType<Type>,
+    i16: NBTDataType<Type>,
+    i32: NBTDataType<Type>,
+    i64: NBTDataType<Type>,
+    f32: NBTDataType<Type>,
+    f64: NBTDataType<Type>,
+    String: NBTDataType<Type>,
+    bool: NBTDataType<Type>,
+    for<'str> &'str str: NBTDataType<Type>,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
 pub struct SerializeSeqInner<'writer, W: Write, Type: NBTType>
 where
     i8: NBTDataType<Type>,
@@ -100,6 +192,12 @@ where
     pub(crate) wrote_header: bool,
     pub(crate) wrote_parent_header: bool,
     pub(crate) parent_size: i32,
+    pub(crate) encoding: SequenceEncoding,
+    pub(crate) unsigned: UnsignedPolicy,
+    pub(crate) element: &'writer mut Option<Tag>,
+    pub(crate) parent_element: &'writer mut Option<Tag>,
+    /// The encoding of the element if it is a sequence. Set by the token of a newtype struct
+    pub(crate) nested: SequenceEncoding,
     pub(crate) phantom: std::marker::PhantomData<Type>,
 }
 
@@ -114,44 +212,43 @@ where
     String: NBTDataType<Type>,
     bool: NBTDataType<Type>,
 {
+    /// Writes the header of the parent list if this list is its first element
     #[inline]
-    fn parent(&mut self, tag: Tag) -> Result<(), Error> {
+    fn parent(&mut self, list: Tag) -> Result<(), Error> {
+        check_element(self.parent_element, list)?;
         if !self.wrote_parent_header {
-            match tag {
-                Tag::Byte => {
-                    Tag::ByteArray.write_alone(&mut self.outer)?;
-                    self.parent_size.write_alone(&mut self.outer)?;
-                }
-                Tag::Int => {
-                    Tag::IntArray.write_alone(&mut self.outer)?;
-                    self.parent_size.write_alone(&mut self.outer)?;
-                }
-                Tag::Long => {
-                    Tag::LongArray.write_alone(&mut self.outer)?;
-                    self.parent_size.write_alone(&mut self.outer)?;
-                }
-                _ => {
-                    Tag::List.write_alone(&mut self.outer)?;
-                    self.parent_size.write_alone(&mut self.outer)?;
-                }
-            }
+            list.write_alone(&mut self.outer)?;
+            self.parent_size.write_alone(&mut self.outer)?;
         }
         Ok(())
     }
+    /// Checks the element and writes the header of this list if this is its first element.
+    ///
+    /// By default this list is an array of bytes, ints or longs unless the earlier elements of
+    /// the parent are lists
     #[inline]
-    pub fn write<Data: NBTDataType<Type>>(&mut self, data: Data) -> Result<(), Error> {
-        self.parent(Data::get_tag())?;
+    fn header(&mut self, tag: Tag) -> Result<(), Error> {
+        self.encoding.check(tag)?;
+        check_element(self.element, tag)?;
+        let list = match (self.encoding, tag) {
+            (SequenceEncoding::Default, _) if *self.parent_element == Some(Tag::List) => Tag::List,
+            (SequenceEncoding::Default, Tag::Byte) => Tag::ByteArray,
+            (SequenceEncoding::Default, Tag::Int) => Tag::IntArray,
+            (SequenceEncoding::Default, Tag::Long) => Tag::LongArray,
+            (encoding, _) => encoding.array_tag().unwrap_or(Tag::List),
+        };
+        self.parent(list)?;
         if !self.wrote_header {
-            match Data::get_tag() {
-                Tag::Byte | Tag::Int | Tag::Long => {
-                    self.length.write_alone(&mut self.outer)?;
-                }
-                v => {
-                    v.write_alone(&mut self.outer)?;
-                    self.length.write_alone(&mut self.outer)?;
-                }
+            if list == Tag::List {
+                tag.write_alone(&mut self.outer)?;
             }
+            self.length.write_alone(&mut self.outer)?;
         }
+        Ok(())
+    }
+    #[inline]
+    pub fn write<Data: NBTDataType<Type>>(&mut self, data: Data) -> Result<(), Error> {
+        self.header(Data::get_tag())?;
         data.write_alone(&mut self.outer)?;
 
         Ok(())
@@ -174,22 +271,83 @@ where
     type Ok = ();
     type Error = Error;
     type SerializeSeq = SubList<'writer, W, Type>;
-    type SerializeTuple = ser::Impossible<(), Self::Error>;
-    type SerializeTupleStruct = ser::Impossible<(), Self::Error>;
-    type SerializeTupleVariant = ser::Impossible<(), Self::Error>;
+    type SerializeTuple = SubList<'writer, W, Type>;
+    type SerializeTupleStruct = SubList<'writer, W, Type>;
+    type SerializeTupleVariant = TupleVariant<'writer, W, Type>;
     type SerializeMap = Compound<'writer, W, Type>;
     type SerializeStruct = Compound<'writer, W, Type>;
-    type SerializeStructVariant = ser::Impossible<(), Self::Error>;
-
-    impossible!(
-        unit,
-        newtype_struct,
-        newtype_variant,
-        tuple,
-        tuple_struct,
-        tuple_variant,
-        struct_variant
-    );
+    type SerializeStructVariant = StructVariant<'writer, W, Type>;
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.header(Tag::Compound)?;
+        let mut serializer: NamedValueSerializer<'_, '_, W, Type, str> = NamedValueSerializer::new(
+            self.outer,
+            StringOrSerializer::String(Cow::Borrowed(variant.as_bytes())),
+            self.unsigned,
+        );
+        value.serialize(&mut serializer)?;
+        Tag::End.write_alone(self.outer)?;
+        Ok(())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        self.header(Tag::Compound)?;
+        Ok(TupleVariant::new(self.outer, variant, len, self.unsigned))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        self.header(Tag::Compound)?;
+        Tag::Compound.write_alone(self.outer)?;
+        Type::write_tag_name(self.outer, variant)?;
+        Ok(StructVariant(Compound::new(self.outer, self.unsigned)))
+    }
+
+    /// Tuples are written as lists. Just like sequences
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+    fn serialize_newtype_struct<T>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        if let Some(encoding) = SequenceEncoding::from_token(name) {
+            self.nested = encoding;
+        }
+        value.serialize(self)
+    }
     fn serialize_unit_variant(
         self,
         _: &'static str,
@@ -198,13 +356,22 @@ where
     ) -> Result<Self::Ok, Self::Error> {
         self.write(variant)
     }
+    /// A list can not have holes. Filter the `None` values out before serializing
     fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
-        Ok(())
+        Err(Error::UnrepresentableValueError(
+            "None can not be an element of a list",
+        ))
+    }
+
+    /// Written as an empty compound
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        let compound = self.serialize_map(Some(0))?;
+        ser::SerializeMap::end(compound)
     }
 
-    fn serialize_some<T: ?Sized>(self, value: &T) -> Result<Self::Ok, Self::Error>
+    fn serialize_some<T>(self, value: &T) -> Result<Self::Ok, Self::Error>
         where
-            T: Serialize,
+            T: ?Sized + Serialize,
     {
         value.serialize(self)
     }
@@ -231,7 +398,7 @@ where
         self.write(v)
     }
 
-    cast_and_write!();
+    write_unsigned_with!(unsigned);
     fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
         self.write(v)
     }
@@ -240,31 +407,40 @@ where
         self.write(v)
     }
 
-    fn serialize_char(self, _v: char) -> Result<Self::Ok, Self::Error> {
-        todo!("serialize_char")
+    /// A string holding the single character
+    fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
+        self.write(&*v.encode_utf8(&mut [0; 4]))
     }
 
     fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
         self.write(v)
     }
 
-    fn serialize_bytes(self, _v: &[u8]) -> Result<Self::Ok, Self::Error> {
-        todo!("serialize_bytes")
+    /// Written like a sequence of i8. So a TAG_Byte_Array by default
+    fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
+        let mut seq = self.serialize_seq(Some(v.len()))?;
+        for byte in v {
+            ser::SerializeSeq::serialize_element(&mut seq, &(*byte as i8))?;
+        }
+        ser::SerializeSeq::end(seq)
     }
 
+    /// The header of this list is written by the nested list. It knows the tag of its elements
     fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        self.encoding.check(Tag::List)?;
         self.parent(Tag::List)?;
 
         if let Some(len) = len {
-            if !self.wrote_header {
-                Tag::List.write_alone(&mut self.outer)?;
-            }
             let list = SubList {
                 outer: self.outer,
                 wrote_header: false,
                 wrote_parent_header: self.wrote_header,
                 parent_size: self.length,
                 length: len as i32,
+                encoding: self.nested,
+                unsigned: self.unsigned,
+                element: None,
+                parent_element: self.element,
                 phantom: Default::default(),
             };
             Ok(list)
@@ -276,16 +452,9 @@ where
     }
 
     fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
-        self.parent(Tag::Compound)?;
-        if !self.wrote_header {
-            Tag::Compound.write_alone(&mut self.outer)?;
-            self.length.write_alone(&mut self.outer)?;
-        }
+        self.header(Tag::Compound)?;
 
-        Ok(Compound {
-            writer: self.outer,
-            phantom: Default::default(),
-        })
+        Ok(Compound::new(self.outer, self.unsigned))
     }
 
     fn serialize_struct(
@@ -293,15 +462,8 @@ where
         _name: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeStruct, Self::Error> {
-        self.parent(Tag::Compound)?;
-        if !self.wrote_header {
-            Tag::Compound.write_alone(&mut self.outer)?;
-            self.length.write_alone(&mut self.outer)?;
-        }
+        self.header(Tag::Compound)?;
 
-        Ok(Compound {
-            writer: self.outer,
-            phantom: Default::default(),
-        })
+        Ok(Compound::new(self.outer, self.unsigned))
     }
 }
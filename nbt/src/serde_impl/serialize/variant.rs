@@ -0,0 +1,133 @@
+//! Externally tagged enum variants. Written as a compound with a single entry named after the variant
+use crate::serde_impl::arrays::SequenceEncoding;
+use crate::serde_impl::serialize::named::StringOrSerializer;
+use crate::serde_impl::serialize::sequence::SerializeSeqInner;
+use crate::serde_impl::serialize::Compound;
+use crate::serde_impl::unsigned::UnsignedPolicy;
+use crate::serde_impl::Error;
+use crate::{NBTDataType, NBTType, Tag};
+use serde::{ser, Serialize};
+use std::borrow::Cow;
+use std::io::Write;
+
+/// A struct variant. The fields are written into the inner compound
+pub struct StructVariant<'writer, W: Write, Type: NBTType>(pub Compound<'writer, W, Type>)
+where
+    i8: NBTDataType<Type>,
+    i16: NBTDataType<Type>,
+    i32: NBTDataType<Type>,
+    i64: NBTDataType<Type>,
+    f32: NBTDataType<Type>,
+    f64: NBTDataType<Type>,
+    String: NBTDataType<Type>,
+    for<'str> &'str str: NBTDataType<Type>;
+
+impl<'writer, W: Write, Type: NBTType> ser::SerializeStructVariant
+    for StructVariant<'writer, W, Type>
+where
+    i8: NBTDataType<Type>,
+    i16: NBTDataType<Type>,
+    i32: NBTDataType<Type>,
+    i64: NBTDataType<Type>,
+    f32: NBTDataType<Type>,
+    f64: NBTDataType<Type>,
+    String: NBTDataType<Type>,
+    bool: NBTDataType<Type>,
+    for<'str> &'str str: NBTDataType<Type>,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        ser::SerializeStruct::serialize_field(&mut self.0, key, value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        // One for the fields and one for the compound holding the variant
+        Tag::End.write_alone(self.0.writer)?;
+        Tag::End.write_alone(self.0.writer)?;
+        Ok(())
+    }
+}
+
+/// A tuple variant. The fields are written as a list named after the variant
+pub struct TupleVariant<'writer, W: Write, Type: NBTType> {
+    pub(crate) outer: &'writer mut W,
+    pub(crate) name: StringOrSerializer<'static, str>,
+    pub(crate) wrote_header: bool,
+    pub(crate) length: i32,
+    pub(crate) unsigned: UnsignedPolicy,
+    pub(crate) element: Option<Tag>,
+    pub(crate) phantom: std::marker::PhantomData<Type>,
+}
+
+impl<'writer, W: Write, Type: NBTType> TupleVariant<'writer, W, Type> {
+    pub(crate) fn new(
+        outer: &'writer mut W,
+        variant: &'static str,
+        length: usize,
+        unsigned: UnsignedPolicy,
+    ) -> Self {
+        Self {
+            outer,
+            name: StringOrSerializer::String(Cow::Borrowed(variant.as_bytes())),
+            wrote_header: false,
+            length: length as i32,
+            unsigned,
+            element: None,
+            phantom: Default::default(),
+        }
+    }
+}
+
+impl<'writer, W: Write, Type: NBTType> ser::SerializeTupleVariant for TupleVariant<'writer, W, Type>
+where
+    i8: NBTDataType<Type>,
+    i16: NBTDataType<Type>,
+    i32: NBTDataType<Type>,
+    i64: NBTDataType<Type>,
+    f32: NBTDataType<Type>,
+    f64: NBTDataType<Type>,
+    String: NBTDataType<Type>,
+    bool: NBTDataType<Type>,
+    for<'str> &'str str: NBTDataType<Type>,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        let mut inner: SerializeSeqInner<'_, '_, W, Type, str> = SerializeSeqInner {
+            outer: self.outer,
+            name: &self.name,
+            length: self.length,
+            wrote_header: self.wrote_header,
+            encoding: SequenceEncoding::Default,
+            unsigned: self.unsigned,
+            element: &mut self.element,
+            nested: SequenceEncoding::Default,
+            phantom: Default::default(),
+        };
+        value.serialize(&mut inner)?;
+        self.wrote_header = true;
+        Ok(())
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        if !self.wrote_header {
+            Tag::List.write_alone(self.outer)?;
+            if let StringOrSerializer::String(name) = &self.name {
+                Type::write_tag_name(self.outer, name)?;
+            }
+            Tag::End.write_alone(self.outer)?;
+            0.write_alone(self.outer)?;
+        }
+        Tag::End.write_alone(self.outer)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,201 @@
+//! NBT only has signed integers. [UnsignedPolicy] picks how unsigned integers are written.
+//!
+//! Reading does not depend on the policy. A tag of the same width is bit-cast back and a wider
+//! tag is converted if the value fits. So data written with any policy can be read back.
+//!
+//! `i128` and `u128` are written as a TAG_Int_Array of length 4, most significant int first.
+//! The same layout as a [BinaryUUID](crate::binary::binary_uuid::BinaryUUID)
+use crate::serde_impl::arrays::INT_ARRAY_TOKEN;
+use crate::serde_impl::Error;
+use crate::Tag;
+use serde::{ser, Serializer};
+
+/// How unsigned integers are written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnsignedPolicy {
+    /// Written as the signed type of the same width. `u32::MAX` becomes `-1`
+    #[default]
+    BitCast,
+    /// Written as the signed type of the same width. Values that do not fit are an error
+    Checked,
+    /// Written as the next wider tag so every value fits. `u64` has no wider tag and is checked
+    Widen,
+}
+
+fn overflow<E: ser::Error>(value: impl std::fmt::Display, tag: Tag) -> E {
+    E::custom(format!("{} does not fit in a TAG_{:?}", value, tag))
+}
+
+macro_rules! write_unsigned {
+    ($func:ident, $unsigned:ty, $signed:ty, $serialize:ident, $tag:ident, $widen:ident) => {
+        pub(crate) fn $func<S: Serializer>(
+            self,
+            serializer: S,
+            v: $unsigned,
+        ) -> Result<S::Ok, S::Error> {
+            match self {
+                Self::BitCast => serializer.$serialize(v as $signed),
+                Self::Checked => serializer
+                    .$serialize(<$signed>::try_from(v).map_err(|_| overflow(v, Tag::$tag))?),
+                Self::Widen => serializer.$widen(v.into()),
+            }
+        }
+    };
+}
+
+impl UnsignedPolicy {
+    write_unsigned!(write_u8, u8, i8, serialize_i8, Byte, serialize_i16);
+    write_unsigned!(write_u16, u16, i16, serialize_i16, Short, serialize_i32);
+    write_unsigned!(write_u32, u32, i32, serialize_i32, Int, serialize_i64);
+
+    pub(crate) fn write_u64<S: Serializer>(self, serializer: S, v: u64) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::BitCast => serializer.serialize_i64(v as i64),
+            Self::Checked | Self::Widen => {
+                serializer.serialize_i64(i64::try_from(v).map_err(|_| overflow(v, Tag::Long))?)
+            }
+        }
+    }
+}
+
+/// Generates the `serialize_u*` and 128-bit methods of a serializer.
+/// `$policy` is the field holding the [UnsignedPolicy]
+macro_rules! write_unsigned_with {
+    ($policy:ident) => {
+        fn serialize_u8(self, v: u8) -> Result<Self::Ok, Self::Error> {
+            let policy = self.$policy;
+            policy.write_u8(self, v)
+        }
+
+        fn serialize_u16(self, v: u16) -> Result<Self::Ok, Self::Error> {
+            let policy = self.$policy;
+            policy.write_u16(self, v)
+        }
+
+        fn serialize_u32(self, v: u32) -> Result<Self::Ok, Self::Error> {
+            let policy = self.$policy;
+            policy.write_u32(self, v)
+        }
+
+        fn serialize_u64(self, v: u64) -> Result<Self::Ok, Self::Error> {
+            let policy = self.$policy;
+            policy.write_u64(self, v)
+        }
+
+        fn serialize_i128(self, v: i128) -> Result<Self::Ok, Self::Error> {
+            $crate::serde_impl::unsigned::write_i128(self, v)
+        }
+
+        fn serialize_u128(self, v: u128) -> Result<Self::Ok, Self::Error> {
+            $crate::serde_impl::unsigned::write_i128(self, v as i128)
+        }
+    };
+}
+pub(crate) use write_unsigned_with;
+
+/// Splits the value into 4 ints. Most significant first
+pub(crate) fn split_i128(value: i128) -> [i32; 4] {
+    [
+        (value >> 96) as i32,
+        (value >> 64) as i32,
+        (value >> 32) as i32,
+        value as i32,
+    ]
+}
+
+pub(crate) fn join_i128(ints: [i32; 4]) -> i128 {
+    ints.iter()
+        .fold(0i128, |value, int| (value << 32) | (*int as u32 as i128))
+}
+
+pub(crate) fn write_i128<S: Serializer>(serializer: S, v: i128) -> Result<S::Ok, S::Error> {
+    serializer.serialize_newtype_struct(INT_ARRAY_TOKEN, &split_i128(v))
+}
+
+/// Reads the value of a TAG_Byte, TAG_Short, TAG_Int or TAG_Long into an unsigned integer.
+/// `$tag` is the tag of the same width which is bit-cast
+macro_rules! read_unsigned {
+    ($func:ident, $unsigned:ty, $signed:ty, $tag:ident) => {
+        pub(crate) fn $func(tag: Tag, value: i64) -> Result<$unsigned, Error> {
+            if tag == Tag::$tag {
+                Ok(value as $signed as $unsigned)
+            } else {
+                <$unsigned>::try_from(value).map_err(|_| {
+                    Error::Custom(format!(
+                        "{} does not fit in a {}",
+                        value,
+                        stringify!($unsigned)
+                    ))
+                })
+            }
+        }
+    };
+}
+read_unsigned!(read_u8, u8, i8, Byte);
+read_unsigned!(read_u16, u16, i16, Short);
+read_unsigned!(read_u32, u32, i32, Int);
+read_unsigned!(read_u64, u64, i64, Long);
+
+/// Generates the `deserialize_u*` and 128-bit methods of a deserializer.
+/// `$integer` is a method of the deserializer that reads the tag and value of an integer.
+/// It is given the tag to report when the value is not an integer
+macro_rules! read_unsigned_with {
+    ($integer:ident) => {
+        read_unsigned_with!(@unsigned $integer;
+            deserialize_u8 => read_u8 => visit_u8 => Byte,
+            deserialize_u16 => read_u16 => visit_u16 => Short,
+            deserialize_u32 => read_u32 => visit_u32 => Int,
+            deserialize_u64 => read_u64 => visit_u64 => Long
+        );
+
+        fn deserialize_i128<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: serde::de::Visitor<'de>,
+        {
+            let ints = <[i32; 4] as serde::Deserialize>::deserialize(self)?;
+            visitor.visit_i128($crate::serde_impl::unsigned::join_i128(ints))
+        }
+
+        fn deserialize_u128<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: serde::de::Visitor<'de>,
+        {
+            let ints = <[i32; 4] as serde::Deserialize>::deserialize(self)?;
+            visitor.visit_u128($crate::serde_impl::unsigned::join_i128(ints) as u128)
+        }
+    };
+    (@unsigned $integer:ident; $($func:ident => $read:ident => $visit:ident => $tag:ident),*) => {
+        $(
+            fn $func<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+            where
+                V: serde::de::Visitor<'de>,
+            {
+                let (tag, value) = self.$integer($crate::Tag::$tag).map_err(|error| {
+                    $crate::serde_impl::Located::with_expected(error, &visitor)
+                })?;
+                visitor.$visit($crate::serde_impl::unsigned::$read(tag, value)?)
+            }
+        )*
+    };
+}
+pub(crate) use read_unsigned_with;
+
+#[cfg(test)]
+mod tests {
+    use super::{join_i128, split_i128};
+
+    #[test]
+    fn i128_round_trip() {
+        for value in [
+            0,
+            -1,
+            1,
+            i128::MIN,
+            i128::MAX,
+            0x0102_0304_0506_0708_090a_0b0c_0d0e_0f10,
+        ] {
+            assert_eq!(join_i128(split_i128(value)), value);
+        }
+        assert_eq!(split_i128(1), [0, 0, 0, 1]);
+    }
+}
@@ -1,20 +1,45 @@
-use crate::serde_impl::Error;
-use crate::value::{NameLessValue, Value};
+use crate::serde_impl::key::MapKeyDeserializer;
+use crate::serde_impl::unsigned::read_unsigned_with;
+use crate::serde_impl::value::VALUE_TOKEN;
+use crate::serde_impl::{Error, Located};
+use crate::value::{ListIntoIter, NameLessValue, PathSegment, Value};
 use crate::Tag;
-use serde::de::{DeserializeSeed, IntoDeserializer, MapAccess, SeqAccess, Visitor};
+use serde::de::value::SeqAccessDeserializer;
+use serde::de::{
+    DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess, VariantAccess, Visitor,
+};
 use serde::{forward_to_deserialize_any, Deserializer};
 
 pub struct ValueDeserializer(pub Value);
 
 macro_rules! impl_deserializer {
     ($name:ty, $self:ident, $visitor:ident, $map:block, $seq:block) => {
+        impl_deserializer!(
+            $name, $self, $visitor, $map, $seq;
+            deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64
+            deserialize_i128 deserialize_u128
+        );
+    };
+    ($name:ty, $self:ident, $visitor:ident, $map:block, $seq:block; $($unsigned:ident)*) => {
         impl<'de> Deserializer<'de> for $name {
-            type Error = Error;
-            fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
+            type Error = Located;
+            fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+            where
+                V: Visitor<'de>,
+            {
+                InnerValueDeserializer::from(self).deserialize_any(visitor)
+            }
+
+            fn deserialize_enum<V>(
+                self,
+                name: &'static str,
+                variants: &'static [&'static str],
+                visitor: V,
+            ) -> Result<V::Value, Self::Error>
             where
                 V: Visitor<'de>,
             {
-                Err(Error::Custom("deserialize_any not implemented".to_string()))
+                InnerValueDeserializer::from(self).deserialize_enum(name, variants, visitor)
             }
 
             fn deserialize_unit_struct<V>(
@@ -29,18 +54,63 @@ macro_rules! impl_deserializer {
             }
 
             serde::forward_to_deserialize_any! {
-                bool u8 u16 u32 u64 i8 i16 i32 i64 f32 f64 char str string bytes byte_buf
-                unit tuple_struct tuple option enum identifier ignored_any
+                bool i8 i16 i32 i64 f32 f64 char str string bytes byte_buf
+                identifier ignored_any
             }
 
-            fn deserialize_newtype_struct<V>(
+            $(
+                fn $unsigned<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+                where
+                    V: Visitor<'de>,
+                {
+                    InnerValueDeserializer::from(self).$unsigned(visitor)
+                }
+            )*
+
+            fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+            where
+                V: Visitor<'de>,
+            {
+                InnerValueDeserializer::from(self).deserialize_option(visitor)
+            }
+
+            fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+            where
+                V: Visitor<'de>,
+            {
+                InnerValueDeserializer::from(self).deserialize_unit(visitor)
+            }
+
+            fn deserialize_tuple<V>(self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+            where
+                V: Visitor<'de>,
+            {
+                InnerValueDeserializer::from(self).deserialize_tuple(len, visitor)
+            }
+
+            fn deserialize_tuple_struct<V>(
                 self,
                 _name: &'static str,
+                len: usize,
+                visitor: V,
+            ) -> Result<V::Value, Self::Error>
+            where
+                V: Visitor<'de>,
+            {
+                self.deserialize_tuple(len, visitor)
+            }
+
+            fn deserialize_newtype_struct<V>(
+                self,
+                name: &'static str,
                 visitor: V,
             ) -> Result<V::Value, Self::Error>
             where
                 V: Visitor<'de>,
             {
+                if name == VALUE_TOKEN {
+                    return InnerValueDeserializer::from(self).deserialize_newtype_struct(name, visitor);
+                }
                 visitor.visit_newtype_struct(self)
             }
 
@@ -74,21 +144,21 @@ impl_deserializer!(
         match self.0 {
             Value::Compound { value, .. } => {
                 let map = CompoundMap {
-                    value,
+                    value: value.into_iter(),
                     next_value: None,
                 };
                 visitor.visit_map(map)
             }
-            v => Err(Error::IncorrectTagError(Tag::Compound, v.tag())),
+            v => Err(Located::incorrect_tag(Tag::Compound, v.tag(), &visitor)),
         }
     },
     {
         match self.0 {
-            Value::ByteArray { value, .. } => visitor.visit_seq(SequenceDeserializer(value)),
-            Value::IntArray { value, .. } => visitor.visit_seq(SequenceDeserializer(value)),
-            Value::LongArray { value, .. } => visitor.visit_seq(SequenceDeserializer(value)),
-            Value::List { value, .. } => visitor.visit_seq(SequenceDeserializer(value)),
-            v => Err(Error::IncorrectTagError(Tag::List, v.tag())),
+            Value::ByteArray { value, .. } => visitor.visit_seq(SequenceDeserializer(value.into_iter().enumerate())),
+            Value::IntArray { value, .. } => visitor.visit_seq(SequenceDeserializer(value.into_iter().enumerate())),
+            Value::LongArray { value, .. } => visitor.visit_seq(SequenceDeserializer(value.into_iter().enumerate())),
+            Value::List { value, .. } => visitor.visit_seq(SequenceDeserializer(value.into_iter().enumerate())),
+            v => Err(Located::incorrect_tag(Tag::List, v.tag(), &visitor)),
         }
     }
 );
@@ -102,54 +172,53 @@ impl_deserializer!(
         match self.0 {
             NameLessValue::Compound(value) => {
                 let map = CompoundMap {
-                    value,
+                    value: value.into_iter(),
                     next_value: None,
                 };
                 visitor.visit_map(map)
             }
-            v => Err(Error::IncorrectTagError(Tag::Compound, v.tag())),
+            v => Err(Located::incorrect_tag(Tag::Compound, v.tag(), &visitor)),
         }
     },
     {
         match self.0 {
-            NameLessValue::ByteArray(v) => visitor.visit_seq(SequenceDeserializer(v)),
-            NameLessValue::IntArray(v) => visitor.visit_seq(SequenceDeserializer(v)),
-            NameLessValue::LongArray(v) => visitor.visit_seq(SequenceDeserializer(v)),
-            NameLessValue::List(v) => visitor.visit_seq(SequenceDeserializer(v)),
-            v => Err(Error::IncorrectTagError(Tag::List, v.tag())),
+            NameLessValue::ByteArray(v) => visitor.visit_seq(SequenceDeserializer(v.into_iter().enumerate())),
+            NameLessValue::IntArray(v) => visitor.visit_seq(SequenceDeserializer(v.into_iter().enumerate())),
+            NameLessValue::LongArray(v) => visitor.visit_seq(SequenceDeserializer(v.into_iter().enumerate())),
+            NameLessValue::List(v) => visitor.visit_seq(SequenceDeserializer(v.into_iter().enumerate())),
+            v => Err(Located::incorrect_tag(Tag::List, v.tag(), &visitor)),
         }
     }
 );
 
 pub struct CompoundMap {
-    pub value: Vec<Value>,
-    pub next_value: Option<NameLessValue>,
+    pub value: std::vec::IntoIter<Value>,
+    /// The name and value of the entry whose key was just read
+    pub next_value: Option<(String, NameLessValue)>,
 }
 
 impl<'de> MapAccess<'de> for CompoundMap {
-    type Error = Error;
+    type Error = Located;
 
     fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
         where
             K: DeserializeSeed<'de>,
     {
-        let key = match &self.next_value {
-            None => {
-                if let Some(v) = self.value.pop() {
-                    let (value, name): (NameLessValue, String) = v.into();
-                    self.next_value = Some(value);
-                    name
-                } else {
-                    return Ok(None);
-                }
-            }
-            Some(_) => {
-                return Err(Error::Custom(
-                    "next_key_seed called when next_value is not None".to_string(),
-                ));
+        if self.next_value.is_some() {
+            return Err(Error::Custom(
+                "next_key_seed called when next_value is not None".to_string(),
+            ).into());
+        }
+        match self.value.next() {
+            Some(entry) => {
+                let (value, name): (NameLessValue, String) = entry.into();
+                let key = seed.deserialize(MapKeyDeserializer(&name))?;
+                // The name is kept for the location of errors in the value
+                self.next_value = Some((name, value));
+                Ok(Some(key))
             }
-        };
-        seed.deserialize(key.into_deserializer()).map(Some)
+            None => Ok(None),
+        }
     }
 
     fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
@@ -158,38 +227,68 @@ impl<'de> MapAccess<'de> for CompoundMap {
     {
         let value = self.next_value.take();
         match value {
-            None => Err(Error::Custom("No value found".to_string())),
-            Some(v) => seed.deserialize(InnerValueDeserializer(v)),
+            None => Err(Error::Custom("No value found".to_string()).into()),
+            Some((key, v)) => {
+                let tag = v.tag();
+                seed.deserialize(InnerValueDeserializer(v))
+                    .map_err(|error| error.with_found(tag).at(PathSegment::Key(key)))
+            }
         }
     }
 }
 
 struct InnerValueDeserializer(NameLessValue);
 
+impl InnerValueDeserializer {
+    /// Any integer value. `expected` is reported if this is not an integer
+    fn read_integer(self, expected: Tag) -> Result<(Tag, i64), Error> {
+        match self.0 {
+            NameLessValue::Byte(v) => Ok((Tag::Byte, v as i64)),
+            NameLessValue::Boolean(v) => Ok((Tag::Byte, v as i64)),
+            NameLessValue::Short(v) => Ok((Tag::Short, v as i64)),
+            NameLessValue::Int(v) => Ok((Tag::Int, v as i64)),
+            NameLessValue::Long(v) => Ok((Tag::Long, v)),
+            v => Err(Error::IncorrectTagError(expected, v.tag())),
+        }
+    }
+}
+
+impl From<ValueDeserializer> for InnerValueDeserializer {
+    fn from(value: ValueDeserializer) -> Self {
+        Self(value.0.into_nameless())
+    }
+}
+
+impl From<NamelessValueDeserializer> for InnerValueDeserializer {
+    fn from(value: NamelessValueDeserializer) -> Self {
+        Self(value.0)
+    }
+}
+
 impl<'de> Deserializer<'de> for InnerValueDeserializer {
-    type Error = Error;
+    type Error = Located;
 
     fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
         where
             V: Visitor<'de>,
     {
         match self.0 {
-            NameLessValue::End => Err(Error::Custom("End tag found".to_string())),
+            NameLessValue::End => Err(Error::Custom("End tag found".to_string()).into()),
             NameLessValue::Byte(v) => visitor.visit_i8(v),
             NameLessValue::Short(v) => visitor.visit_i16(v),
             NameLessValue::Int(v) => visitor.visit_i32(v),
             NameLessValue::Long(v) => visitor.visit_i64(v),
             NameLessValue::Float(v) => visitor.visit_f32(v),
             NameLessValue::Double(v) => visitor.visit_f64(v),
-            NameLessValue::ByteArray(v) => visitor.visit_seq(SequenceDeserializer(v)),
+            NameLessValue::ByteArray(v) => visitor.visit_seq(SequenceDeserializer(v.into_iter().enumerate())),
             NameLessValue::String(v) => visitor.visit_string(v),
-            NameLessValue::List(v) => visitor.visit_seq(SequenceDeserializer(v)),
+            NameLessValue::List(v) => visitor.visit_seq(SequenceDeserializer(v.into_iter().enumerate())),
             NameLessValue::Compound(v) => visitor.visit_map(CompoundMap {
-                value: v,
+                value: v.into_iter(),
                 next_value: None,
             }),
-            NameLessValue::IntArray(v) => visitor.visit_seq(SequenceDeserializer(v)),
-            NameLessValue::LongArray(v) => visitor.visit_seq(SequenceDeserializer(v)),
+            NameLessValue::IntArray(v) => visitor.visit_seq(SequenceDeserializer(v.into_iter().enumerate())),
+            NameLessValue::LongArray(v) => visitor.visit_seq(SequenceDeserializer(v.into_iter().enumerate())),
             NameLessValue::Boolean(v) => visitor.visit_bool(v),
         }
     }
@@ -198,31 +297,123 @@ impl<'de> Deserializer<'de> for InnerValueDeserializer {
         where
             V: Visitor<'de>,
     {
-        if let NameLessValue::Byte(value) = self.0 {
-            visitor.visit_bool(value != 0)
-        } else if let NameLessValue::Boolean(value) = self.0 {
-            visitor.visit_bool(value)
-        } else {
-            Err(Error::IncorrectTagError(Tag::Byte, self.0.tag()))
+        match self.0.as_bool() {
+            Some(value) => visitor.visit_bool(value),
+            None => Err(Located::incorrect_tag(Tag::Byte, self.0.tag(), &visitor)),
         }
     }
 
     forward_to_deserialize_any! {
-        u8 u16 u32 u64 i8 i16 i32 i64 f32 f64 char str string bytes byte_buf seq
-        map tuple_struct struct tuple enum identifier ignored_any
+        i8 i16 i32 i64 f32 f64 char str string seq
+        map struct identifier ignored_any
     }
+
+    read_unsigned_with!(read_integer);
+
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+    {
+        self.deserialize_byte_buf(visitor)
+    }
+
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+    {
+        match self.0 {
+            NameLessValue::ByteArray(v) => {
+                visitor.visit_byte_buf(v.into_iter().map(|v| v as u8).collect())
+            }
+            v => InnerValueDeserializer(v).deserialize_any(visitor),
+        }
+    }
+
+    /// Tuples are lists or arrays of exactly `len` elements
+    fn deserialize_tuple<V>(self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+    {
+        let length = match &self.0 {
+            NameLessValue::List(v) => v.len(),
+            NameLessValue::ByteArray(v) => v.len(),
+            NameLessValue::IntArray(v) => v.len(),
+            NameLessValue::LongArray(v) => v.len(),
+            v => return Err(Located::incorrect_tag(Tag::List, v.tag(), &visitor)),
+        };
+        if length != len {
+            return Err(Error::IncorrectLengthError(len, length).into());
+        }
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+    {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    /// Unit variants are strings. Other variants are a compound with a single entry named after the variant
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+    {
+        match self.0 {
+            NameLessValue::String(variant) => {
+                visitor.visit_enum(IntoDeserializer::<Located>::into_deserializer(variant))
+            }
+            NameLessValue::Compound(value) => {
+                let mut entries = value.into_iter().filter(|v| !matches!(v, Value::End));
+                match (entries.next(), entries.next()) {
+                    (Some(entry), None) => {
+                        let (value, variant) = entry.into();
+                        visitor.visit_enum(CompoundVariant { variant, value })
+                    }
+                    _ => Err(Error::Custom(
+                        "An enum variant must be a compound with a single entry".to_string(),
+                    ).into()),
+                }
+            }
+            v => Err(Located::incorrect_tag(Tag::Compound, v.tag(), &visitor)),
+        }
+    }
+    /// A value that is present is always `Some`. Missing compound entries become `None`
     fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
         where
             V: Visitor<'de>,
     {
-        visitor.visit_some(self)
+        match self.0 {
+            NameLessValue::End => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
     }
 
+    /// Unit is written as an empty compound
     fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value, Self::Error>
         where
             V: Visitor<'de>,
     {
-        visitor.visit_unit()
+        match self.0 {
+            NameLessValue::End => visitor.visit_unit(),
+            NameLessValue::Compound(value) if value.iter().all(|v| matches!(v, Value::End)) => {
+                visitor.visit_unit()
+            }
+            NameLessValue::Compound(_) => {
+                Err(Error::Custom("Expected an empty compound".to_string()).into())
+            }
+            value => Err(Located::incorrect_tag(Tag::Compound, value.tag(), &visitor)),
+        }
     }
 
     fn deserialize_unit_struct<V>(
@@ -236,15 +427,27 @@ impl<'de> Deserializer<'de> for InnerValueDeserializer {
         visitor.visit_unit()
     }
 
+    /// Like the binary deserializer, lists of bytes, ints and longs are given as `Some` when
+    /// read through [VALUE_TOKEN]
     fn deserialize_newtype_struct<V>(
         self,
-        _name: &'static str,
+        name: &'static str,
         visitor: V,
     ) -> Result<V::Value, Self::Error>
         where
             V: Visitor<'de>,
     {
-        visitor.visit_newtype_struct(self)
+        match (name, self.0) {
+            (VALUE_TOKEN, NameLessValue::List(v))
+                if matches!(v.tag(), Tag::Byte | Tag::Int | Tag::Long) =>
+            {
+                visitor.visit_some(SeqAccessDeserializer::new(SequenceDeserializer(
+                    v.into_iter().enumerate(),
+                )))
+            }
+            (VALUE_TOKEN, v) => InnerValueDeserializer(v).deserialize_any(visitor),
+            (_, v) => visitor.visit_newtype_struct(InnerValueDeserializer(v)),
+        }
     }
 }
 
@@ -254,26 +457,89 @@ impl NameLessValue {
     }
 }
 
-struct SequenceDeserializer<Value>(Vec<Value>);
+struct SequenceDeserializer<Iter>(Iter);
 
+/// `$value` turns an element into a [NameLessValue] so it is read like any other value
 macro_rules! define_seq_access {
-    ($p:ty) => {
-        impl<'de> SeqAccess<'de> for SequenceDeserializer<$p> {
-            type Error = Error;
+    ($p:ty, $value:path) => {
+        impl<'de> SeqAccess<'de> for SequenceDeserializer<std::iter::Enumerate<$p>> {
+            type Error = Located;
 
             fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
             where
                 T: DeserializeSeed<'de>,
             {
-                match self.0.pop() {
+                match self.0.next() {
                     None => Ok(None),
-                    Some(v) => seed.deserialize(v.into_deserializer()).map(Some),
+                    Some((index, v)) => {
+                        let value = $value(v);
+                        let tag = value.tag();
+                        seed.deserialize(InnerValueDeserializer(value))
+                            .map(Some)
+                            .map_err(|error| error.with_found(tag).at(PathSegment::Index(index)))
+                    }
                 }
             }
+
+            fn size_hint(&self) -> Option<usize> {
+                Some(self.0.len())
+            }
         }
     };
 }
-define_seq_access!(i8);
-define_seq_access!(i32);
-define_seq_access!(i64);
-define_seq_access!(NameLessValue);
+define_seq_access!(std::vec::IntoIter<i8>, NameLessValue::Byte);
+define_seq_access!(std::vec::IntoIter<i32>, NameLessValue::Int);
+define_seq_access!(std::vec::IntoIter<i64>, NameLessValue::Long);
+define_seq_access!(ListIntoIter, std::convert::identity);
+
+struct CompoundVariant {
+    variant: String,
+    value: NameLessValue,
+}
+
+impl<'de> EnumAccess<'de> for CompoundVariant {
+    type Error = Located;
+    type Variant = InnerValueDeserializer;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error>
+        where
+            V: DeserializeSeed<'de>,
+    {
+        let variant =
+            seed.deserialize(IntoDeserializer::<Located>::into_deserializer(self.variant))?;
+        Ok((variant, InnerValueDeserializer(self.value)))
+    }
+}
+
+impl<'de> VariantAccess<'de> for InnerValueDeserializer {
+    type Error = Located;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Self::Error>
+        where
+            T: DeserializeSeed<'de>,
+    {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V>(self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+    {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    fn struct_variant<V>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+    {
+        self.deserialize_map(visitor)
+    }
+}
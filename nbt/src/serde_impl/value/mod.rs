@@ -1,73 +1,28 @@
 pub mod deserialize;
+pub mod serialize;
 
-use crate::{NameLessValue, Value};
+use crate::serde_impl::arrays::{
+    BYTE_ARRAY_TOKEN, INT_ARRAY_TOKEN, LIST_TOKEN, LONG_ARRAY_TOKEN,
+};
+use crate::value::List;
+use crate::{NameLessValue, Tag, Value};
 use serde::de::{DeserializeSeed, Error, MapAccess, SeqAccess, Visitor};
 use serde::ser::{SerializeMap, SerializeSeq};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt::Formatter;
 
+/// Asked for by the visitors of [NameLessValue] and [Value]. The deserializers of this crate then
+/// give lists of bytes, ints and longs as `Some`, so they are not read back as arrays. Every other
+/// deserializer visits it as a newtype and is read through deserialize_any
+pub(crate) const VALUE_TOKEN: &str = "$axolotl_nbt::Value";
+
 impl Serialize for Value {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
         where
             S: Serializer,
     {
         match self {
-            Value::Compound { value, .. } => {
-                let mut map = serializer.serialize_map(Some(value.len()))?;
-                for v in value {
-                    match v {
-                        Value::Byte { name, value } => {
-                            map.serialize_entry(name, value)?;
-                        }
-                        Value::Short { name, value } => {
-                            map.serialize_entry(name, value)?;
-                        }
-                        Value::Int { name, value } => {
-                            map.serialize_entry(name, value)?;
-                        }
-                        Value::Long { name, value } => {
-                            map.serialize_entry(name, value)?;
-                        }
-                        Value::Float { name, value } => {
-                            map.serialize_entry(name, value)?;
-                        }
-                        Value::Double { name, value } => {
-                            map.serialize_entry(name, value)?;
-                        }
-                        Value::ByteArray { name, value } => {
-                            map.serialize_entry(name, value)?;
-                        }
-                        Value::String { name, value } => {
-                            map.serialize_entry(name, value)?;
-                        }
-                        Value::List { name, value } => {
-                            map.serialize_entry(name, value)?;
-                        }
-                        Value::IntArray { name, value } => {
-                            map.serialize_entry(name, value)?;
-                        }
-                        Value::LongArray { name, value } => {
-                            map.serialize_entry(name, value)?;
-                        }
-                        Value::Boolean { name, value } => {
-                            map.serialize_entry(name, value)?;
-                        }
-                        Value::End => {
-                            // I think
-                            continue;
-                        }
-                        v => {
-                            let name = if let Value::Compound { name, .. } = v {
-                                name.as_str()
-                            } else {
-                                ""
-                            };
-                            map.serialize_entry(name, v)?;
-                        }
-                    }
-                }
-                map.end()
-            }
+            Value::Compound { value, .. } => CompoundSerializer(value).serialize(serializer),
             _ => Err(serde::ser::Error::custom("Value is not a compound")),
         }
     }
@@ -209,18 +164,25 @@ impl<'de> Visitor<'de> for InnerValueDeserializer {
             value,
         })
     });
-    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
         where
             A: SeqAccess<'de>,
     {
-        let mut values = Vec::with_capacity(seq.size_hint().unwrap_or(0));
-        while let Some(value) = seq.next_element_seed(NamelessValueVisitor)? {
-            values.push(value);
-        }
-        Ok(Value::List {
-            name: self.0,
-            value: values,
-        })
+        NamelessValueVisitor.visit_seq(seq).map(|value| value.into_named(self.0))
+    }
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where
+            D: Deserializer<'de>,
+    {
+        NamelessValueVisitor
+            .visit_some(deserializer)
+            .map(|value| value.into_named(self.0))
+    }
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where
+            D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
     }
 }
 
@@ -231,7 +193,7 @@ impl<'de> DeserializeSeed<'de> for InnerValueDeserializer {
         where
             D: Deserializer<'de>,
     {
-        deserializer.deserialize_any(self)
+        deserializer.deserialize_newtype_struct(VALUE_TOKEN, self)
     }
 }
 
@@ -243,6 +205,12 @@ impl<'de> Visitor<'de> for NamelessValueVisitor {
     fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
         formatter.write_str("A valid NBT value")
     }
+    fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+    {
+        Ok(NameLessValue::Boolean(v))
+    }
     fn visit_i8<E>(self, v: i8) -> Result<Self::Value, E>
         where
             E: serde::de::Error,
@@ -291,15 +259,30 @@ impl<'de> Visitor<'de> for NamelessValueVisitor {
     {
         Ok(NameLessValue::String(v))
     }
-    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    /// Sequences of bytes, ints and longs are arrays. The lists of them come as `Some`, see
+    /// [VALUE_TOKEN]
+    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
         where
             A: SeqAccess<'de>,
     {
-        let mut values = Vec::with_capacity(seq.size_hint().unwrap_or(0));
-        while let Some(value) = seq.next_element_seed(NamelessValueVisitor)? {
-            values.push(value);
-        }
-        Ok(NameLessValue::List(values))
+        Ok(match visit_list(seq)? {
+            List::Byte(v) => NameLessValue::ByteArray(v),
+            List::Int(v) => NameLessValue::IntArray(v),
+            List::Long(v) => NameLessValue::LongArray(v),
+            list => NameLessValue::List(list),
+        })
+    }
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where
+            D: Deserializer<'de>,
+    {
+        List::deserialize(deserializer).map(NameLessValue::List)
+    }
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where
+            D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
     }
     visit_map!(self values {
         Ok(NameLessValue::Compound(values))
@@ -313,7 +296,7 @@ impl<'de> DeserializeSeed<'de> for NamelessValueVisitor {
         where
             D: Deserializer<'de>,
     {
-        deserializer.deserialize_any(self)
+        deserializer.deserialize_newtype_struct(VALUE_TOKEN, self)
     }
 }
 
@@ -322,7 +305,7 @@ impl<'de> Deserialize<'de> for NameLessValue {
         where
             D: Deserializer<'de>,
     {
-        deserializer.deserialize_any(NamelessValueVisitor)
+        NamelessValueVisitor.deserialize(deserializer)
     }
 }
 
@@ -339,84 +322,191 @@ impl Serialize for NameLessValue {
             NameLessValue::Float(v) => serializer.serialize_f32(*v),
             NameLessValue::Double(v) => serializer.serialize_f64(*v),
             NameLessValue::String(v) => serializer.serialize_str(v),
-            NameLessValue::List(v) => {
-                let mut seq = serializer.serialize_seq(Some(v.len()))?;
-                for value in v {
-                    seq.serialize_element(value)?;
-                }
-                seq.end()
+            NameLessValue::List(v) => v.serialize(serializer),
+            NameLessValue::Compound(v) => CompoundSerializer(v).serialize(serializer),
+            NameLessValue::ByteArray(v) => serializer.serialize_newtype_struct(BYTE_ARRAY_TOKEN, v),
+            NameLessValue::IntArray(v) => serializer.serialize_newtype_struct(INT_ARRAY_TOKEN, v),
+            NameLessValue::LongArray(v) => serializer.serialize_newtype_struct(LONG_ARRAY_TOKEN, v),
+            NameLessValue::Boolean(v) => serializer.serialize_bool(*v),
+            _ => Err(serde::ser::Error::custom("Unsupported type")),
+        }
+    }
+}
+
+/// Lists of bytes, ints and longs may have been read as arrays, see [VALUE_TOKEN]. Arrays of one
+/// kind are kept with the empty lists next to them read as empty arrays. Otherwise the arrays
+/// are read as lists
+fn visit_list<'de, A>(mut seq: A) -> Result<List, A::Error>
+where
+    A: SeqAccess<'de>,
+{
+    let mut values = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+    while let Some(value) = seq.next_element_seed(NamelessValueVisitor)? {
+        values.push(value);
+    }
+    let array = values.iter().map(NameLessValue::tag).find(|tag| {
+        matches!(tag, Tag::ByteArray | Tag::IntArray | Tag::LongArray)
+    });
+    let arrays = array.is_some_and(|array| {
+        values
+            .iter()
+            .all(|value| value.tag() == array || matches!(value, NameLessValue::List(List::End)))
+    });
+    let mut list = List::End;
+    for value in values {
+        let value = match (value, array) {
+            (NameLessValue::List(List::End), Some(Tag::ByteArray)) if arrays => {
+                NameLessValue::ByteArray(Vec::new())
             }
-            NameLessValue::Compound(v) => {
-                let mut map = serializer.serialize_map(Some(v.len()))?;
-                for value in v {
-                    match value {
-                        Value::End => {}
-                        Value::Byte { name, value } => {
-                            map.serialize_entry(name, value)?;
-                        }
-                        Value::Short { name, value } => {
-                            map.serialize_entry(name, value)?;
-                        }
-                        Value::Int { name, value } => {
-                            map.serialize_entry(name, value)?;
-                        }
-                        Value::Long { name, value } => {
-                            map.serialize_entry(name, value)?;
-                        }
-                        Value::Float { name, value } => {
-                            map.serialize_entry(name, value)?;
-                        }
-                        Value::Double { name, value } => {
-                            map.serialize_entry(name, value)?;
-                        }
-                        Value::ByteArray { name, value } => {
-                            map.serialize_entry(name, value)?;
-                        }
-                        Value::String { name, value } => {
-                            map.serialize_entry(name, value)?;
-                        }
-                        Value::List { name, value } => {
-                            map.serialize_entry(name, value)?;
-                        }
-                        Value::Compound { name, value } => {
-                            map.serialize_entry(name, value)?;
-                        }
-                        Value::IntArray { name, value } => {
-                            map.serialize_entry(name, value)?;
-                        }
-                        Value::LongArray { name, value } => {
-                            map.serialize_entry(name, value)?;
-                        }
-                        Value::Boolean { name, value } => {
-                            map.serialize_entry(name, value)?;
-                        }
-                    }
-                }
-                map.end()
+            (NameLessValue::List(List::End), Some(Tag::IntArray)) if arrays => {
+                NameLessValue::IntArray(Vec::new())
             }
-            NameLessValue::ByteArray(v) => {
-                let mut seq = serializer.serialize_seq(Some(v.len()))?;
-                for value in v {
-                    seq.serialize_element(value)?;
-                }
-                seq.end()
+            (NameLessValue::List(List::End), Some(Tag::LongArray)) if arrays => {
+                NameLessValue::LongArray(Vec::new())
             }
-            NameLessValue::IntArray(v) => {
-                let mut seq = serializer.serialize_seq(Some(v.len()))?;
-                for value in v {
-                    seq.serialize_element(value)?;
+            (NameLessValue::ByteArray(v), _) if !arrays => NameLessValue::List(List::Byte(v)),
+            (NameLessValue::IntArray(v), _) if !arrays => NameLessValue::List(List::Int(v)),
+            (NameLessValue::LongArray(v), _) if !arrays => NameLessValue::List(List::Long(v)),
+            (value, _) => value,
+        };
+        list.push(value).map_err(A::Error::custom)?;
+    }
+    Ok(list)
+}
+
+/// Serializes the contents of a compound as a map
+struct CompoundSerializer<'value>(&'value [Value]);
+
+impl Serialize for CompoundSerializer<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+    {
+        let v = self.0;
+        let mut map = serializer.serialize_map(Some(v.len()))?;
+        for value in v {
+            match value {
+                Value::End => {}
+                Value::Byte { name, value } => {
+                    map.serialize_entry(name, value)?;
+                }
+                Value::Short { name, value } => {
+                    map.serialize_entry(name, value)?;
+                }
+                Value::Int { name, value } => {
+                    map.serialize_entry(name, value)?;
+                }
+                Value::Long { name, value } => {
+                    map.serialize_entry(name, value)?;
+                }
+                Value::Float { name, value } => {
+                    map.serialize_entry(name, value)?;
+                }
+                Value::Double { name, value } => {
+                    map.serialize_entry(name, value)?;
+                }
+                Value::ByteArray { name, value } => {
+                    map.serialize_entry(name, &Encoded(BYTE_ARRAY_TOKEN, value))?;
+                }
+                Value::String { name, value } => {
+                    map.serialize_entry(name, value)?;
+                }
+                Value::List { name, value } => {
+                    map.serialize_entry(name, value)?;
+                }
+                Value::Compound { name, value } => {
+                    map.serialize_entry(name, &CompoundSerializer(value))?;
+                }
+                Value::IntArray { name, value } => {
+                    map.serialize_entry(name, &Encoded(INT_ARRAY_TOKEN, value))?;
+                }
+                Value::LongArray { name, value } => {
+                    map.serialize_entry(name, &Encoded(LONG_ARRAY_TOKEN, value))?;
+                }
+                Value::Boolean { name, value } => {
+                    map.serialize_entry(name, value)?;
                 }
-                seq.end()
             }
-            NameLessValue::LongArray(v) => {
+        }
+        map.end()
+    }
+}
+
+impl Serialize for List {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+    {
+        match self {
+            List::End => serializer.serialize_seq(Some(0))?.end(),
+            List::Byte(v) => serializer.serialize_newtype_struct(LIST_TOKEN, v),
+            List::Short(v) => v.serialize(serializer),
+            List::Int(v) => serializer.serialize_newtype_struct(LIST_TOKEN, v),
+            List::Long(v) => serializer.serialize_newtype_struct(LIST_TOKEN, v),
+            List::Float(v) => v.serialize(serializer),
+            List::Double(v) => v.serialize(serializer),
+            List::ByteArray(v) => serialize_arrays(BYTE_ARRAY_TOKEN, v, serializer),
+            List::String(v) => v.serialize(serializer),
+            List::List(v) => v.serialize(serializer),
+            List::Compound(v) => {
                 let mut seq = serializer.serialize_seq(Some(v.len()))?;
                 for value in v {
-                    seq.serialize_element(value)?;
+                    seq.serialize_element(&CompoundSerializer(value))?;
                 }
                 seq.end()
             }
-            NameLessValue::Boolean(v) => serializer.serialize_bool(*v),
-            _ => Err(serde::ser::Error::custom("Unsupported type")),
+            List::IntArray(v) => serialize_arrays(INT_ARRAY_TOKEN, v, serializer),
+            List::LongArray(v) => serialize_arrays(LONG_ARRAY_TOKEN, v, serializer),
         }
     }
 }
+
+/// Always read as a list, even when the elements could be an array
+impl<'de> Deserialize<'de> for List {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+    {
+        struct ListVisitor;
+
+        impl<'de> Visitor<'de> for ListVisitor {
+            type Value = List;
+
+            fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+                formatter.write_str("A list")
+            }
+            fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+                where
+                    A: SeqAccess<'de>,
+            {
+                visit_list(seq)
+            }
+        }
+
+        deserializer.deserialize_seq(ListVisitor)
+    }
+}
+
+/// Writes the value with the tag of `token`. The tag would otherwise depend on the serializer
+struct Encoded<'value, T>(&'static str, &'value T);
+
+impl<T: Serialize> Serialize for Encoded<'_, T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+    {
+        serializer.serialize_newtype_struct(self.0, self.1)
+    }
+}
+
+fn serialize_arrays<T: Serialize, S: Serializer>(
+    token: &'static str,
+    arrays: &[Vec<T>],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut seq = serializer.serialize_seq(Some(arrays.len()))?;
+    for array in arrays {
+        seq.serialize_element(&Encoded(token, array))?;
+    }
+    seq.end()
+}
@@ -0,0 +1,546 @@
+use crate::serde_impl::arrays::SequenceEncoding;
+use crate::serde_impl::key::MapKey;
+use crate::serde_impl::unsigned::{write_unsigned_with, UnsignedPolicy};
+use crate::serde_impl::Error;
+use crate::value::{List, NameLessValue, Value};
+use crate::Tag;
+use serde::{ser, Serialize, Serializer};
+
+/// Serializes into a [Value] tree instead of bytes.
+///
+/// Uses the same type mapping as [NBTSerializer](crate::serde_impl::serialize::NBTSerializer).
+/// The name is given to the produced value. At the root a struct is named after the struct
+#[derive(Debug, Default)]
+pub struct ValueSerializer {
+    pub name: Option<String>,
+    pub unsigned: UnsignedPolicy,
+    /// Set by the token of a newtype struct
+    encoding: SequenceEncoding,
+    position: Position,
+}
+
+/// Where a value is written. Decides the tag of a sequence like
+/// [NBTSerializer](crate::serde_impl::serialize::NBTSerializer) does
+#[derive(Debug, Clone, Copy, Default)]
+enum Position {
+    /// The root, an entry of a compound or a variant
+    #[default]
+    Field,
+    /// An element of a list. Holds the tag of the elements before it
+    Element(Option<Tag>),
+}
+
+impl ValueSerializer {
+    pub fn named(name: impl Into<String>) -> Self {
+        Self {
+            name: Some(name.into()),
+            ..Self::default()
+        }
+    }
+
+    /// Picks how unsigned integers are written. See [UnsignedPolicy]
+    pub fn unsigned(mut self, policy: UnsignedPolicy) -> Self {
+        self.unsigned = policy;
+        self
+    }
+    #[inline]
+    fn value(self, value: NameLessValue) -> Result<Value, Error> {
+        Ok(value.into_named(self.name.unwrap_or_default()))
+    }
+}
+
+impl Serializer for ValueSerializer {
+    type Ok = Value;
+    type Error = Error;
+    type SerializeSeq = SerializeList;
+    type SerializeTuple = SerializeList;
+    type SerializeTupleStruct = SerializeList;
+    type SerializeTupleVariant = SerializeTupleVariant;
+    type SerializeMap = SerializeCompound;
+    type SerializeStruct = SerializeCompound;
+    type SerializeStructVariant = SerializeStructVariant;
+
+    fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
+        self.value(NameLessValue::Boolean(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Self::Ok, Self::Error> {
+        self.value(NameLessValue::Byte(v))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Self::Ok, Self::Error> {
+        self.value(NameLessValue::Short(v))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Self::Ok, Self::Error> {
+        self.value(NameLessValue::Int(v))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Self::Ok, Self::Error> {
+        self.value(NameLessValue::Long(v))
+    }
+
+    write_unsigned_with!(unsigned);
+
+    fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
+        self.value(NameLessValue::Float(v))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> {
+        self.value(NameLessValue::Double(v))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
+        self.value(NameLessValue::String(v.to_string()))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
+        self.value(NameLessValue::String(v.to_string()))
+    }
+
+    /// Written like a sequence of i8. So a TAG_Byte_Array by default
+    fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
+        let mut seq = self.serialize_seq(Some(v.len()))?;
+        for byte in v {
+            ser::SerializeSeq::serialize_element(&mut seq, &(*byte as i8))?;
+        }
+        ser::SerializeSeq::end(seq)
+    }
+
+    /// Produces [Value::End]. Compounds leave the entry out and lists reject it
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        Ok(Value::End)
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<Self::Ok, Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<Self::Ok, Self::Error> {
+        self.serialize_str(name)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok, Self::Error> {
+        self.serialize_str(variant)
+    }
+
+    /// Transparent. Unless it is one of the adapters in [crate::serde_impl::arrays].
+    /// `None` is kept as [Value::End] so compounds leave it out
+    fn serialize_newtype_struct<T>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        match SequenceEncoding::from_token(name) {
+            Some(encoding) => match value.serialize(ValueSerializer { encoding, ..self })? {
+                Value::End => Ok(Value::End),
+                value => {
+                    let (value, name) = value.into();
+                    Ok(encode(value, encoding)?.into_named(name))
+                }
+            },
+            None => value.serialize(self),
+        }
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        Ok(SerializeList {
+            name: self.name.unwrap_or_default(),
+            values: Vec::with_capacity(len.unwrap_or(0)),
+            unsigned: self.unsigned,
+            encoding: self.encoding,
+            position: self.position,
+        })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        Ok(SerializeCompound {
+            name: self.name.unwrap_or_default(),
+            values: Vec::with_capacity(len.unwrap_or(0)),
+            key: None,
+            unsigned: self.unsigned,
+        })
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        Ok(SerializeCompound {
+            name: self.name.unwrap_or_else(|| name.to_string()),
+            values: Vec::with_capacity(len),
+            key: None,
+            unsigned: self.unsigned,
+        })
+    }
+
+    /// A compound with a single entry named after the variant
+    fn serialize_newtype_variant<T>(
+        self,
+        name: &'static str,
+        _: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        let mut compound = SerializeCompound {
+            name: self.name.unwrap_or_else(|| name.to_string()),
+            values: Vec::with_capacity(1),
+            key: None,
+            unsigned: self.unsigned,
+        };
+        compound.push(variant.to_string(), value)?;
+        ser::SerializeMap::end(compound)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        _: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        Ok(SerializeTupleVariant {
+            name: self.name.unwrap_or_else(|| name.to_string()),
+            list: SerializeList {
+                name: variant.to_string(),
+                values: Vec::with_capacity(len),
+                unsigned: self.unsigned,
+                encoding: SequenceEncoding::Default,
+                position: Position::Field,
+            },
+        })
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        _: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        Ok(SerializeStructVariant {
+            name: self.name.unwrap_or_else(|| name.to_string()),
+            compound: SerializeCompound {
+                name: variant.to_string(),
+                values: Vec::with_capacity(len),
+                key: None,
+                unsigned: self.unsigned,
+            },
+        })
+    }
+
+    /// An empty compound
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        self.value(NameLessValue::Compound(Vec::new()))
+    }
+
+    /// Tuples are written as lists. Just like sequences
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+}
+
+/// Converts a serialized sequence to the encoding picked by an adapter
+fn encode(value: NameLessValue, encoding: SequenceEncoding) -> Result<NameLessValue, Error> {
+    let value = match (encoding, value) {
+        (SequenceEncoding::Default, value) => value,
+        (SequenceEncoding::List, NameLessValue::ByteArray(v)) => NameLessValue::List(List::Byte(v)),
+        (SequenceEncoding::List, NameLessValue::IntArray(v)) => NameLessValue::List(List::Int(v)),
+        (SequenceEncoding::List, NameLessValue::LongArray(v)) => NameLessValue::List(List::Long(v)),
+        (SequenceEncoding::List, NameLessValue::List(v)) => NameLessValue::List(v),
+        (SequenceEncoding::ByteArray, NameLessValue::ByteArray(v)) => NameLessValue::ByteArray(v),
+        (SequenceEncoding::IntArray, NameLessValue::IntArray(v)) => NameLessValue::IntArray(v),
+        (SequenceEncoding::LongArray, NameLessValue::LongArray(v)) => NameLessValue::LongArray(v),
+        (SequenceEncoding::ByteArray, NameLessValue::List(List::End)) => {
+            NameLessValue::ByteArray(vec![])
+        }
+        (SequenceEncoding::IntArray, NameLessValue::List(List::End)) => {
+            NameLessValue::IntArray(vec![])
+        }
+        (SequenceEncoding::LongArray, NameLessValue::List(List::End)) => {
+            NameLessValue::LongArray(vec![])
+        }
+        (encoding, NameLessValue::List(list)) => {
+            return Err(Error::IncorrectTagError(
+                encoding.element_tag().unwrap_or(Tag::List),
+                list.tag(),
+            ))
+        }
+        (_, value) => return Err(Error::IncorrectTagError(Tag::List, value.tag())),
+    };
+    Ok(value)
+}
+
+/// Collects the elements of a sequence.
+///
+/// Sequences of Byte, Int and Long become the matching array type
+pub struct SerializeList {
+    name: String,
+    values: Vec<NameLessValue>,
+    unsigned: UnsignedPolicy,
+    encoding: SequenceEncoding,
+    position: Position,
+}
+
+impl SerializeList {
+    /// The tag of this sequence. Booleans outside of a list and sequences within a list of
+    /// lists are lists. Empty sequences within a list of arrays are empty arrays
+    fn tag(&self) -> Result<Tag, Error> {
+        let first = self.values.first();
+        if let Some(value) = first {
+            self.encoding.check(value.tag())?;
+        }
+        let tag = match (self.encoding, self.position, first) {
+            (SequenceEncoding::Default, Position::Element(Some(Tag::List)), _) => Tag::List,
+            (
+                SequenceEncoding::Default,
+                Position::Element(Some(array @ (Tag::ByteArray | Tag::IntArray | Tag::LongArray))),
+                None,
+            ) => array,
+            (SequenceEncoding::Default, Position::Field, Some(NameLessValue::Boolean(_))) => {
+                Tag::List
+            }
+            (SequenceEncoding::Default, _, Some(value)) => match value.tag() {
+                Tag::Byte => Tag::ByteArray,
+                Tag::Int => Tag::IntArray,
+                Tag::Long => Tag::LongArray,
+                _ => Tag::List,
+            },
+            (encoding, _, _) => encoding.array_tag().unwrap_or(Tag::List),
+        };
+        Ok(tag)
+    }
+}
+
+impl ser::SerializeSeq for SerializeList {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        let serializer = ValueSerializer {
+            name: Some(String::new()),
+            unsigned: self.unsigned,
+            encoding: SequenceEncoding::Default,
+            position: Position::Element(self.values.first().map(NameLessValue::tag)),
+        };
+        match value.serialize(serializer)? {
+            Value::End => Err(Error::UnrepresentableValueError(
+                "None can not be an element of a list",
+            )),
+            value => {
+                self.values.push(value.into_nameless());
+                Ok(())
+            }
+        }
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        let value = match self.tag()? {
+            Tag::ByteArray => NameLessValue::ByteArray(
+                self.values
+                    .into_iter()
+                    .map(|v| match v {
+                        NameLessValue::Byte(v) => Ok(v),
+                        NameLessValue::Boolean(v) => Ok(i8::from(v)),
+                        v => Err(crate::NBTError::ListTypeMismatch(Tag::ByteArray, v.tag())),
+                    })
+                    .collect::<Result<_, _>>()?,
+            ),
+            Tag::IntArray => NameLessValue::IntArray(
+                self.values
+                    .into_iter()
+                    .map(|v| match v {
+                        NameLessValue::Int(v) => Ok(v),
+                        v => Err(crate::NBTError::ListTypeMismatch(Tag::IntArray, v.tag())),
+                    })
+                    .collect::<Result<_, _>>()?,
+            ),
+            Tag::LongArray => NameLessValue::LongArray(
+                self.values
+                    .into_iter()
+                    .map(|v| match v {
+                        NameLessValue::Long(v) => Ok(v),
+                        v => Err(crate::NBTError::ListTypeMismatch(Tag::LongArray, v.tag())),
+                    })
+                    .collect::<Result<_, _>>()?,
+            ),
+            _ => NameLessValue::List(List::try_from(self.values)?),
+        };
+        Ok(value.into_named(self.name))
+    }
+}
+
+impl ser::SerializeTuple for SerializeList {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl ser::SerializeTupleStruct for SerializeList {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+/// Collects the entries of a map or struct. Entries that are `None` are left out
+pub struct SerializeCompound {
+    name: String,
+    values: Vec<Value>,
+    key: Option<String>,
+    unsigned: UnsignedPolicy,
+}
+
+impl SerializeCompound {
+    fn push<T>(&mut self, key: String, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        match value.serialize(ValueSerializer::named(key).unsigned(self.unsigned))? {
+            Value::End => {}
+            value => self.values.push(value),
+        }
+        Ok(())
+    }
+}
+
+impl ser::SerializeMap for SerializeCompound {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.key = Some(key.serialize(MapKey)?);
+        Ok(())
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        let key = self.key.take().ok_or_else(|| {
+            Error::Custom("serialize_value called before serialize_key".to_string())
+        })?;
+        self.push(key, value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(Value::Compound {
+            name: self.name,
+            value: self.values,
+        })
+    }
+}
+
+impl ser::SerializeStruct for SerializeCompound {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.push(key.to_string(), value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        ser::SerializeMap::end(self)
+    }
+}
+
+/// The list of a tuple variant. Wrapped in a compound named after the enum
+pub struct SerializeTupleVariant {
+    name: String,
+    list: SerializeList,
+}
+
+impl ser::SerializeTupleVariant for SerializeTupleVariant {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        ser::SerializeSeq::serialize_element(&mut self.list, value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(Value::Compound {
+            name: self.name,
+            value: vec![ser::SerializeSeq::end(self.list)?],
+        })
+    }
+}
+
+/// The fields of a struct variant. Wrapped in a compound named after the enum
+pub struct SerializeStructVariant {
+    name: String,
+    compound: SerializeCompound,
+}
+
+impl ser::SerializeStructVariant for SerializeStructVariant {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.compound.push(key.to_string(), value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(Value::Compound {
+            name: self.name,
+            value: vec![ser::SerializeMap::end(self.compound)?],
+        })
+    }
+}
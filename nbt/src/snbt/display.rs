@@ -0,0 +1,161 @@
+//! Formats Values as SNBT
+use crate::value::{List, NameLessValue, Value};
+use std::fmt::{Display, Formatter, Result, Write};
+
+fn write_string(f: &mut Formatter<'_>, value: &str) -> Result {
+    f.write_char('"')?;
+    for c in value.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            c => f.write_char(c)?,
+        }
+    }
+    f.write_char('"')
+}
+
+fn write_name(f: &mut Formatter<'_>, name: &str) -> Result {
+    let plain = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '+'));
+    if plain {
+        f.write_str(name)
+    } else {
+        write_string(f, name)
+    }
+}
+
+fn write_array<T: Display>(
+    f: &mut Formatter<'_>,
+    prefix: &str,
+    values: &[T],
+    suffix: &str,
+) -> Result {
+    f.write_str(prefix)?;
+    for (index, value) in values.iter().enumerate() {
+        if index != 0 {
+            f.write_char(',')?;
+        }
+        write!(f, "{}{}", value, suffix)?;
+    }
+    f.write_char(']')
+}
+
+/// NaN and the infinities have no SNBT literal. An infinity is written as a number too large for
+/// the type, which reads back as the infinity. NaN is written as 0
+fn write_float(f: &mut Formatter<'_>, value: f32) -> Result {
+    if value.is_nan() {
+        f.write_str("0f")
+    } else if value.is_infinite() {
+        f.write_str(if value > 0.0 { "1e39f" } else { "-1e39f" })
+    } else {
+        write!(f, "{}f", value)
+    }
+}
+
+/// See [write_float]
+fn write_double(f: &mut Formatter<'_>, value: f64) -> Result {
+    if value.is_nan() {
+        f.write_str("0d")
+    } else if value.is_infinite() {
+        f.write_str(if value > 0.0 { "1e309d" } else { "-1e309d" })
+    } else {
+        write!(f, "{}d", value)
+    }
+}
+
+fn write_compound(f: &mut Formatter<'_>, values: &[Value]) -> Result {
+    f.write_char('{')?;
+    let mut first = true;
+    for value in values {
+        if let Value::End = value {
+            continue;
+        }
+        if !first {
+            f.write_char(',')?;
+        }
+        first = false;
+        write_name(f, value.get_name())?;
+        write!(f, ":{}", value)?;
+    }
+    f.write_char('}')
+}
+
+fn write_list<T>(
+    f: &mut Formatter<'_>,
+    values: &[T],
+    element: impl Fn(&mut Formatter<'_>, &T) -> Result,
+) -> Result {
+    f.write_char('[')?;
+    for (index, value) in values.iter().enumerate() {
+        if index != 0 {
+            f.write_char(',')?;
+        }
+        element(f, value)?;
+    }
+    f.write_char(']')
+}
+
+/// Writes the payload of the value. The name is only written by the parent compound
+impl Display for Value {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Value::End => Ok(()),
+            Value::Byte { value, .. } => write!(f, "{}b", value),
+            Value::Short { value, .. } => write!(f, "{}s", value),
+            Value::Int { value, .. } => write!(f, "{}", value),
+            Value::Long { value, .. } => write!(f, "{}L", value),
+            Value::Float { value, .. } => write_float(f, *value),
+            Value::Double { value, .. } => write_double(f, *value),
+            Value::ByteArray { value, .. } => write_array(f, "[B;", value, "b"),
+            Value::String { value, .. } => write_string(f, value),
+            Value::List { value, .. } => write!(f, "{}", value),
+            Value::Compound { value, .. } => write_compound(f, value),
+            Value::IntArray { value, .. } => write_array(f, "[I;", value, ""),
+            Value::LongArray { value, .. } => write_array(f, "[L;", value, "L"),
+            Value::Boolean { value, .. } => write!(f, "{}", value),
+        }
+    }
+}
+
+impl Display for NameLessValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            NameLessValue::End => Ok(()),
+            NameLessValue::Byte(value) => write!(f, "{}b", value),
+            NameLessValue::Short(value) => write!(f, "{}s", value),
+            NameLessValue::Int(value) => write!(f, "{}", value),
+            NameLessValue::Long(value) => write!(f, "{}L", value),
+            NameLessValue::Float(value) => write_float(f, *value),
+            NameLessValue::Double(value) => write_double(f, *value),
+            NameLessValue::ByteArray(value) => write_array(f, "[B;", value, "b"),
+            NameLessValue::String(value) => write_string(f, value),
+            NameLessValue::List(value) => write!(f, "{}", value),
+            NameLessValue::Compound(value) => write_compound(f, value),
+            NameLessValue::IntArray(value) => write_array(f, "[I;", value, ""),
+            NameLessValue::LongArray(value) => write_array(f, "[L;", value, "L"),
+            NameLessValue::Boolean(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+impl Display for List {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            List::End => f.write_str("[]"),
+            List::Byte(v) => write_list(f, v, |f, v| write!(f, "{}b", v)),
+            List::Short(v) => write_list(f, v, |f, v| write!(f, "{}s", v)),
+            List::Int(v) => write_list(f, v, |f, v| write!(f, "{}", v)),
+            List::Long(v) => write_list(f, v, |f, v| write!(f, "{}L", v)),
+            List::Float(v) => write_list(f, v, |f, v| write_float(f, *v)),
+            List::Double(v) => write_list(f, v, |f, v| write_double(f, *v)),
+            List::ByteArray(v) => write_list(f, v, |f, v| write_array(f, "[B;", v, "b")),
+            List::String(v) => write_list(f, v, |f, v| write_string(f, v)),
+            List::List(v) => write_list(f, v, |f, v| write!(f, "{}", v)),
+            List::Compound(v) => write_list(f, v, |f, v| write_compound(f, v)),
+            List::IntArray(v) => write_list(f, v, |f, v| write_array(f, "[I;", v, "")),
+            List::LongArray(v) => write_list(f, v, |f, v| write_array(f, "[L;", v, "L")),
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use crate::snbt::parse::lexer::Token;
+use crate::NBTError;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -7,4 +8,6 @@ pub enum Error {
     UnexpectedToken(Token),
     #[error("Name Token Missing")]
     MissingName,
+    #[error(transparent)]
+    NBTErr(#[from] NBTError),
 }
@@ -1,3 +1,4 @@
+mod display;
 mod error;
 
 mod parse;
@@ -15,6 +16,7 @@ use std::io::{Read, Write};
 #[cfg(test)]
 mod tests {
     use crate::snbt::to_value;
+    use crate::value::{List, NameLessValue};
 
     #[test]
     fn it_works() {
@@ -23,6 +25,32 @@ mod tests {
                 .unwrap();
         println!("{:?}", value);
     }
+
+    #[test]
+    fn display() {
+        let value = to_value(r#"{name1:123,name2:"some text",name3:{list:[1b,2b],long:[L;1L]}}"#)
+            .unwrap();
+        assert_eq!(
+            value.to_string(),
+            r#"{name1:123,name2:"some text",name3:{list:[1b,2b],long:[L;1L]}}"#
+        );
+    }
+
+    #[test]
+    fn display_non_finite() {
+        let value = NameLessValue::Compound(vec![
+            NameLessValue::Float(f32::NAN).into_named("nan"),
+            NameLessValue::Float(f32::INFINITY).into_named("infinity"),
+            NameLessValue::Double(f64::NEG_INFINITY).into_named("negative"),
+            NameLessValue::List(List::Double(vec![f64::NAN, 1.5])).into_named("list"),
+        ]);
+        assert_eq!(
+            value.to_string(),
+            "{nan:0f,infinity:1e39f,negative:-1e309d,list:[0d,1.5d]}"
+        );
+        assert_eq!("1e39".parse::<f32>().unwrap(), f32::INFINITY);
+        assert_eq!("-1e309".parse::<f64>().unwrap(), f64::NEG_INFINITY);
+    }
 }
 
 pub fn to_value(str: &str) -> Result<Value, Error> {
@@ -1,7 +1,7 @@
 use crate::snbt::error::Error;
 
 use crate::snbt::parse::lexer::Token;
-use crate::value::NameLessValue;
+use crate::value::{List, NameLessValue};
 use crate::Value;
 use logos::Lexer;
 
@@ -138,44 +138,44 @@ pub fn parse_compound(lex: &mut Lexer<Token>) -> Result<Vec<Value>, Error> {
     }
     Ok(values)
 }
-fn parse_list(lex: &mut Lexer<Token>) -> Result<Vec<NameLessValue>, Error> {
-    let mut values = Vec::new();
+fn parse_list(lex: &mut Lexer<Token>) -> Result<List, Error> {
+    let mut values = List::End;
     while let Some(token) = lex.next() {
         match token {
             Token::StartList => {
                 let value = parse_list(lex)?;
-                values.push(NameLessValue::List(value));
+                values.push(NameLessValue::List(value))?;
             }
             Token::EndListOrArray => {
                 break;
             }
             Token::StartCompound => {
                 let value = parse_compound(lex)?;
-                values.push(NameLessValue::Compound(value));
+                values.push(NameLessValue::Compound(value))?;
             }
             Token::Byte(b) => {
-                values.push(NameLessValue::Byte(b));
+                values.push(NameLessValue::Byte(b))?;
             }
             Token::Short(s) => {
-                values.push(NameLessValue::Short(s));
+                values.push(NameLessValue::Short(s))?;
             }
             Token::Int(i) => {
-                values.push(NameLessValue::Int(i));
+                values.push(NameLessValue::Int(i))?;
             }
             Token::Long(l) => {
-                values.push(NameLessValue::Long(l));
+                values.push(NameLessValue::Long(l))?;
             }
             Token::Float(v) => {
-                values.push(NameLessValue::Float(v));
+                values.push(NameLessValue::Float(v))?;
             }
             Token::True => {
-                values.push(NameLessValue::Boolean(true));
+                values.push(NameLessValue::Boolean(true))?;
             }
             Token::False => {
-                values.push(NameLessValue::Boolean(false));
+                values.push(NameLessValue::Boolean(false))?;
             }
             Token::String(string) => {
-                values.push(NameLessValue::String(string));
+                values.push(NameLessValue::String(string))?;
             }
             Token::ArrayListCompoundSeparator => {}
             Token::Error => {
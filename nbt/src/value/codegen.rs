@@ -0,0 +1,576 @@
+//! Infers a [Schema] from sample values and generates serde structures from it.
+//! ```rust ignore
+//! let samples = files
+//!     .iter()
+//!     .map(|file| Value::read(&mut File::open(file)?))
+//!     .collect::<Result<Vec<_>, _>>()?;
+//! let schema = codegen::infer(&samples);
+//! std::fs::write("src/player.rs", codegen::generate("Player", &schema))?;
+//! ```
+use crate::value::schema::{CompoundSchema, Field, Length, Schema};
+use crate::value::{List, Value, ValueRef};
+use crate::Tag;
+use std::collections::HashSet;
+use std::fmt::Write;
+
+/// The union schema of every sample. See [Schema::infer]
+pub fn infer<'a>(samples: impl IntoIterator<Item = &'a Value>) -> Schema {
+    samples
+        .into_iter()
+        .map(Schema::infer)
+        .fold(Schema::OneOf(Vec::new()), Schema::union)
+}
+
+impl Schema {
+    /// The schema of a single value.
+    ///
+    /// Numbers and strings are not bounded. Arrays keep their length. The elements of an empty
+    /// list are an empty [Schema::OneOf] which is replaced by the first union with a known element
+    pub fn infer(value: &Value) -> Schema {
+        infer_value(value.as_value_ref())
+    }
+
+    /// A schema that accepts the values of both schemas.
+    ///
+    /// Fields missing from either compound become optional. Schemas with different tags become a [Schema::OneOf]
+    pub fn union(self, other: Schema) -> Schema {
+        match (self, other) {
+            (Schema::Any, _) | (_, Schema::Any) => Schema::Any,
+            (one_of @ Schema::OneOf(_), Schema::OneOf(others)) => {
+                others.into_iter().fold(one_of, add_to)
+            }
+            (one_of @ Schema::OneOf(_), other) => add_to(one_of, other),
+            (schema, Schema::OneOf(others)) => others.into_iter().fold(schema, add_to),
+            (
+                Schema::Number { tag, min, max },
+                Schema::Number {
+                    tag: other,
+                    min: other_min,
+                    max: other_max,
+                },
+            ) if tag == other => Schema::Number {
+                tag,
+                min: min.zip(other_min).map(|(a, b)| a.min(b)),
+                max: max.zip(other_max).map(|(a, b)| a.max(b)),
+            },
+            (
+                Schema::String { values, length },
+                Schema::String {
+                    values: other_values,
+                    length: other_length,
+                },
+            ) => Schema::String {
+                // Empty values allow every string
+                values: if values.is_empty() || other_values.is_empty() {
+                    Vec::new()
+                } else {
+                    let mut values = values;
+                    for value in other_values {
+                        if !values.contains(&value) {
+                            values.push(value);
+                        }
+                    }
+                    values
+                },
+                length: length.union(other_length),
+            },
+            (
+                Schema::Array { tag, length },
+                Schema::Array {
+                    tag: other,
+                    length: other_length,
+                },
+            ) if tag == other => Schema::Array {
+                tag,
+                length: length.union(other_length),
+            },
+            (
+                Schema::List { elements, length },
+                Schema::List {
+                    elements: other,
+                    length: other_length,
+                },
+            ) => Schema::List {
+                elements: Box::new(elements.union(*other)),
+                length: length.union(other_length),
+            },
+            (Schema::Compound(compound), Schema::Compound(other)) => {
+                Schema::Compound(compound.union(other))
+            }
+            (schema, other) => Schema::OneOf(vec![schema, other]),
+        }
+    }
+}
+
+/// Adds the schema to a [Schema::OneOf]. Merged into the member with the same tag if there is one
+fn add_to(one_of: Schema, schema: Schema) -> Schema {
+    let mut schemas = match one_of {
+        Schema::OneOf(schemas) => schemas,
+        other => vec![other],
+    };
+    match schemas
+        .iter()
+        .position(|s| s.tag().is_some() && s.tag() == schema.tag())
+    {
+        Some(index) => {
+            let existing = schemas.remove(index);
+            schemas.insert(index, existing.union(schema));
+        }
+        None => schemas.push(schema),
+    }
+    match schemas.len() {
+        1 => schemas.pop().unwrap(),
+        _ => Schema::OneOf(schemas),
+    }
+}
+
+impl Length {
+    fn union(self, other: Length) -> Length {
+        Length {
+            min: self.min.zip(other.min).map(|(a, b)| a.min(b)),
+            max: self.max.zip(other.max).map(|(a, b)| a.max(b)),
+        }
+    }
+}
+
+impl CompoundSchema {
+    fn union(mut self, other: CompoundSchema) -> CompoundSchema {
+        let mut other_fields = other.fields;
+        for field in &mut self.fields {
+            match other_fields.iter().position(|f| f.name == field.name) {
+                Some(index) => {
+                    let other = other_fields.remove(index);
+                    let schema = std::mem::replace(&mut field.schema, Schema::Any);
+                    field.schema = schema.union(other.schema);
+                    field.required &= other.required;
+                }
+                None => field.required = false,
+            }
+        }
+        self.fields
+            .extend(other_fields.into_iter().map(|field| Field {
+                required: false,
+                ..field
+            }));
+        self.deny_unknown &= other.deny_unknown;
+        self.other = match (self.other, other.other) {
+            (Some(a), Some(b)) => Some(Box::new(a.union(*b))),
+            _ => None,
+        };
+        self
+    }
+}
+
+fn exact(length: usize) -> Length {
+    Length {
+        min: Some(length),
+        max: Some(length),
+    }
+}
+
+fn number(tag: Tag) -> Schema {
+    Schema::Number {
+        tag,
+        min: None,
+        max: None,
+    }
+}
+
+fn infer_value(value: ValueRef<'_>) -> Schema {
+    match value {
+        ValueRef::End => Schema::Any,
+        ValueRef::String(_) => Schema::string().into(),
+        ValueRef::ByteArray(array) => Schema::Array {
+            tag: Tag::ByteArray,
+            length: exact(array.len()),
+        },
+        ValueRef::IntArray(array) => Schema::Array {
+            tag: Tag::IntArray,
+            length: exact(array.len()),
+        },
+        ValueRef::LongArray(array) => Schema::Array {
+            tag: Tag::LongArray,
+            length: exact(array.len()),
+        },
+        ValueRef::List(list) => Schema::List {
+            elements: Box::new(infer_list(list)),
+            length: Length::default(),
+        },
+        ValueRef::Compound(entries) => Schema::Compound(CompoundSchema {
+            fields: entries
+                .iter()
+                .filter(|entry| !matches!(entry, Value::End))
+                .map(|entry| Field {
+                    name: entry.get_name().to_string(),
+                    schema: Schema::infer(entry),
+                    required: true,
+                })
+                .collect(),
+            ..CompoundSchema::default()
+        }),
+        other => number(other.tag()),
+    }
+}
+
+fn infer_list(list: &List) -> Schema {
+    match list {
+        // Numbers do not need to look at every element
+        List::Byte(_)
+        | List::Short(_)
+        | List::Int(_)
+        | List::Long(_)
+        | List::Float(_)
+        | List::Double(_)
+            if !list.is_empty() =>
+        {
+            number(list.tag())
+        }
+        List::String(_) if !list.is_empty() => Schema::string().into(),
+        list => list
+            .iter()
+            .map(infer_value)
+            .fold(Schema::OneOf(Vec::new()), Schema::union),
+    }
+}
+
+/// Generates serde structures for the schema. The root must be a compound named `name`.
+///
+/// - Nested compounds become their own structure named after the key
+/// - Optional fields become an `Option`
+/// - Int arrays of 4 elements with `UUID` in the key become a [BinaryUUID](crate::binary::binary_uuid::BinaryUUID)
+/// - Lists of bytes, ints and longs are written with [as_list](crate::serde_impl::as_list)
+/// - Anything else that can not be typed, such as a [Schema::OneOf], becomes a [NameLessValue](crate::value::NameLessValue)
+pub fn generate(name: &str, schema: &Schema) -> String {
+    let mut generator = Generator::default();
+    let root = match schema {
+        Schema::Compound(compound) => compound.clone(),
+        _ => CompoundSchema::default(),
+    };
+    generator.structure(&pascal_case(name), &root);
+
+    let mut output = String::new();
+    if generator.uuid {
+        output.push_str("use axolotl_nbt::binary::binary_uuid::BinaryUUID;\n");
+    }
+    if generator.value {
+        output.push_str("use axolotl_nbt::value::NameLessValue;\n");
+    }
+    output.push_str("use serde::{Deserialize, Serialize};\n");
+    for structure in generator.structures {
+        output.push('\n');
+        output.push_str(&structure);
+    }
+    output
+}
+
+#[derive(Default)]
+struct Generator {
+    structures: Vec<String>,
+    names: HashSet<String>,
+    uuid: bool,
+    value: bool,
+}
+
+impl Generator {
+    fn unique_name(&mut self, name: &str) -> String {
+        let mut unique = name.to_string();
+        let mut suffix = 2;
+        while !self.names.insert(unique.clone()) {
+            unique = format!("{}{}", name, suffix);
+            suffix += 1;
+        }
+        unique
+    }
+
+    /// Adds the structure and the structures of its fields
+    fn structure(&mut self, name: &str, compound: &CompoundSchema) -> String {
+        let name = self.unique_name(name);
+        let index = self.structures.len();
+        // Reserved so parents come before their children
+        self.structures.push(String::new());
+
+        let mut body = String::new();
+        let mut fields = HashSet::new();
+        for field in &compound.fields {
+            let mut attributes = Vec::new();
+            let mut ident = snake_case(&field.name);
+            while !fields.insert(ident.clone()) {
+                ident.push('_');
+            }
+            if ident.trim_start_matches("r#") != field.name {
+                attributes.push(format!("rename = {:?}", field.name));
+            }
+            let (ty, list) = self.field_type(&field.name, &field.schema);
+            let ty = if field.required {
+                ty
+            } else {
+                if list {
+                    attributes.push("default".to_string());
+                    attributes.push("skip_serializing_if = \"Option::is_none\"".to_string());
+                }
+                format!("Option<{}>", ty)
+            };
+            if list {
+                attributes.push("with = \"axolotl_nbt::serde_impl::as_list\"".to_string());
+            }
+            if !attributes.is_empty() {
+                let _ = writeln!(body, "    #[serde({})]", attributes.join(", "));
+            }
+            let _ = writeln!(body, "    pub {}: {},", ident, ty);
+        }
+        self.structures[index] = format!(
+            "#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]\npub struct {} {{\n{}}}\n",
+            name, body
+        );
+        name
+    }
+
+    /// The type of a field. True if the field is a list of numbers that would otherwise be written as an array
+    fn field_type(&mut self, key: &str, schema: &Schema) -> (String, bool) {
+        match schema {
+            Schema::List { elements, .. }
+                if matches!(elements.tag(), Some(Tag::Byte | Tag::Int | Tag::Long)) =>
+            {
+                (format!("Vec<{}>", self.element_type(key, elements)), true)
+            }
+            Schema::Array {
+                tag: Tag::IntArray,
+                length,
+            } if *length == exact(4) && key.to_ascii_uppercase().contains("UUID") => {
+                self.uuid = true;
+                ("BinaryUUID".to_string(), false)
+            }
+            schema => (self.element_type(key, schema), false),
+        }
+    }
+
+    fn element_type(&mut self, key: &str, schema: &Schema) -> String {
+        match schema {
+            Schema::Number { tag, .. } => match tag {
+                Tag::Byte => "i8",
+                Tag::Short => "i16",
+                Tag::Int => "i32",
+                Tag::Long => "i64",
+                Tag::Float => "f32",
+                _ => "f64",
+            }
+            .to_string(),
+            Schema::String { .. } => "String".to_string(),
+            Schema::Array { tag, .. } => match tag {
+                Tag::ByteArray => "Vec<i8>",
+                Tag::IntArray => "Vec<i32>",
+                _ => "Vec<i64>",
+            }
+            .to_string(),
+            // The adapter only applies to the outer list. Inner lists of numbers would become arrays
+            Schema::List { elements, .. }
+                if !matches!(elements.tag(), Some(Tag::Byte | Tag::Int | Tag::Long)) =>
+            {
+                format!("Vec<{}>", self.element_type(key, elements))
+            }
+            Schema::Compound(compound) => self.structure(&pascal_case(key), compound),
+            _ => {
+                self.value = true;
+                "NameLessValue".to_string()
+            }
+        }
+    }
+}
+
+/// Splits a key such as `HurtTime`, `UUIDMost` or `minecraft:hurt-time` into lowercase words
+fn words(key: &str) -> Vec<String> {
+    let chars: Vec<char> = key.chars().collect();
+    let mut words = Vec::new();
+    let mut current = String::new();
+    for (index, c) in chars.iter().copied().enumerate() {
+        if !c.is_ascii_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        let previous = index.checked_sub(1).map(|i| chars[i]);
+        let next = chars.get(index + 1).copied();
+        // A word starts at an uppercase letter after a lowercase letter or digit.
+        // Or at the last uppercase letter of an acronym that is followed by a lowercase letter
+        let boundary = c.is_ascii_uppercase()
+            && (previous.is_some_and(|p| p.is_ascii_lowercase() || p.is_ascii_digit())
+                || (previous.is_some_and(|p| p.is_ascii_uppercase())
+                    && next.is_some_and(|n| n.is_ascii_lowercase())));
+        if boundary && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+        current.push(c.to_ascii_lowercase());
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "box", "break", "const", "continue", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "static", "struct", "trait", "true", "try", "type", "unsafe", "use", "where",
+    "while", "yield",
+];
+
+/// Keywords that can not be raw identifiers
+const PATH_KEYWORDS: &[&str] = &["crate", "self", "super"];
+
+fn snake_case(key: &str) -> String {
+    let ident = words(key).join("_");
+    match ident.chars().next() {
+        None => "field".to_string(),
+        Some(c) if c.is_ascii_digit() => format!("_{}", ident),
+        _ if KEYWORDS.contains(&ident.as_str()) => format!("r#{}", ident),
+        _ if PATH_KEYWORDS.contains(&ident.as_str()) => format!("{}_", ident),
+        _ => ident,
+    }
+}
+
+fn pascal_case(key: &str) -> String {
+    let ident: String = words(key)
+        .iter()
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect();
+    match ident.chars().next() {
+        None => "Compound".to_string(),
+        Some(c) if c.is_ascii_digit() => format!("_{}", ident),
+        // `Self` is the only PascalCase keyword
+        _ if ident == "Self" => "Self_".to_string(),
+        _ => ident,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::snbt::to_value;
+    use crate::value::codegen::{generate, infer, snake_case};
+    use crate::value::schema::{CompoundSchema, Field, Schema};
+    use crate::value::{NameLessValue, Value};
+    use crate::Tag;
+
+    /// The SNBT parser does not read arrays. So the UUID is inserted after the id
+    fn mob(snbt: &str, uuid: i32) -> Value {
+        let Value::Compound { name, mut value } = to_value(snbt).unwrap() else {
+            panic!("Expected a compound");
+        };
+        value.insert(1, NameLessValue::IntArray(vec![uuid; 4]).into_named("UUID"));
+        Value::Compound { name, value }
+    }
+
+    #[test]
+    fn infer_union() {
+        let samples = [
+            mob(r#"{id:"minecraft:pig",Tags:[],Pos:[0s,64s,0s]}"#, 1),
+            mob(r#"{id:"minecraft:cow",Tags:["a"],Age:3}"#, 2),
+        ];
+        let Schema::Compound(CompoundSchema { fields, .. }) = infer(&samples) else {
+            panic!("Expected a compound");
+        };
+        let summary: Vec<(&str, bool, Option<Tag>)> = fields
+            .iter()
+            .map(
+                |Field {
+                     name,
+                     required,
+                     schema,
+                 }| (name.as_str(), *required, schema.tag()),
+            )
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("id", true, Some(Tag::String)),
+                ("UUID", true, Some(Tag::IntArray)),
+                ("Tags", true, Some(Tag::List)),
+                ("Pos", false, Some(Tag::List)),
+                ("Age", false, Some(Tag::Int)),
+            ]
+        );
+        assert_eq!(fields[2].schema, Schema::list(Schema::string()).into());
+        // Different tags under the same key
+        let schema = infer(&[to_value("{a:1}").unwrap(), to_value(r#"{a:"b"}"#).unwrap()]);
+        assert_eq!(
+            schema,
+            Schema::compound()
+                .required(
+                    "a",
+                    Schema::one_of([Schema::int().into(), Schema::string().into()])
+                )
+                .into()
+        );
+    }
+
+    #[test]
+    fn generate_structures() {
+        let samples = [
+            mob(
+                r#"{id:"minecraft:pig",HurtTime:0s,Passengers:[{id:"minecraft:zombie"}],Heights:[1,2],type:1b,Data:{Level:1},Extra:1b}"#,
+                1,
+            ),
+            mob(
+                r#"{id:"minecraft:cow",HurtTime:0s,Passengers:[],Heights:[3],type:2b,Extra:"a"}"#,
+                2,
+            ),
+        ];
+        let code = generate("mob", &infer(&samples));
+        assert_eq!(
+            code,
+            r#"use axolotl_nbt::binary::binary_uuid::BinaryUUID;
+use axolotl_nbt::value::NameLessValue;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Mob {
+    pub id: String,
+    #[serde(rename = "UUID")]
+    pub uuid: BinaryUUID,
+    #[serde(rename = "HurtTime")]
+    pub hurt_time: i16,
+    #[serde(rename = "Passengers")]
+    pub passengers: Vec<Passengers>,
+    #[serde(rename = "Heights", with = "axolotl_nbt::serde_impl::as_list")]
+    pub heights: Vec<i32>,
+    pub r#type: i8,
+    #[serde(rename = "Data")]
+    pub data: Option<Data>,
+    #[serde(rename = "Extra")]
+    pub extra: NameLessValue,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Passengers {
+    pub id: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Data {
+    #[serde(rename = "Level")]
+    pub level: i32,
+}
+"#
+        );
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!(snake_case("HurtTime"), "hurt_time");
+        assert_eq!(snake_case("UUIDMost"), "uuid_most");
+        assert_eq!(snake_case("minecraft:custom_name"), "minecraft_custom_name");
+        assert_eq!(snake_case("1"), "_1");
+        assert_eq!(snake_case("type"), "r#type");
+        assert_eq!(snake_case("crate"), "crate_");
+        assert_eq!(snake_case("Self"), "self_");
+        let code = generate("Keys", &infer(&[to_value("{crate:1,super:2}").unwrap()]));
+        assert!(code.contains("    #[serde(rename = \"crate\")]\n    pub crate_: i32,\n"));
+        assert!(code.contains("    #[serde(rename = \"super\")]\n    pub super_: i32,\n"));
+    }
+}
@@ -0,0 +1,325 @@
+use crate::value::path::NBTPath;
+use crate::value::{List, NameLessValue, Value, ValueRef};
+use std::fmt::{Display, Formatter};
+
+/// A single difference between two NBT trees.
+///
+/// Serialized as a compound tagged by `change`. `{change:"added",path:"Inventory[1]",value:{...}}`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "change", rename_all = "snake_case")
+)]
+pub enum Change {
+    /// The entry only exists in the new tree
+    Added { path: NBTPath, value: NameLessValue },
+    /// The entry only exists in the old tree
+    Removed { path: NBTPath, value: NameLessValue },
+    /// The entry has the same type in both trees but a different value
+    Changed {
+        path: NBTPath,
+        old: NameLessValue,
+        new: NameLessValue,
+    },
+    /// The entry exists in both trees with a different type
+    TypeChanged {
+        path: NBTPath,
+        old: NameLessValue,
+        new: NameLessValue,
+    },
+}
+
+impl Change {
+    pub fn path(&self) -> &NBTPath {
+        match self {
+            Change::Added { path, .. } => path,
+            Change::Removed { path, .. } => path,
+            Change::Changed { path, .. } => path,
+            Change::TypeChanged { path, .. } => path,
+        }
+    }
+}
+
+/// One line per change. Values are written as SNBT
+impl Display for Change {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Change::Added { path, value } => write!(f, "+ {}: {}", path, value),
+            Change::Removed { path, value } => write!(f, "- {}: {}", path, value),
+            Change::Changed { path, old, new } => write!(f, "~ {}: {} -> {}", path, old, new),
+            Change::TypeChanged { path, old, new } => write!(
+                f,
+                "! {}: {:?} {} -> {:?} {}",
+                path,
+                old.tag(),
+                old,
+                new.tag(),
+                new
+            ),
+        }
+    }
+}
+
+/// Compares two trees and returns every difference between them.
+///
+/// Compounds are compared by entry name. Lists are compared by index.
+/// The names of the two roots are ignored
+pub fn diff(old: &Value, new: &Value) -> Vec<Change> {
+    let mut changes = Vec::new();
+    diff_values(
+        &mut NBTPath::root(),
+        old.as_value_ref(),
+        new.as_value_ref(),
+        &mut changes,
+    );
+    changes
+}
+
+/// Formats the changes as a human readable report. One change per line
+pub fn report(changes: &[Change]) -> String {
+    let mut report = String::new();
+    for change in changes {
+        report.push_str(&change.to_string());
+        report.push('\n');
+    }
+    report
+}
+
+/// Only the values that changed are copied
+fn diff_values(
+    path: &mut NBTPath,
+    old: ValueRef<'_>,
+    new: ValueRef<'_>,
+    changes: &mut Vec<Change>,
+) {
+    match (old, new) {
+        (ValueRef::Compound(old), ValueRef::Compound(new)) => {
+            diff_compounds(path, old, new, changes)
+        }
+        (ValueRef::List(old), ValueRef::List(new))
+            if old.tag() == new.tag() || old.is_empty() || new.is_empty() =>
+        {
+            diff_lists(path, old, new, changes)
+        }
+        // Lists whose elements have a different tag
+        (ValueRef::List(_), ValueRef::List(_)) => changes.push(Change::TypeChanged {
+            path: path.clone(),
+            old: old.to_nameless(),
+            new: new.to_nameless(),
+        }),
+        (old, new) => {
+            if old == new {
+                return;
+            }
+            let path = path.clone();
+            let (old, new) = (old.to_nameless(), new.to_nameless());
+            if old.tag() == new.tag() {
+                changes.push(Change::Changed { path, old, new });
+            } else {
+                changes.push(Change::TypeChanged { path, old, new });
+            }
+        }
+    }
+}
+
+fn diff_compounds(path: &mut NBTPath, old: &[Value], new: &[Value], changes: &mut Vec<Change>) {
+    let mut matched = vec![false; new.len()];
+    for old_value in old {
+        if let Value::End = old_value {
+            continue;
+        }
+        let name = old_value.get_name();
+        path.push_key(name);
+        match (0..new.len()).find(|&index| !matched[index] && new[index].get_name() == name) {
+            Some(index) => {
+                matched[index] = true;
+                diff_values(
+                    path,
+                    old_value.as_value_ref(),
+                    new[index].as_value_ref(),
+                    changes,
+                );
+            }
+            None => changes.push(Change::Removed {
+                path: path.clone(),
+                value: old_value.as_value_ref().to_nameless(),
+            }),
+        }
+        path.pop();
+    }
+    for (new_value, matched) in new.iter().zip(matched) {
+        if matched || matches!(new_value, Value::End) {
+            continue;
+        }
+        changes.push(Change::Added {
+            path: path.key(new_value.get_name()),
+            value: new_value.as_value_ref().to_nameless(),
+        });
+    }
+}
+
+fn diff_lists(path: &mut NBTPath, old: &List, new: &List, changes: &mut Vec<Change>) {
+    for index in 0..old.len().max(new.len()) {
+        path.push_index(index);
+        match (old.get(index), new.get(index)) {
+            (Some(old), Some(new)) => diff_values(path, old, new, changes),
+            (Some(value), None) => changes.push(Change::Removed {
+                path: path.clone(),
+                value: value.to_nameless(),
+            }),
+            (None, Some(value)) => changes.push(Change::Added {
+                path: path.clone(),
+                value: value.to_nameless(),
+            }),
+            (None, None) => unreachable!("The index is within one of the lists"),
+        }
+        path.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::value::diff::{diff, report, Change};
+    use crate::value::path::NBTPath;
+    use crate::value::{List, NameLessValue, Value};
+    use crate::Tag;
+
+    fn player(health: f32, items: Vec<Vec<Value>>, extra: Option<Value>) -> Value {
+        let mut value = vec![
+            Value::Float {
+                name: "Health".to_string(),
+                value: health,
+            },
+            Value::List {
+                name: "Inventory".to_string(),
+                value: List::Compound(items),
+            },
+        ];
+        value.extend(extra);
+        Value::Compound {
+            name: "".to_string(),
+            value,
+        }
+    }
+
+    fn item(id: &str, count: i8) -> Vec<Value> {
+        vec![
+            Value::String {
+                name: "id".to_string(),
+                value: id.to_string(),
+            },
+            Value::Byte {
+                name: "Count".to_string(),
+                value: count,
+            },
+        ]
+    }
+
+    #[test]
+    fn diff_trees() {
+        let old = player(
+            20.0,
+            vec![item("minecraft:stone", 64), item("minecraft:dirt", 1)],
+            Some(Value::Int {
+                name: "XpLevel".to_string(),
+                value: 3,
+            }),
+        );
+        let new = player(
+            18.5,
+            vec![item("minecraft:stone", 32)],
+            Some(Value::Long {
+                name: "XpLevel".to_string(),
+                value: 3,
+            }),
+        );
+        let changes = diff(&old, &new);
+        let root = NBTPath::root();
+        assert_eq!(
+            changes,
+            vec![
+                Change::Changed {
+                    path: root.key("Health"),
+                    old: NameLessValue::Float(20.0),
+                    new: NameLessValue::Float(18.5),
+                },
+                Change::Changed {
+                    path: root.key("Inventory").index(0).key("Count"),
+                    old: NameLessValue::Byte(64),
+                    new: NameLessValue::Byte(32),
+                },
+                Change::Removed {
+                    path: root.key("Inventory").index(1),
+                    value: NameLessValue::Compound(item("minecraft:dirt", 1)),
+                },
+                Change::TypeChanged {
+                    path: root.key("XpLevel"),
+                    old: NameLessValue::Int(3),
+                    new: NameLessValue::Long(3),
+                },
+            ]
+        );
+        assert_eq!(
+            report(&changes),
+            "~ Health: 20f -> 18.5f\n\
+             ~ Inventory[0].Count: 64b -> 32b\n\
+             - Inventory[1]: {id:\"minecraft:dirt\",Count:1b}\n\
+             ! XpLevel: Int 3 -> Long 3L\n"
+        );
+        assert!(diff(&old, &old).is_empty());
+
+        // Empty lists are the same whatever their tag
+        let empty = |list: List| Value::List {
+            name: "Tags".to_string(),
+            value: list,
+        };
+        assert!(diff(&empty(List::End), &empty(List::new(Tag::String))).is_empty());
+
+        // Lists of elements of another tag changed type
+        assert_eq!(
+            diff(
+                &empty(List::Int(vec![1])),
+                &empty(List::String(vec!["a".to_string()])),
+            ),
+            vec![Change::TypeChanged {
+                path: NBTPath::root(),
+                old: NameLessValue::List(List::Int(vec![1])),
+                new: NameLessValue::List(List::String(vec!["a".to_string()])),
+            }]
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn changes_as_nbt() {
+        let changes = vec![
+            Change::Added {
+                path: NBTPath::root().key("Inventory").index(1),
+                value: NameLessValue::Compound(item("minecraft:dirt", 1)),
+            },
+            Change::TypeChanged {
+                path: NBTPath::root().key("XpLevel"),
+                old: NameLessValue::Int(3),
+                new: NameLessValue::Long(3),
+            },
+        ];
+        let values = changes
+            .iter()
+            .map(|change| crate::serde_impl::to_value(change).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            values[0].to_string(),
+            r#"{change:"added",path:"Inventory[1]",value:{id:"minecraft:dirt",Count:1b}}"#
+        );
+        assert_eq!(
+            values[1].to_string(),
+            r#"{change:"type_changed",path:"XpLevel",old:3,new:3L}"#
+        );
+        let read = values
+            .into_iter()
+            .map(|value| crate::serde_impl::from_value(value).unwrap())
+            .collect::<Vec<Change>>();
+        assert_eq!(read, changes);
+    }
+}
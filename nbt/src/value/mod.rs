@@ -0,0 +1,575 @@
+use crate::{NBTError, Tag};
+use std::fmt::Debug;
+
+pub mod codegen;
+pub mod diff;
+pub mod patch;
+pub mod path;
+pub mod schema;
+pub mod view;
+
+pub use diff::{diff, Change};
+pub use patch::{Operation, Patch};
+pub use path::{NBTPath, PathSegment};
+pub use schema::{Schema, Violation};
+pub use view::{ValueMut, ValueRef};
+
+#[derive(Clone, Debug)]
+pub enum Value {
+    End,
+    Byte {
+        name: String,
+        value: i8,
+    },
+    Short {
+        name: String,
+        value: i16,
+    },
+    Int {
+        name: String,
+        value: i32,
+    },
+    Long {
+        name: String,
+        value: i64,
+    },
+    Float {
+        name: String,
+        value: f32,
+    },
+    Double {
+        name: String,
+        value: f64,
+    },
+    ByteArray {
+        name: String,
+        value: Vec<i8>,
+    },
+    String {
+        name: String,
+        value: String,
+    },
+    List {
+        name: String,
+        value: List,
+    },
+    Compound {
+        name: String,
+        value: Vec<Value>,
+    },
+    IntArray {
+        name: String,
+        value: Vec<i32>,
+    },
+    LongArray {
+        name: String,
+        value: Vec<i64>,
+    },
+    /// A boolean view over a [Tag::Byte].
+    ///
+    /// NBT has no boolean tag. This is written as a Byte of 0 or 1 and is read back as a Byte.
+    /// So it compares equal to the matching Byte
+    Boolean {
+        name: String,
+        value: bool,
+    },
+}
+
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        if self.get_name() != other.get_name() {
+            return false;
+        }
+        match (self, other) {
+            (Value::End, Value::End) => true,
+            (Value::Byte { value: a, .. }, Value::Byte { value: b, .. }) => a == b,
+            (Value::Short { value: a, .. }, Value::Short { value: b, .. }) => a == b,
+            (Value::Int { value: a, .. }, Value::Int { value: b, .. }) => a == b,
+            (Value::Long { value: a, .. }, Value::Long { value: b, .. }) => a == b,
+            (Value::Float { value: a, .. }, Value::Float { value: b, .. }) => a == b,
+            (Value::Double { value: a, .. }, Value::Double { value: b, .. }) => a == b,
+            (Value::ByteArray { value: a, .. }, Value::ByteArray { value: b, .. }) => a == b,
+            (Value::String { value: a, .. }, Value::String { value: b, .. }) => a == b,
+            (Value::List { value: a, .. }, Value::List { value: b, .. }) => a == b,
+            (Value::Compound { value: a, .. }, Value::Compound { value: b, .. }) => a == b,
+            (Value::IntArray { value: a, .. }, Value::IntArray { value: b, .. }) => a == b,
+            (Value::LongArray { value: a, .. }, Value::LongArray { value: b, .. }) => a == b,
+            (Value::Boolean { value: a, .. }, Value::Boolean { value: b, .. }) => a == b,
+            (Value::Boolean { value: a, .. }, Value::Byte { value: b, .. })
+            | (Value::Byte { value: b, .. }, Value::Boolean { value: a, .. }) => i8::from(*a) == *b,
+            _ => false,
+        }
+    }
+}
+
+impl From<Value> for (NameLessValue, String) {
+    fn from(v: Value) -> Self {
+        match v {
+            Value::End => (NameLessValue::End, String::new()),
+            Value::Byte { value, name } => (NameLessValue::Byte(value), name),
+            Value::Short { value, name } => (NameLessValue::Short(value), name),
+            Value::Int { value, name } => (NameLessValue::Int(value), name),
+            Value::Long { value, name } => (NameLessValue::Long(value), name),
+            Value::Float { value, name } => (NameLessValue::Float(value), name),
+            Value::Double { value, name } => (NameLessValue::Double(value), name),
+            Value::ByteArray { value, name } => (NameLessValue::ByteArray(value), name),
+            Value::String { value, name } => (NameLessValue::String(value), name),
+            Value::List { value, name } => (NameLessValue::List(value), name),
+            Value::Compound { value, name } => (NameLessValue::Compound(value), name),
+            Value::IntArray { value, name } => (NameLessValue::IntArray(value), name),
+            Value::LongArray { value, name } => (NameLessValue::LongArray(value), name),
+            Value::Boolean { value, name } => (NameLessValue::Boolean(value), name),
+        }
+    }
+}
+
+impl Value {
+    pub fn tag(&self) -> Tag {
+        match self {
+            Value::End => Tag::End,
+            Value::Byte { .. } => Tag::Byte,
+            Value::Short { .. } => Tag::Short,
+            Value::Int { .. } => Tag::Int,
+            Value::Long { .. } => Tag::Long,
+            Value::Float { .. } => Tag::Float,
+            Value::Double { .. } => Tag::Double,
+            Value::ByteArray { .. } => Tag::ByteArray,
+            Value::String { .. } => Tag::String,
+            Value::List { .. } => Tag::List,
+            Value::Compound { .. } => Tag::Compound,
+            Value::IntArray { .. } => Tag::IntArray,
+            Value::LongArray { .. } => Tag::LongArray,
+            Value::Boolean { .. } => Tag::Byte,
+        }
+    }
+    pub fn get_name(&self) -> &str {
+        match self {
+            Value::End => "",
+            Value::Byte { name, .. } => name.as_str(),
+            Value::Short { name, .. } => name.as_str(),
+            Value::Int { name, .. } => name.as_str(),
+            Value::Long { name, .. } => name.as_str(),
+
+            Value::Float { name, .. } => name.as_str(),
+            Value::Double { name, .. } => name.as_str(),
+            Value::ByteArray { name, .. } => name.as_str(),
+            Value::String { name, .. } => name.as_str(),
+            Value::List { name, .. } => name.as_str(),
+            Value::Compound { name, .. } => name.as_str(),
+            Value::IntArray { name, .. } => name.as_str(),
+            Value::LongArray { name, .. } => name.as_str(),
+            Value::Boolean { name, .. } => name.as_str(),
+        }
+    }
+    /// Drops the name of the value
+    pub fn into_nameless(self) -> NameLessValue {
+        let (value, _): (NameLessValue, String) = self.into();
+        value
+    }
+    /// Reads the value as a boolean.
+    ///
+    /// Any Byte other than 0 is true. This matches how Minecraft reads booleans
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Byte { value, .. } => Some(*value != 0),
+            Value::Boolean { value, .. } => Some(*value),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum NameLessValue {
+    End,
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    ByteArray(Vec<i8>),
+    String(String),
+    List(List),
+    Compound(Vec<Value>),
+    IntArray(Vec<i32>),
+    LongArray(Vec<i64>),
+    /// A boolean view over a [Tag::Byte]. See [Value::Boolean]
+    Boolean(bool),
+}
+
+impl PartialEq for NameLessValue {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (NameLessValue::End, NameLessValue::End) => true,
+            (NameLessValue::Byte(a), NameLessValue::Byte(b)) => a == b,
+            (NameLessValue::Short(a), NameLessValue::Short(b)) => a == b,
+            (NameLessValue::Int(a), NameLessValue::Int(b)) => a == b,
+            (NameLessValue::Long(a), NameLessValue::Long(b)) => a == b,
+            (NameLessValue::Float(a), NameLessValue::Float(b)) => a == b,
+            (NameLessValue::Double(a), NameLessValue::Double(b)) => a == b,
+            (NameLessValue::ByteArray(a), NameLessValue::ByteArray(b)) => a == b,
+            (NameLessValue::String(a), NameLessValue::String(b)) => a == b,
+            (NameLessValue::List(a), NameLessValue::List(b)) => a == b,
+            (NameLessValue::Compound(a), NameLessValue::Compound(b)) => a == b,
+            (NameLessValue::IntArray(a), NameLessValue::IntArray(b)) => a == b,
+            (NameLessValue::LongArray(a), NameLessValue::LongArray(b)) => a == b,
+            (NameLessValue::Boolean(a), NameLessValue::Boolean(b)) => a == b,
+            (NameLessValue::Boolean(a), NameLessValue::Byte(b))
+            | (NameLessValue::Byte(b), NameLessValue::Boolean(a)) => i8::from(*a) == *b,
+            _ => false,
+        }
+    }
+}
+
+impl NameLessValue {
+    pub fn tag(&self) -> Tag {
+        match self {
+            NameLessValue::End => Tag::End,
+            NameLessValue::Byte(_) => Tag::Byte,
+            NameLessValue::Short(_) => Tag::Short,
+            NameLessValue::Int(_) => Tag::Int,
+            NameLessValue::Long(_) => Tag::Long,
+            NameLessValue::Float(_) => Tag::Float,
+            NameLessValue::Double(_) => Tag::Double,
+            NameLessValue::ByteArray(_) => Tag::ByteArray,
+            NameLessValue::String(_) => Tag::String,
+            NameLessValue::List(_) => Tag::List,
+            NameLessValue::Compound(_) => Tag::Compound,
+            NameLessValue::IntArray(_) => Tag::IntArray,
+            NameLessValue::LongArray(_) => Tag::LongArray,
+            NameLessValue::Boolean(_) => Tag::Byte,
+        }
+    }
+    /// Gives the value a name. The reverse of [Value::into_nameless]
+    pub fn into_named(self, name: impl Into<String>) -> Value {
+        let name = name.into();
+        match self {
+            NameLessValue::End => Value::End,
+            NameLessValue::Byte(value) => Value::Byte { name, value },
+            NameLessValue::Short(value) => Value::Short { name, value },
+            NameLessValue::Int(value) => Value::Int { name, value },
+            NameLessValue::Long(value) => Value::Long { name, value },
+            NameLessValue::Float(value) => Value::Float { name, value },
+            NameLessValue::Double(value) => Value::Double { name, value },
+            NameLessValue::ByteArray(value) => Value::ByteArray { name, value },
+            NameLessValue::String(value) => Value::String { name, value },
+            NameLessValue::List(value) => Value::List { name, value },
+            NameLessValue::Compound(value) => Value::Compound { name, value },
+            NameLessValue::IntArray(value) => Value::IntArray { name, value },
+            NameLessValue::LongArray(value) => Value::LongArray { name, value },
+            NameLessValue::Boolean(value) => Value::Boolean { name, value },
+        }
+    }
+    /// Reads the value as a boolean. See [Value::as_bool]
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            NameLessValue::Byte(value) => Some(*value != 0),
+            NameLessValue::Boolean(value) => Some(*value),
+            _ => None,
+        }
+    }
+}
+
+/// A NBT List. Every element has the same [Tag]
+///
+/// Numeric elements are stored in their own Vec. So a list of a million ints is a `Vec<i32>`
+#[derive(Clone, Debug, Default)]
+pub enum List {
+    /// An empty list that has no element type yet
+    #[default]
+    End,
+    Byte(Vec<i8>),
+    Short(Vec<i16>),
+    Int(Vec<i32>),
+    Long(Vec<i64>),
+    Float(Vec<f32>),
+    Double(Vec<f64>),
+    ByteArray(Vec<Vec<i8>>),
+    String(Vec<String>),
+    List(Vec<List>),
+    Compound(Vec<Vec<Value>>),
+    IntArray(Vec<Vec<i32>>),
+    LongArray(Vec<Vec<i64>>),
+}
+
+/// Empty lists are equal whatever their tag. Vanilla writes every empty list with the tag End,
+/// so [List::End] and `List::new(Tag::Int)` are the same list
+impl PartialEq for List {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (List::Byte(a), List::Byte(b)) => a == b,
+            (List::Short(a), List::Short(b)) => a == b,
+            (List::Int(a), List::Int(b)) => a == b,
+            (List::Long(a), List::Long(b)) => a == b,
+            (List::Float(a), List::Float(b)) => a == b,
+            (List::Double(a), List::Double(b)) => a == b,
+            (List::ByteArray(a), List::ByteArray(b)) => a == b,
+            (List::String(a), List::String(b)) => a == b,
+            (List::List(a), List::List(b)) => a == b,
+            (List::Compound(a), List::Compound(b)) => a == b,
+            (List::IntArray(a), List::IntArray(b)) => a == b,
+            (List::LongArray(a), List::LongArray(b)) => a == b,
+            (a, b) => a.is_empty() && b.is_empty(),
+        }
+    }
+}
+
+impl List {
+    /// Creates an empty list that will only accept elements of the given tag
+    pub fn new(tag: Tag) -> Self {
+        match tag {
+            Tag::End => List::End,
+            Tag::Byte => List::Byte(Vec::new()),
+            Tag::Short => List::Short(Vec::new()),
+            Tag::Int => List::Int(Vec::new()),
+            Tag::Long => List::Long(Vec::new()),
+            Tag::Float => List::Float(Vec::new()),
+            Tag::Double => List::Double(Vec::new()),
+            Tag::ByteArray => List::ByteArray(Vec::new()),
+            Tag::String => List::String(Vec::new()),
+            Tag::List => List::List(Vec::new()),
+            Tag::Compound => List::Compound(Vec::new()),
+            Tag::IntArray => List::IntArray(Vec::new()),
+            Tag::LongArray => List::LongArray(Vec::new()),
+        }
+    }
+    /// The tag of the elements within the list
+    pub fn tag(&self) -> Tag {
+        match self {
+            List::End => Tag::End,
+            List::Byte(_) => Tag::Byte,
+            List::Short(_) => Tag::Short,
+            List::Int(_) => Tag::Int,
+            List::Long(_) => Tag::Long,
+            List::Float(_) => Tag::Float,
+            List::Double(_) => Tag::Double,
+            List::ByteArray(_) => Tag::ByteArray,
+            List::String(_) => Tag::String,
+            List::List(_) => Tag::List,
+            List::Compound(_) => Tag::Compound,
+            List::IntArray(_) => Tag::IntArray,
+            List::LongArray(_) => Tag::LongArray,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            List::End => 0,
+            List::Byte(v) => v.len(),
+            List::Short(v) => v.len(),
+            List::Int(v) => v.len(),
+            List::Long(v) => v.len(),
+            List::Float(v) => v.len(),
+            List::Double(v) => v.len(),
+            List::ByteArray(v) => v.len(),
+            List::String(v) => v.len(),
+            List::List(v) => v.len(),
+            List::Compound(v) => v.len(),
+            List::IntArray(v) => v.len(),
+            List::LongArray(v) => v.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Pushes a value to the end of the list.
+    ///
+    /// An untyped empty list takes the tag of the first value.
+    /// Booleans are stored as Bytes
+    pub fn push(&mut self, value: NameLessValue) -> Result<(), NBTError> {
+        self.insert(self.len(), value)
+    }
+
+    /// Inserts a value at the index. Follows the same rules as [List::push]
+    pub fn insert(&mut self, index: usize, value: NameLessValue) -> Result<(), NBTError> {
+        if index > self.len() {
+            return Err(NBTError::IndexOutOfBounds(index, self.len()));
+        }
+        if let List::End = self {
+            *self = List::new(value.tag());
+        }
+        match (self, value) {
+            (List::Byte(list), NameLessValue::Byte(v)) => list.insert(index, v),
+            (List::Byte(list), NameLessValue::Boolean(v)) => list.insert(index, i8::from(v)),
+            (List::Short(list), NameLessValue::Short(v)) => list.insert(index, v),
+            (List::Int(list), NameLessValue::Int(v)) => list.insert(index, v),
+            (List::Long(list), NameLessValue::Long(v)) => list.insert(index, v),
+            (List::Float(list), NameLessValue::Float(v)) => list.insert(index, v),
+            (List::Double(list), NameLessValue::Double(v)) => list.insert(index, v),
+            (List::ByteArray(list), NameLessValue::ByteArray(v)) => list.insert(index, v),
+            (List::String(list), NameLessValue::String(v)) => list.insert(index, v),
+            (List::List(list), NameLessValue::List(v)) => list.insert(index, v),
+            (List::Compound(list), NameLessValue::Compound(v)) => list.insert(index, v),
+            (List::IntArray(list), NameLessValue::IntArray(v)) => list.insert(index, v),
+            (List::LongArray(list), NameLessValue::LongArray(v)) => list.insert(index, v),
+            (_, NameLessValue::End) => return Err(NBTError::UnexpectedEnd),
+            (list, value) => return Err(NBTError::ListTypeMismatch(list.tag(), value.tag())),
+        }
+        Ok(())
+    }
+
+    /// Removes and returns the element at the index.
+    ///
+    /// The list keeps its element type when it becomes empty
+    pub fn remove(&mut self, index: usize) -> Result<NameLessValue, NBTError> {
+        if index >= self.len() {
+            return Err(NBTError::IndexOutOfBounds(index, self.len()));
+        }
+        let value = match self {
+            List::End => unreachable!("An empty list has no elements"),
+            List::Byte(v) => NameLessValue::Byte(v.remove(index)),
+            List::Short(v) => NameLessValue::Short(v.remove(index)),
+            List::Int(v) => NameLessValue::Int(v.remove(index)),
+            List::Long(v) => NameLessValue::Long(v.remove(index)),
+            List::Float(v) => NameLessValue::Float(v.remove(index)),
+            List::Double(v) => NameLessValue::Double(v.remove(index)),
+            List::ByteArray(v) => NameLessValue::ByteArray(v.remove(index)),
+            List::String(v) => NameLessValue::String(v.remove(index)),
+            List::List(v) => NameLessValue::List(v.remove(index)),
+            List::Compound(v) => NameLessValue::Compound(v.remove(index)),
+            List::IntArray(v) => NameLessValue::IntArray(v.remove(index)),
+            List::LongArray(v) => NameLessValue::LongArray(v.remove(index)),
+        };
+        Ok(value)
+    }
+
+    /// Replaces the element at the index. Returns the old element
+    pub fn set(&mut self, index: usize, value: NameLessValue) -> Result<NameLessValue, NBTError> {
+        if index >= self.len() {
+            return Err(NBTError::IndexOutOfBounds(index, self.len()));
+        }
+        if self.tag() != value.tag() {
+            return Err(NBTError::ListTypeMismatch(self.tag(), value.tag()));
+        }
+        let old = self.remove(index)?;
+        self.insert(index, value)?;
+        Ok(old)
+    }
+}
+
+pub enum ListIntoIter {
+    End,
+    Byte(std::vec::IntoIter<i8>),
+    Short(std::vec::IntoIter<i16>),
+    Int(std::vec::IntoIter<i32>),
+    Long(std::vec::IntoIter<i64>),
+    Float(std::vec::IntoIter<f32>),
+    Double(std::vec::IntoIter<f64>),
+    ByteArray(std::vec::IntoIter<Vec<i8>>),
+    String(std::vec::IntoIter<String>),
+    List(std::vec::IntoIter<List>),
+    Compound(std::vec::IntoIter<Vec<Value>>),
+    IntArray(std::vec::IntoIter<Vec<i32>>),
+    LongArray(std::vec::IntoIter<Vec<i64>>),
+}
+
+impl Iterator for ListIntoIter {
+    type Item = NameLessValue;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            ListIntoIter::End => None,
+            ListIntoIter::Byte(v) => v.next().map(NameLessValue::Byte),
+            ListIntoIter::Short(v) => v.next().map(NameLessValue::Short),
+            ListIntoIter::Int(v) => v.next().map(NameLessValue::Int),
+            ListIntoIter::Long(v) => v.next().map(NameLessValue::Long),
+            ListIntoIter::Float(v) => v.next().map(NameLessValue::Float),
+            ListIntoIter::Double(v) => v.next().map(NameLessValue::Double),
+            ListIntoIter::ByteArray(v) => v.next().map(NameLessValue::ByteArray),
+            ListIntoIter::String(v) => v.next().map(NameLessValue::String),
+            ListIntoIter::List(v) => v.next().map(NameLessValue::List),
+            ListIntoIter::Compound(v) => v.next().map(NameLessValue::Compound),
+            ListIntoIter::IntArray(v) => v.next().map(NameLessValue::IntArray),
+            ListIntoIter::LongArray(v) => v.next().map(NameLessValue::LongArray),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            ListIntoIter::End => (0, Some(0)),
+            ListIntoIter::Byte(v) => v.size_hint(),
+            ListIntoIter::Short(v) => v.size_hint(),
+            ListIntoIter::Int(v) => v.size_hint(),
+            ListIntoIter::Long(v) => v.size_hint(),
+            ListIntoIter::Float(v) => v.size_hint(),
+            ListIntoIter::Double(v) => v.size_hint(),
+            ListIntoIter::ByteArray(v) => v.size_hint(),
+            ListIntoIter::String(v) => v.size_hint(),
+            ListIntoIter::List(v) => v.size_hint(),
+            ListIntoIter::Compound(v) => v.size_hint(),
+            ListIntoIter::IntArray(v) => v.size_hint(),
+            ListIntoIter::LongArray(v) => v.size_hint(),
+        }
+    }
+}
+
+impl ExactSizeIterator for ListIntoIter {}
+
+impl IntoIterator for List {
+    type Item = NameLessValue;
+    type IntoIter = ListIntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        match self {
+            List::End => ListIntoIter::End,
+            List::Byte(v) => ListIntoIter::Byte(v.into_iter()),
+            List::Short(v) => ListIntoIter::Short(v.into_iter()),
+            List::Int(v) => ListIntoIter::Int(v.into_iter()),
+            List::Long(v) => ListIntoIter::Long(v.into_iter()),
+            List::Float(v) => ListIntoIter::Float(v.into_iter()),
+            List::Double(v) => ListIntoIter::Double(v.into_iter()),
+            List::ByteArray(v) => ListIntoIter::ByteArray(v.into_iter()),
+            List::String(v) => ListIntoIter::String(v.into_iter()),
+            List::List(v) => ListIntoIter::List(v.into_iter()),
+            List::Compound(v) => ListIntoIter::Compound(v.into_iter()),
+            List::IntArray(v) => ListIntoIter::IntArray(v.into_iter()),
+            List::LongArray(v) => ListIntoIter::LongArray(v.into_iter()),
+        }
+    }
+}
+
+impl TryFrom<Vec<NameLessValue>> for List {
+    type Error = NBTError;
+
+    fn try_from(values: Vec<NameLessValue>) -> Result<Self, Self::Error> {
+        let mut list = List::End;
+        for value in values {
+            list.push(value)?;
+        }
+        Ok(list)
+    }
+}
+
+impl From<List> for Vec<NameLessValue> {
+    fn from(list: List) -> Self {
+        list.into_iter().collect()
+    }
+}
+
+macro_rules! list_from_vec {
+    ($($ty:ty => $variant:ident),*) => {
+        $(
+            impl From<Vec<$ty>> for List {
+                fn from(v: Vec<$ty>) -> Self {
+                    List::$variant(v)
+                }
+            }
+        )*
+    };
+}
+list_from_vec!(
+    i8 => Byte,
+    i16 => Short,
+    i32 => Int,
+    i64 => Long,
+    f32 => Float,
+    f64 => Double,
+    Vec<i8> => ByteArray,
+    String => String,
+    List => List,
+    Vec<Value> => Compound,
+    Vec<i32> => IntArray,
+    Vec<i64> => LongArray
+);
@@ -0,0 +1,625 @@
+//! Deep merging and RFC 6902 style patches for [Value] trees
+use crate::value::path::{NBTPath, PathSegment};
+use crate::value::{List, NameLessValue, Value, ValueMut};
+use crate::{NBTError, Tag};
+use std::str::FromStr;
+
+impl Value {
+    /// Merges `other` into this value the same way `/data merge` does.
+    ///
+    /// Entries of `other` replace the entries with the same name. Unless both are compounds
+    /// in which case they are merged. Lists are replaced, not merged.
+    /// If either value is not a compound this value is replaced and keeps its name
+    pub fn merge(&mut self, other: Value) {
+        match (self, other) {
+            (Value::Compound { value, .. }, Value::Compound { value: other, .. }) => {
+                merge_compound(value, other)
+            }
+            (this, other) => {
+                let (_, name): (NameLessValue, String) = std::mem::replace(this, Value::End).into();
+                *this = other.into_nameless().into_named(name);
+            }
+        }
+    }
+
+    /// Applies the patch. If any operation fails the value is left unchanged
+    pub fn apply(&mut self, patch: &Patch) -> Result<(), NBTError> {
+        let mut value = self.clone();
+        value.apply_in_place(patch)?;
+        *self = value;
+        Ok(())
+    }
+
+    /// Applies the patch without a copy to fall back to. A failed operation keeps the operations before it
+    pub(crate) fn apply_in_place(&mut self, patch: &Patch) -> Result<(), NBTError> {
+        let (mut value, name): (NameLessValue, String) = std::mem::replace(self, Value::End).into();
+        let result = patch
+            .0
+            .iter()
+            .try_for_each(|operation| operation.apply(&mut value));
+        *self = value.into_named(name);
+        result
+    }
+}
+
+fn merge_compound(target: &mut Vec<Value>, source: Vec<Value>) {
+    for entry in source {
+        if let Value::End = entry {
+            continue;
+        }
+        match target.iter_mut().find(|v| v.get_name() == entry.get_name()) {
+            Some(existing) => existing.merge(entry),
+            None => target.push(entry),
+        }
+    }
+}
+
+fn not_found(path: &NBTPath) -> NBTError {
+    NBTError::PathNotFound(path.to_string())
+}
+
+/// The value that holds the last segment of the path
+fn parent<'a, 'p>(
+    root: &'a mut NameLessValue,
+    path: &'p NBTPath,
+) -> Result<(ValueMut<'a>, &'p PathSegment), NBTError> {
+    let (last, segments) = path
+        .segments()
+        .split_last()
+        .ok_or_else(|| not_found(path))?;
+    let parent = segments
+        .iter()
+        .try_fold(root.as_value_mut(), |value, segment| value.child(segment))
+        .ok_or_else(|| not_found(path))?;
+    Ok((parent, last))
+}
+
+fn byte(value: NameLessValue) -> Result<i8, NBTError> {
+    match value {
+        NameLessValue::Byte(v) => Ok(v),
+        NameLessValue::Boolean(v) => Ok(i8::from(v)),
+        value => Err(NBTError::ListTypeMismatch(Tag::ByteArray, value.tag())),
+    }
+}
+
+fn int(value: NameLessValue) -> Result<i32, NBTError> {
+    match value {
+        NameLessValue::Int(v) => Ok(v),
+        value => Err(NBTError::ListTypeMismatch(Tag::IntArray, value.tag())),
+    }
+}
+
+fn long(value: NameLessValue) -> Result<i64, NBTError> {
+    match value {
+        NameLessValue::Long(v) => Ok(v),
+        value => Err(NBTError::ListTypeMismatch(Tag::LongArray, value.tag())),
+    }
+}
+
+fn insert_element<T>(
+    array: &mut Vec<T>,
+    segment: &PathSegment,
+    value: NameLessValue,
+    element: impl FnOnce(NameLessValue) -> Result<T, NBTError>,
+    path: &NBTPath,
+) -> Result<(), NBTError> {
+    let index = match segment {
+        PathSegment::Index(index) if *index > array.len() => {
+            return Err(NBTError::IndexOutOfBounds(*index, array.len()))
+        }
+        PathSegment::Index(index) => *index,
+        PathSegment::Append => array.len(),
+        PathSegment::Key(_) => return Err(not_found(path)),
+    };
+    array.insert(index, element(value)?);
+    Ok(())
+}
+
+fn replace_element<T>(
+    array: &mut [T],
+    segment: &PathSegment,
+    value: NameLessValue,
+    element: impl FnOnce(NameLessValue) -> Result<T, NBTError>,
+    path: &NBTPath,
+) -> Result<(), NBTError> {
+    match segment {
+        PathSegment::Index(index) if *index < array.len() => {
+            array[*index] = element(value)?;
+            Ok(())
+        }
+        _ => Err(not_found(path)),
+    }
+}
+
+/// Sets a compound entry or inserts into a list or array
+fn add(
+    parent: ValueMut<'_>,
+    segment: &PathSegment,
+    value: NameLessValue,
+    path: &NBTPath,
+) -> Result<(), NBTError> {
+    match (parent, segment) {
+        (ValueMut::Compound(entries), PathSegment::Key(key)) => {
+            let value = value.into_named(key.clone());
+            match entries.iter_mut().find(|v| v.get_name() == key) {
+                Some(existing) => *existing = value,
+                None => entries.push(value),
+            }
+            Ok(())
+        }
+        (ValueMut::List(list), PathSegment::Index(index)) => list.insert(*index, value),
+        (ValueMut::List(list), PathSegment::Append) => list.push(value),
+        (ValueMut::ByteArray(array), segment) => insert_element(array, segment, value, byte, path),
+        (ValueMut::IntArray(array), segment) => insert_element(array, segment, value, int, path),
+        (ValueMut::LongArray(array), segment) => insert_element(array, segment, value, long, path),
+        _ => Err(not_found(path)),
+    }
+}
+
+/// Replaces an existing entry or element where it is
+fn replace(
+    parent: ValueMut<'_>,
+    segment: &PathSegment,
+    value: NameLessValue,
+    path: &NBTPath,
+) -> Result<(), NBTError> {
+    match (parent, segment) {
+        (ValueMut::Compound(entries), PathSegment::Key(key)) => {
+            let entry = entries
+                .iter_mut()
+                .find(|v| v.get_name() == key)
+                .ok_or_else(|| not_found(path))?;
+            *entry = value.into_named(key.clone());
+            Ok(())
+        }
+        (ValueMut::List(list), PathSegment::Index(index)) if *index < list.len() => {
+            list.set(*index, value)?;
+            Ok(())
+        }
+        (ValueMut::ByteArray(array), segment) => replace_element(array, segment, value, byte, path),
+        (ValueMut::IntArray(array), segment) => replace_element(array, segment, value, int, path),
+        (ValueMut::LongArray(array), segment) => replace_element(array, segment, value, long, path),
+        _ => Err(not_found(path)),
+    }
+}
+
+fn remove(
+    parent: ValueMut<'_>,
+    segment: &PathSegment,
+    path: &NBTPath,
+) -> Result<NameLessValue, NBTError> {
+    match (parent, segment) {
+        (ValueMut::Compound(entries), PathSegment::Key(key)) => {
+            let index = entries
+                .iter()
+                .position(|v| v.get_name() == key)
+                .ok_or_else(|| not_found(path))?;
+            Ok(entries.remove(index).into_nameless())
+        }
+        (ValueMut::List(list), PathSegment::Index(index)) => {
+            list.remove(*index).map_err(|_| not_found(path))
+        }
+        (ValueMut::ByteArray(array), PathSegment::Index(index)) if *index < array.len() => {
+            Ok(NameLessValue::Byte(array.remove(*index)))
+        }
+        (ValueMut::IntArray(array), PathSegment::Index(index)) if *index < array.len() => {
+            Ok(NameLessValue::Int(array.remove(*index)))
+        }
+        (ValueMut::LongArray(array), PathSegment::Index(index)) if *index < array.len() => {
+            Ok(NameLessValue::Long(array.remove(*index)))
+        }
+        _ => Err(not_found(path)),
+    }
+}
+
+/// A single step of a [Patch]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operation {
+    /// Sets a compound entry or inserts into a list or array at the index. `[-]` appends
+    Add { path: NBTPath, value: NameLessValue },
+    /// Removes the value. It must exist
+    Remove { path: NBTPath },
+    /// Replaces the value where it is. It must exist
+    Replace { path: NBTPath, value: NameLessValue },
+    /// Fails the patch if the value is not equal
+    Test { path: NBTPath, value: NameLessValue },
+    /// Removes the value at `from` and adds it at `path`
+    Move { from: NBTPath, path: NBTPath },
+    /// Adds a copy of the value at `from` at `path`
+    Copy { from: NBTPath, path: NBTPath },
+    /// Merges the value into the value at the path. See [Value::merge]
+    Merge { path: NBTPath, value: NameLessValue },
+}
+
+impl Operation {
+    pub fn apply(&self, root: &mut NameLessValue) -> Result<(), NBTError> {
+        match self {
+            Operation::Add { path, value } => set(root, path, value.clone()),
+            Operation::Remove { path } => {
+                let (parent, last) = parent(root, path)?;
+                remove(parent, last, path)?;
+                Ok(())
+            }
+            Operation::Replace { path, value } => replace_at(root, path, value.clone()),
+            Operation::Test { path, value } => {
+                if root.get_path(path).ok_or_else(|| not_found(path))? == *value {
+                    Ok(())
+                } else {
+                    Err(NBTError::TestFailed(path.to_string()))
+                }
+            }
+            Operation::Move { from, path } => {
+                let value = if from.is_root() {
+                    std::mem::replace(root, NameLessValue::Compound(vec![]))
+                } else {
+                    let (parent, last) = parent(root, from)?;
+                    remove(parent, last, from)?
+                };
+                set(root, path, value)
+            }
+            Operation::Copy { from, path } => {
+                let value = root.get_path(from).ok_or_else(|| not_found(from))?;
+                set(root, path, value.to_nameless())
+            }
+            Operation::Merge { path, value } => match (root.get_path_mut(path), value) {
+                (Some(ValueMut::Compound(entries)), NameLessValue::Compound(other)) => {
+                    merge_compound(entries, other.clone());
+                    Ok(())
+                }
+                (Some(_), value) => replace_at(root, path, value.clone()),
+                (None, _) => Err(not_found(path)),
+            },
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Operation::Add { .. } => "add",
+            Operation::Remove { .. } => "remove",
+            Operation::Replace { .. } => "replace",
+            Operation::Test { .. } => "test",
+            Operation::Move { .. } => "move",
+            Operation::Copy { .. } => "copy",
+            Operation::Merge { .. } => "merge",
+        }
+    }
+}
+
+fn set(root: &mut NameLessValue, path: &NBTPath, value: NameLessValue) -> Result<(), NBTError> {
+    if path.is_root() {
+        *root = value;
+        return Ok(());
+    }
+    let (parent, last) = parent(root, path)?;
+    add(parent, last, value, path)
+}
+
+fn replace_at(
+    root: &mut NameLessValue,
+    path: &NBTPath,
+    value: NameLessValue,
+) -> Result<(), NBTError> {
+    if path.is_root() {
+        *root = value;
+        return Ok(());
+    }
+    let (parent, last) = parent(root, path)?;
+    replace(parent, last, value, path)
+}
+
+/// A list of operations applied in order.
+///
+/// Stored in NBT as a list of compounds
+/// `[{op:"add",path:"tag.display.Name",value:"\"Sword\""},{op:"remove",path:"tag.Damage"}]`
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Patch(pub Vec<Operation>);
+
+impl Patch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(mut self, operation: Operation) -> Self {
+        self.0.push(operation);
+        self
+    }
+}
+
+impl From<&Patch> for NameLessValue {
+    fn from(patch: &Patch) -> Self {
+        let mut list = List::new(Tag::Compound);
+        for operation in &patch.0 {
+            let mut compound = vec![Value::String {
+                name: "op".to_string(),
+                value: operation.name().to_string(),
+            }];
+            let (path, from, value) = match operation {
+                Operation::Add { path, value }
+                | Operation::Replace { path, value }
+                | Operation::Test { path, value }
+                | Operation::Merge { path, value } => (path, None, Some(value)),
+                Operation::Remove { path } => (path, None, None),
+                Operation::Move { from, path } | Operation::Copy { from, path } => {
+                    (path, Some(from), None)
+                }
+            };
+            compound.push(Value::String {
+                name: "path".to_string(),
+                value: path.to_string(),
+            });
+            if let Some(from) = from {
+                compound.push(Value::String {
+                    name: "from".to_string(),
+                    value: from.to_string(),
+                });
+            }
+            if let Some(value) = value {
+                compound.push(value.clone().into_named("value"));
+            }
+            list.push(NameLessValue::Compound(compound))
+                .expect("Every element is a compound");
+        }
+        NameLessValue::List(list)
+    }
+}
+
+impl TryFrom<NameLessValue> for Patch {
+    type Error = NBTError;
+
+    fn try_from(value: NameLessValue) -> Result<Self, Self::Error> {
+        let list = match value {
+            NameLessValue::List(list) => list,
+            value => return Err(NBTError::ExpectedTag(Tag::List, value.tag())),
+        };
+        let mut operations = Vec::with_capacity(list.len());
+        for operation in list {
+            let entries = match operation {
+                NameLessValue::Compound(entries) => entries,
+                value => return Err(NBTError::ExpectedTag(Tag::Compound, value.tag())),
+            };
+            let mut op = None;
+            let mut path = None;
+            let mut from = None;
+            let mut value = None;
+            for entry in entries {
+                match entry {
+                    Value::String { name, value: v } if name == "op" => op = Some(v),
+                    Value::String { name, value: v } if name == "path" => {
+                        path = Some(NBTPath::from_str(&v)?)
+                    }
+                    Value::String { name, value: v } if name == "from" => {
+                        from = Some(NBTPath::from_str(&v)?)
+                    }
+                    entry if entry.get_name() == "value" => value = Some(entry.into_nameless()),
+                    _ => {}
+                }
+            }
+            let missing = |field: &str| NBTError::MissingField(field.to_string());
+            let path = path.ok_or_else(|| missing("path"))?;
+            let operation = match op.as_deref() {
+                Some("add") => Operation::Add {
+                    path,
+                    value: value.ok_or_else(|| missing("value"))?,
+                },
+                Some("remove") => Operation::Remove { path },
+                Some("replace") => Operation::Replace {
+                    path,
+                    value: value.ok_or_else(|| missing("value"))?,
+                },
+                Some("test") => Operation::Test {
+                    path,
+                    value: value.ok_or_else(|| missing("value"))?,
+                },
+                Some("move") => Operation::Move {
+                    from: from.ok_or_else(|| missing("from"))?,
+                    path,
+                },
+                Some("copy") => Operation::Copy {
+                    from: from.ok_or_else(|| missing("from"))?,
+                    path,
+                },
+                Some("merge") => Operation::Merge {
+                    path,
+                    value: value.ok_or_else(|| missing("value"))?,
+                },
+                Some(op) => return Err(NBTError::UnknownVariant(op.to_string())),
+                None => return Err(missing("op")),
+            };
+            operations.push(operation);
+        }
+        Ok(Patch(operations))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Patch {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        NameLessValue::from(self).serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Patch {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let value = NameLessValue::deserialize(deserializer)?;
+        Patch::try_from(value).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::value::patch::{Operation, Patch};
+    use crate::value::path::NBTPath;
+    use crate::value::{List, NameLessValue, Value, ValueMut, ValueRef};
+    use crate::NBTError;
+    use std::str::FromStr;
+
+    fn item() -> Value {
+        crate::snbt::to_value(
+            r#"{id:"minecraft:diamond_sword",Count:1b,tag:{Damage:5,display:{Name:"Sword"},Enchantments:[{id:"sharpness",lvl:1s}]}}"#,
+        )
+        .unwrap()
+    }
+
+    fn path(path: &str) -> NBTPath {
+        NBTPath::from_str(path).unwrap()
+    }
+
+    #[test]
+    fn merge() {
+        let mut value = item();
+        value.merge(
+            crate::snbt::to_value(r#"{Count:2b,tag:{display:{Lore:["a"]},Unbreakable:1b}}"#)
+                .unwrap(),
+        );
+        assert_eq!(
+            value.to_string(),
+            r#"{id:"minecraft:diamond_sword",Count:2b,tag:{Damage:5,display:{Name:"Sword",Lore:["a"]},Enchantments:[{id:"sharpness",lvl:1s}],Unbreakable:1b}}"#
+        );
+    }
+
+    #[test]
+    fn apply() {
+        let patch = Patch::new()
+            .push(Operation::Test {
+                path: path("id"),
+                value: NameLessValue::String("minecraft:diamond_sword".to_string()),
+            })
+            .push(Operation::Move {
+                from: path("tag.Damage"),
+                path: path(r#"components."minecraft:damage""#),
+            })
+            .push(Operation::Replace {
+                path: path("tag.Enchantments[0].lvl"),
+                value: NameLessValue::Short(3),
+            })
+            .push(Operation::Add {
+                path: path("tag.Enchantments[0]"),
+                value: NameLessValue::Compound(vec![]),
+            })
+            .push(Operation::Remove {
+                path: path("tag.display"),
+            });
+        let mut value = crate::snbt::to_value(
+            r#"{id:"minecraft:diamond_sword",tag:{Damage:5,display:{Name:"Sword"},Enchantments:[{id:"sharpness",lvl:1s}]},components:{}}"#,
+        )
+        .unwrap();
+        value.apply(&patch).unwrap();
+        assert_eq!(
+            value.to_string(),
+            r#"{id:"minecraft:diamond_sword",tag:{Enchantments:[{},{id:"sharpness",lvl:3s}]},components:{"minecraft:damage":5}}"#
+        );
+        assert_eq!(
+            value.get_path(&path("tag.Enchantments[1].lvl")),
+            Some(ValueRef::Short(3))
+        );
+    }
+
+    #[test]
+    fn in_place() {
+        let mut value = item();
+        let patch = Patch::new()
+            .push(Operation::Replace {
+                path: path("id"),
+                value: NameLessValue::String("minecraft:stick".to_string()),
+            })
+            .push(Operation::Merge {
+                path: path("tag"),
+                value: NameLessValue::Compound(vec![NameLessValue::Int(1).into_named("Damage")]),
+            })
+            .push(Operation::Merge {
+                path: path("Count"),
+                value: NameLessValue::Byte(3),
+            })
+            .push(Operation::Add {
+                path: path("tag.Enchantments[-]"),
+                value: NameLessValue::Compound(vec![]),
+            })
+            .push(Operation::Add {
+                path: path("tag.Tags"),
+                value: NameLessValue::IntArray(vec![1]),
+            })
+            .push(Operation::Add {
+                path: path("tag.Tags[-]"),
+                value: NameLessValue::Int(2),
+            });
+        value.apply(&patch).unwrap();
+        // Replaced and merged entries keep their position
+        assert_eq!(
+            value.to_string(),
+            r#"{id:"minecraft:stick",Count:3b,tag:{Damage:1,display:{Name:"Sword"},Enchantments:[{id:"sharpness",lvl:1s},{}],Tags:[I;1,2]}}"#
+        );
+        // The append position holds no value
+        let remove = Patch::new().push(Operation::Remove {
+            path: path("tag.Enchantments[-]"),
+        });
+        assert!(matches!(
+            value.apply(&remove),
+            Err(NBTError::PathNotFound(_))
+        ));
+
+        if let Some(ValueMut::Short(lvl)) = value.get_path_mut(&path("tag.Enchantments[0].lvl")) {
+            *lvl = 5;
+        }
+        assert_eq!(
+            value.get_path(&path("tag.Enchantments[0].lvl")),
+            Some(ValueRef::Short(5))
+        );
+    }
+
+    #[test]
+    fn failed_patch_is_atomic() {
+        let mut value = item();
+        let patch = Patch::new()
+            .push(Operation::Remove {
+                path: path("Count"),
+            })
+            .push(Operation::Test {
+                path: path("id"),
+                value: NameLessValue::String("minecraft:stone".to_string()),
+            });
+        assert!(matches!(value.apply(&patch), Err(NBTError::TestFailed(_))));
+        assert_eq!(value, item());
+    }
+
+    #[test]
+    fn patch_as_nbt() {
+        let patch = Patch::new()
+            .push(Operation::Add {
+                path: path("tag.Tags"),
+                value: NameLessValue::List(List::String(vec!["a".to_string()])),
+            })
+            .push(Operation::Copy {
+                from: path("id"),
+                path: path("old_id"),
+            });
+        let value = NameLessValue::from(&patch);
+        assert_eq!(
+            value.to_string(),
+            r#"[{op:"add",path:"tag.Tags",value:["a"]},{op:"copy",path:"old_id",from:"id"}]"#
+        );
+        assert_eq!(Patch::try_from(value).unwrap(), patch);
+
+        let operation = |op: Option<&str>| {
+            let mut entries = vec![NameLessValue::String("id".to_string()).into_named("path")];
+            if let Some(op) = op {
+                entries.push(NameLessValue::String(op.to_string()).into_named("op"));
+            }
+            NameLessValue::List(List::Compound(vec![entries]))
+        };
+        assert!(matches!(
+            Patch::try_from(operation(Some("frobnicate"))),
+            Err(NBTError::UnknownVariant(op)) if op == "frobnicate"
+        ));
+        assert!(matches!(
+            Patch::try_from(operation(None)),
+            Err(NBTError::MissingField(field)) if field == "op"
+        ));
+    }
+}
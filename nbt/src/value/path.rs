@@ -0,0 +1,250 @@
+use crate::NBTError;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// A single step within a [NBTPath]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PathSegment {
+    /// The name of an entry within a compound
+    Key(String),
+    /// The index of an element within a list or array
+    Index(usize),
+    /// `[-]`. The position after the last element of a list or array.
+    ///
+    /// Only the last segment of the target of an add, move or copy can be this
+    Append,
+}
+
+/// The location of a value within a NBT tree.
+///
+/// Displayed and parsed like `Inventory[2].tag.display.Name`.
+/// Keys that are not plain words are quoted `"minecraft:custom_data"`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct NBTPath(Vec<PathSegment>);
+
+impl NBTPath {
+    /// The path of the root compound
+    pub fn root() -> Self {
+        Self::default()
+    }
+
+    pub fn is_root(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.0
+    }
+
+    pub fn push_key(&mut self, key: impl Into<String>) {
+        self.0.push(PathSegment::Key(key.into()));
+    }
+
+    pub fn push_index(&mut self, index: usize) {
+        self.0.push(PathSegment::Index(index));
+    }
+
+    /// Adds the segment at the start. Used while an error travels up the tree
+    pub(crate) fn push_front(&mut self, segment: PathSegment) {
+        self.0.insert(0, segment);
+    }
+
+    pub fn push_append(&mut self) {
+        self.0.push(PathSegment::Append);
+    }
+
+    pub fn pop(&mut self) -> Option<PathSegment> {
+        self.0.pop()
+    }
+
+    /// Returns a new path with the key appended
+    pub fn key(&self, key: impl Into<String>) -> Self {
+        let mut path = self.clone();
+        path.push_key(key);
+        path
+    }
+
+    /// Returns a new path with the index appended
+    pub fn index(&self, index: usize) -> Self {
+        let mut path = self.clone();
+        path.push_index(index);
+        path
+    }
+
+    /// Returns a new path that appends to the list or array at this path
+    pub fn append(&self) -> Self {
+        let mut path = self.clone();
+        path.push_append();
+        path
+    }
+
+    /// The path without its last segment
+    pub fn parent(&self) -> Option<Self> {
+        if self.is_root() {
+            None
+        } else {
+            Some(Self(self.0[..self.0.len() - 1].to_vec()))
+        }
+    }
+
+    pub fn last(&self) -> Option<&PathSegment> {
+        self.0.last()
+    }
+}
+
+impl From<Vec<PathSegment>> for NBTPath {
+    fn from(segments: Vec<PathSegment>) -> Self {
+        Self(segments)
+    }
+}
+
+fn is_plain_key(key: &str) -> bool {
+    !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '+'))
+}
+
+impl Display for NBTPath {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (index, segment) in self.0.iter().enumerate() {
+            match segment {
+                PathSegment::Key(key) => {
+                    if index != 0 {
+                        f.write_str(".")?;
+                    }
+                    if is_plain_key(key) {
+                        f.write_str(key)?;
+                    } else {
+                        write!(f, "\"{}\"", key.replace('\\', "\\\\").replace('"', "\\\""))?;
+                    }
+                }
+                PathSegment::Index(i) => write!(f, "[{}]", i)?,
+                PathSegment::Append => f.write_str("[-]")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for NBTPath {
+    type Err = NBTError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || NBTError::InvalidPath(s.to_string());
+        let mut segments = Vec::new();
+        let mut chars = s.chars().peekable();
+        let mut expect_key = true;
+        while let Some(c) = chars.next() {
+            match c {
+                '.' if !expect_key => expect_key = true,
+                '[' => {
+                    let mut number = String::new();
+                    let mut closed = false;
+                    for c in chars.by_ref() {
+                        if c == ']' {
+                            closed = true;
+                            break;
+                        }
+                        number.push(c);
+                    }
+                    if !closed {
+                        return Err(invalid());
+                    }
+                    segments.push(match number.trim() {
+                        "-" => PathSegment::Append,
+                        number => PathSegment::Index(number.parse().map_err(|_| invalid())?),
+                    });
+                    expect_key = false;
+                }
+                '"' if expect_key => {
+                    let mut key = String::new();
+                    let mut closed = false;
+                    while let Some(c) = chars.next() {
+                        match c {
+                            '\\' => key.push(chars.next().ok_or_else(invalid)?),
+                            '"' => {
+                                closed = true;
+                                break;
+                            }
+                            c => key.push(c),
+                        }
+                    }
+                    if !closed {
+                        return Err(invalid());
+                    }
+                    segments.push(PathSegment::Key(key));
+                    expect_key = false;
+                }
+                c if expect_key && c != '.' && c != ']' => {
+                    let mut key = String::from(c);
+                    while let Some(&c) = chars.peek() {
+                        if c == '.' || c == '[' {
+                            break;
+                        }
+                        key.push(c);
+                        chars.next();
+                    }
+                    segments.push(PathSegment::Key(key));
+                    expect_key = false;
+                }
+                _ => return Err(invalid()),
+            }
+        }
+        if expect_key && !segments.is_empty() {
+            return Err(invalid());
+        }
+        Ok(Self(segments))
+    }
+}
+
+/// Serialized as the string form
+#[cfg(feature = "serde")]
+impl serde::Serialize for NBTPath {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for NBTPath {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let path = String::deserialize(deserializer)?;
+        NBTPath::from_str(&path).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::value::path::{NBTPath, PathSegment};
+    use std::str::FromStr;
+
+    #[test]
+    fn display_and_parse() {
+        let path = NBTPath::root()
+            .key("Inventory")
+            .index(2)
+            .key("tag")
+            .key("minecraft:custom_data");
+        let string = path.to_string();
+        assert_eq!(string, r#"Inventory[2].tag."minecraft:custom_data""#);
+        assert_eq!(NBTPath::from_str(&string).unwrap(), path);
+        assert_eq!(
+            NBTPath::from_str("[0][1]").unwrap().segments(),
+            &[PathSegment::Index(0), PathSegment::Index(1)]
+        );
+        assert!(NBTPath::from_str("a..b").is_err());
+        assert!(NBTPath::from_str("a.").is_err());
+        assert!(NBTPath::from_str("a[1").is_err());
+        assert!(NBTPath::from_str("[").is_err());
+        let append = NBTPath::root().key("Tags").append();
+        assert_eq!(append.to_string(), "Tags[-]");
+        assert_eq!(NBTPath::from_str("Tags[-]").unwrap(), append);
+    }
+}
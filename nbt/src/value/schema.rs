@@ -0,0 +1,876 @@
+//! Expected shapes of [Value] trees.
+//!
+//! Schemas are built in Rust or read from a NBT value, usually written as SNBT.
+//! Validation returns every violation instead of stopping at the first one
+//! ```rust ignore
+//! let item = Schema::compound()
+//!     .required("id", Schema::string())
+//!     .required("Count", Schema::byte().range(1..=64))
+//!     .optional("tag", Schema::compound());
+//! let violations = Schema::from(item).validate(&value);
+//!
+//! let item = Schema::from_value(&snbt::to_value(
+//!     r#"{type:"compound",fields:{id:"string",Count:{type:"byte",min:1,max:64},tag:{type:"compound",optional:1b}}}"#,
+//! )?)?;
+//! ```
+use crate::value::path::NBTPath;
+use crate::value::{List, NameLessValue, Value, ValueRef};
+use crate::{NBTError, Tag};
+use std::fmt::{Display, Formatter};
+use std::ops::RangeInclusive;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Schema {
+    /// Accepts every value
+    Any,
+    /// A Byte, Short, Int, Long, Float or Double. Bounds are inclusive
+    Number {
+        tag: Tag,
+        min: Option<f64>,
+        max: Option<f64>,
+    },
+    /// When `values` is not empty the string must be one of them
+    String {
+        values: Vec<String>,
+        length: Length,
+    },
+    /// A ByteArray, IntArray or LongArray
+    Array {
+        tag: Tag,
+        length: Length,
+    },
+    List {
+        elements: Box<Schema>,
+        length: Length,
+    },
+    Compound(CompoundSchema),
+    /// The value must match at least one of the schemas
+    OneOf(Vec<Schema>),
+}
+
+/// Inclusive bounds of the length of a string, array or list
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Length {
+    pub min: Option<usize>,
+    pub max: Option<usize>,
+}
+
+impl Length {
+    fn contains(&self, length: usize) -> bool {
+        self.min.is_none_or(|min| length >= min) && self.max.is_none_or(|max| length <= max)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field {
+    pub name: String,
+    pub schema: Schema,
+    pub required: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CompoundSchema {
+    pub fields: Vec<Field>,
+    /// Keys that are not a field are a violation
+    pub deny_unknown: bool,
+    /// The schema of keys that are not a field. Used for maps such as the keys of a recipe
+    pub other: Option<Box<Schema>>,
+}
+
+impl CompoundSchema {
+    pub fn required(mut self, name: impl Into<String>, schema: impl Into<Schema>) -> Self {
+        self.fields.push(Field {
+            name: name.into(),
+            schema: schema.into(),
+            required: true,
+        });
+        self
+    }
+
+    pub fn optional(mut self, name: impl Into<String>, schema: impl Into<Schema>) -> Self {
+        self.fields.push(Field {
+            name: name.into(),
+            schema: schema.into(),
+            required: false,
+        });
+        self
+    }
+
+    pub fn deny_unknown(mut self) -> Self {
+        self.deny_unknown = true;
+        self
+    }
+
+    pub fn other(mut self, schema: impl Into<Schema>) -> Self {
+        self.other = Some(Box::new(schema.into()));
+        self
+    }
+}
+
+impl From<CompoundSchema> for Schema {
+    fn from(value: CompoundSchema) -> Self {
+        Schema::Compound(value)
+    }
+}
+
+/// A number schema. Only a number has a range
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NumberSchema {
+    tag: Tag,
+    min: Option<f64>,
+    max: Option<f64>,
+}
+
+impl NumberSchema {
+    /// The inclusive range of the number
+    pub fn range<T: Into<f64>>(self, range: RangeInclusive<T>) -> Self {
+        let (min, max) = range.into_inner();
+        NumberSchema {
+            min: Some(min.into()),
+            max: Some(max.into()),
+            ..self
+        }
+    }
+}
+
+impl From<NumberSchema> for Schema {
+    fn from(value: NumberSchema) -> Self {
+        Schema::Number {
+            tag: value.tag,
+            min: value.min,
+            max: value.max,
+        }
+    }
+}
+
+/// A string, array or list schema. Only those have a length
+#[derive(Debug, Clone, PartialEq)]
+pub struct LengthSchema {
+    kind: LengthKind,
+    length: Length,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum LengthKind {
+    String(Vec<String>),
+    Array(Tag),
+    List(Box<Schema>),
+}
+
+impl LengthSchema {
+    fn new(kind: LengthKind) -> Self {
+        LengthSchema {
+            kind,
+            length: Length::default(),
+        }
+    }
+
+    /// The inclusive range of the length
+    pub fn length(self, range: RangeInclusive<usize>) -> Self {
+        LengthSchema {
+            length: Length {
+                min: Some(*range.start()),
+                max: Some(*range.end()),
+            },
+            ..self
+        }
+    }
+}
+
+impl From<LengthSchema> for Schema {
+    fn from(value: LengthSchema) -> Self {
+        let length = value.length;
+        match value.kind {
+            LengthKind::String(values) => Schema::String { values, length },
+            LengthKind::Array(tag) => Schema::Array { tag, length },
+            LengthKind::List(elements) => Schema::List { elements, length },
+        }
+    }
+}
+
+impl Schema {
+    fn number(tag: Tag) -> NumberSchema {
+        NumberSchema {
+            tag,
+            min: None,
+            max: None,
+        }
+    }
+    pub fn byte() -> NumberSchema {
+        Self::number(Tag::Byte)
+    }
+    pub fn short() -> NumberSchema {
+        Self::number(Tag::Short)
+    }
+    pub fn int() -> NumberSchema {
+        Self::number(Tag::Int)
+    }
+    pub fn long() -> NumberSchema {
+        Self::number(Tag::Long)
+    }
+    pub fn float() -> NumberSchema {
+        Self::number(Tag::Float)
+    }
+    pub fn double() -> NumberSchema {
+        Self::number(Tag::Double)
+    }
+    pub fn string() -> LengthSchema {
+        LengthSchema::new(LengthKind::String(Vec::new()))
+    }
+    /// A string that is one of the values
+    pub fn one_of_strings<'a>(values: impl IntoIterator<Item = &'a str>) -> LengthSchema {
+        LengthSchema::new(LengthKind::String(
+            values.into_iter().map(str::to_string).collect(),
+        ))
+    }
+    pub fn byte_array() -> LengthSchema {
+        LengthSchema::new(LengthKind::Array(Tag::ByteArray))
+    }
+    pub fn int_array() -> LengthSchema {
+        LengthSchema::new(LengthKind::Array(Tag::IntArray))
+    }
+    pub fn long_array() -> LengthSchema {
+        LengthSchema::new(LengthKind::Array(Tag::LongArray))
+    }
+    pub fn list(elements: impl Into<Schema>) -> LengthSchema {
+        LengthSchema::new(LengthKind::List(Box::new(elements.into())))
+    }
+    pub fn compound() -> CompoundSchema {
+        CompoundSchema::default()
+    }
+    pub fn one_of(schemas: impl IntoIterator<Item = Schema>) -> Self {
+        Schema::OneOf(schemas.into_iter().collect())
+    }
+
+    /// Checks the value against the schema. The name of the root is ignored
+    pub fn validate(&self, value: &Value) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        self.validate_value(&mut NBTPath::root(), value.as_value_ref(), &mut violations);
+        violations
+    }
+
+    pub fn is_valid(&self, value: &Value) -> bool {
+        self.validate(value).is_empty()
+    }
+
+    /// Values are only copied into violations
+    fn validate_value(
+        &self,
+        path: &mut NBTPath,
+        value: ValueRef<'_>,
+        violations: &mut Vec<Violation>,
+    ) {
+        match (self, value) {
+            (Schema::Any, _) => {}
+            (Schema::OneOf(schemas), value) => {
+                // A single schema reports its own violations
+                if let [schema] = schemas.as_slice() {
+                    return schema.validate_value(path, value, violations);
+                }
+                let matches = schemas.iter().any(|schema| {
+                    let mut ignored = Vec::new();
+                    schema.validate_value(path, value, &mut ignored);
+                    ignored.is_empty()
+                });
+                if !matches {
+                    violations.push(Violation::NoMatch {
+                        path: path.clone(),
+                        value: value.to_nameless(),
+                    });
+                }
+            }
+            (Schema::Number { tag, min, max }, value) if value.tag() == *tag => {
+                let number = match value {
+                    ValueRef::Byte(v) => v as f64,
+                    ValueRef::Boolean(v) => v as u8 as f64,
+                    ValueRef::Short(v) => v as f64,
+                    ValueRef::Int(v) => v as f64,
+                    ValueRef::Long(v) => v as f64,
+                    ValueRef::Float(v) => v as f64,
+                    ValueRef::Double(v) => v,
+                    _ => unreachable!("Checked by the tag"),
+                };
+                if min.is_some_and(|min| number < min) || max.is_some_and(|max| number > max) {
+                    violations.push(Violation::OutOfRange {
+                        path: path.clone(),
+                        value: value.to_nameless(),
+                        min: *min,
+                        max: *max,
+                    });
+                }
+            }
+            (Schema::String { values, length }, ValueRef::String(string)) => {
+                if !values.is_empty() && !values.iter().any(|value| value == string) {
+                    violations.push(Violation::NotAllowed {
+                        path: path.clone(),
+                        value: string.to_string(),
+                    });
+                } else {
+                    check_length(path, string.chars().count(), length, violations);
+                }
+            }
+            (Schema::Array { tag, length }, value) if value.tag() == *tag => {
+                let actual = match value {
+                    ValueRef::ByteArray(array) => array.len(),
+                    ValueRef::IntArray(array) => array.len(),
+                    ValueRef::LongArray(array) => array.len(),
+                    _ => unreachable!("Checked by the tag"),
+                };
+                check_length(path, actual, length, violations);
+            }
+            (Schema::List { elements, length }, ValueRef::List(list)) => {
+                check_length(path, list.len(), length, violations);
+                elements.validate_list(path, list, violations);
+            }
+            (Schema::Compound(compound), ValueRef::Compound(entries)) => {
+                compound.validate_entries(path, entries, violations)
+            }
+            (schema, value) => violations.push(Violation::WrongTag {
+                path: path.clone(),
+                expected: schema.tag(),
+                found: value.tag(),
+            }),
+        }
+    }
+
+    fn validate_list(&self, path: &mut NBTPath, list: &List, violations: &mut Vec<Violation>) {
+        // Every element has the same tag. So a wrong tag is reported once for the list
+        if let Some(expected) = self.tag() {
+            if !list.is_empty() && list.tag() != expected {
+                violations.push(Violation::WrongElementTag {
+                    path: path.clone(),
+                    expected,
+                    found: list.tag(),
+                });
+                return;
+            }
+        }
+        for (index, element) in list.iter().enumerate() {
+            path.push_index(index);
+            self.validate_value(path, element, violations);
+            path.pop();
+        }
+    }
+
+    /// The tag the schema requires. None if it accepts more than one tag
+    pub fn tag(&self) -> Option<Tag> {
+        match self {
+            Schema::Any => None,
+            Schema::Number { tag, .. } | Schema::Array { tag, .. } => Some(*tag),
+            Schema::String { .. } => Some(Tag::String),
+            Schema::List { .. } => Some(Tag::List),
+            Schema::Compound(_) => Some(Tag::Compound),
+            Schema::OneOf(schemas) => {
+                let tag = schemas.first()?.tag()?;
+                schemas
+                    .iter()
+                    .all(|schema| schema.tag() == Some(tag))
+                    .then_some(tag)
+            }
+        }
+    }
+}
+
+impl CompoundSchema {
+    fn validate_entries(
+        &self,
+        path: &mut NBTPath,
+        entries: &[Value],
+        violations: &mut Vec<Violation>,
+    ) {
+        for field in &self.fields {
+            match entries.iter().find(|entry| entry.get_name() == field.name) {
+                Some(entry) => {
+                    path.push_key(field.name.as_str());
+                    field
+                        .schema
+                        .validate_value(path, entry.as_value_ref(), violations);
+                    path.pop();
+                }
+                None if field.required => violations.push(Violation::Missing {
+                    path: path.key(&field.name),
+                }),
+                None => {}
+            }
+        }
+        for entry in entries {
+            if let Value::End = entry {
+                continue;
+            }
+            let name = entry.get_name();
+            if self.fields.iter().any(|field| field.name == name) {
+                continue;
+            }
+            if self.deny_unknown {
+                violations.push(Violation::Unknown {
+                    path: path.key(name),
+                });
+            } else if let Some(other) = &self.other {
+                path.push_key(name);
+                other.validate_value(path, entry.as_value_ref(), violations);
+                path.pop();
+            }
+        }
+    }
+}
+
+fn check_length(path: &NBTPath, actual: usize, length: &Length, violations: &mut Vec<Violation>) {
+    if !length.contains(actual) {
+        violations.push(Violation::WrongLength {
+            path: path.clone(),
+            length: actual,
+            min: length.min,
+            max: length.max,
+        });
+    }
+}
+
+/// A single place where a value does not match its schema
+#[derive(Debug, Clone, PartialEq)]
+pub enum Violation {
+    /// A required field is missing
+    Missing { path: NBTPath },
+    /// A field the schema does not know while unknown fields are denied
+    Unknown { path: NBTPath },
+    WrongTag {
+        path: NBTPath,
+        /// None if the schema accepts more than one tag
+        expected: Option<Tag>,
+        found: Tag,
+    },
+    /// The elements of the list have the wrong tag
+    WrongElementTag {
+        path: NBTPath,
+        expected: Tag,
+        found: Tag,
+    },
+    OutOfRange {
+        path: NBTPath,
+        value: NameLessValue,
+        min: Option<f64>,
+        max: Option<f64>,
+    },
+    WrongLength {
+        path: NBTPath,
+        length: usize,
+        min: Option<usize>,
+        max: Option<usize>,
+    },
+    /// The string is not one of the allowed values
+    NotAllowed { path: NBTPath, value: String },
+    /// The value matches none of the schemas of a [Schema::OneOf]
+    NoMatch { path: NBTPath, value: NameLessValue },
+}
+
+impl Violation {
+    pub fn path(&self) -> &NBTPath {
+        match self {
+            Violation::Missing { path } => path,
+            Violation::Unknown { path } => path,
+            Violation::WrongTag { path, .. } => path,
+            Violation::WrongElementTag { path, .. } => path,
+            Violation::OutOfRange { path, .. } => path,
+            Violation::WrongLength { path, .. } => path,
+            Violation::NotAllowed { path, .. } => path,
+            Violation::NoMatch { path, .. } => path,
+        }
+    }
+}
+
+fn bounds<T: Display>(min: &Option<T>, max: &Option<T>) -> String {
+    match (min, max) {
+        (Some(min), Some(max)) => format!("{}..={}", min, max),
+        (Some(min), None) => format!("{}..", min),
+        (None, Some(max)) => format!("..={}", max),
+        (None, None) => "..".to_string(),
+    }
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Violation::Missing { path } => write!(f, "{}: missing required field", path),
+            Violation::Unknown { path } => write!(f, "{}: unknown field", path),
+            Violation::WrongTag {
+                path,
+                expected: Some(expected),
+                found,
+            } => write!(f, "{}: expected {:?}, found {:?}", path, expected, found),
+            Violation::WrongTag {
+                path,
+                expected: None,
+                found,
+            } => write!(f, "{}: unexpected {:?}", path, found),
+            Violation::WrongElementTag {
+                path,
+                expected,
+                found,
+            } => write!(
+                f,
+                "{}: expected a list of {:?}, found a list of {:?}",
+                path, expected, found
+            ),
+            Violation::OutOfRange {
+                path,
+                value,
+                min,
+                max,
+            } => write!(f, "{}: {} is not in {}", path, value, bounds(min, max)),
+            Violation::WrongLength {
+                path,
+                length,
+                min,
+                max,
+            } => write!(
+                f,
+                "{}: length {} is not in {}",
+                path,
+                length,
+                bounds(min, max)
+            ),
+            Violation::NotAllowed { path, value } => {
+                write!(f, "{}: {:?} is not an allowed value", path, value)
+            }
+            Violation::NoMatch { path, value } => {
+                write!(f, "{}: {} matches none of the schemas", path, value)
+            }
+        }
+    }
+}
+
+/// Formats the violations as a human readable report. One violation per line
+pub fn report(violations: &[Violation]) -> String {
+    let mut report = String::new();
+    for violation in violations {
+        report.push_str(&violation.to_string());
+        report.push('\n');
+    }
+    report
+}
+
+impl Schema {
+    /// Reads a schema from NBT.
+    ///
+    /// A schema is either the name of a type or a compound with a `type` key. The types are
+    /// `any`, `byte`, `short`, `int`, `long`, `float`, `double`, `string`, `byte_array`,
+    /// `int_array`, `long_array`, `list`, `compound` and `one_of`.
+    /// - Numbers take `min` and `max`
+    /// - Strings, arrays and lists take `min_length` and `max_length`
+    /// - Strings take a list of allowed `values`
+    /// - Lists take the schema of their `elements`. `any` when missing
+    /// - Compounds take the schemas of their `fields`, `deny_unknown` and the schema of `other` keys.
+    ///   A field is required unless its schema has `optional:1b`
+    /// - `one_of` takes a list of `schemas`
+    pub fn from_value(value: &Value) -> Result<Schema, NBTError> {
+        parse(&NBTPath::root(), value.as_value_ref())
+    }
+}
+
+fn invalid(path: &NBTPath, message: impl Display) -> NBTError {
+    NBTError::InvalidSchema(path.to_string(), message.to_string())
+}
+
+fn entry<'a>(entries: &'a [Value], name: &str) -> Option<ValueRef<'a>> {
+    entries
+        .iter()
+        .find(|entry| entry.get_name() == name)
+        .map(Value::as_value_ref)
+}
+
+fn parse_number(path: &NBTPath, value: Option<ValueRef<'_>>) -> Result<Option<f64>, NBTError> {
+    Ok(match value {
+        None => None,
+        Some(ValueRef::Byte(v)) => Some(v as f64),
+        Some(ValueRef::Short(v)) => Some(v as f64),
+        Some(ValueRef::Int(v)) => Some(v as f64),
+        Some(ValueRef::Long(v)) => Some(v as f64),
+        Some(ValueRef::Float(v)) => Some(v as f64),
+        Some(ValueRef::Double(v)) => Some(v),
+        Some(other) => {
+            return Err(invalid(
+                path,
+                format!("{} is not a number", other.to_nameless()),
+            ))
+        }
+    })
+}
+
+fn parse_length(path: &NBTPath, value: Option<ValueRef<'_>>) -> Result<Option<usize>, NBTError> {
+    match parse_number(path, value)? {
+        Some(length) if length < 0.0 || length.fract() != 0.0 => {
+            Err(invalid(path, format!("{} is not a length", length)))
+        }
+        length => Ok(length.map(|length| length as usize)),
+    }
+}
+
+fn parse_flag(path: &NBTPath, value: Option<ValueRef<'_>>) -> Result<bool, NBTError> {
+    match value {
+        None => Ok(false),
+        Some(value) => value
+            .as_bool()
+            .ok_or_else(|| invalid(path, format!("{} is not a boolean", value.to_nameless()))),
+    }
+}
+
+fn parse(path: &NBTPath, value: ValueRef<'_>) -> Result<Schema, NBTError> {
+    let entries = match value {
+        ValueRef::String(kind) => return parse_type(path, kind, &[]),
+        ValueRef::Compound(entries) => entries,
+        other => {
+            return Err(invalid(
+                path,
+                format!(
+                    "expected a type or a compound, found {}",
+                    other.to_nameless()
+                ),
+            ))
+        }
+    };
+    match entry(entries, "type") {
+        Some(ValueRef::String(kind)) => parse_type(path, kind, entries),
+        Some(other) => Err(invalid(
+            &path.key("type"),
+            format!("{} is not a type", other.to_nameless()),
+        )),
+        None => Err(invalid(path, "missing the type")),
+    }
+}
+
+fn parse_type(path: &NBTPath, kind: &str, entries: &[Value]) -> Result<Schema, NBTError> {
+    let get = |name: &str| entry(entries, name);
+    let length = || -> Result<Length, NBTError> {
+        Ok(Length {
+            min: parse_length(&path.key("min_length"), get("min_length"))?,
+            max: parse_length(&path.key("max_length"), get("max_length"))?,
+        })
+    };
+    let schema = match kind {
+        "any" => Schema::Any,
+        "byte" | "short" | "int" | "long" | "float" | "double" => {
+            let tag = match kind {
+                "byte" => Tag::Byte,
+                "short" => Tag::Short,
+                "int" => Tag::Int,
+                "long" => Tag::Long,
+                "float" => Tag::Float,
+                _ => Tag::Double,
+            };
+            Schema::Number {
+                tag,
+                min: parse_number(&path.key("min"), get("min"))?,
+                max: parse_number(&path.key("max"), get("max"))?,
+            }
+        }
+        "string" => {
+            let values = match get("values") {
+                None => Vec::new(),
+                Some(ValueRef::List(List::String(values))) => values.clone(),
+                Some(ValueRef::List(List::End)) => Vec::new(),
+                Some(other) => {
+                    return Err(invalid(
+                        &path.key("values"),
+                        format!("{} is not a list of strings", other.to_nameless()),
+                    ))
+                }
+            };
+            Schema::String {
+                values,
+                length: length()?,
+            }
+        }
+        "byte_array" | "int_array" | "long_array" => Schema::Array {
+            tag: match kind {
+                "byte_array" => Tag::ByteArray,
+                "int_array" => Tag::IntArray,
+                _ => Tag::LongArray,
+            },
+            length: length()?,
+        },
+        "list" => Schema::List {
+            elements: Box::new(match get("elements") {
+                Some(elements) => parse(&path.key("elements"), elements)?,
+                None => Schema::Any,
+            }),
+            length: length()?,
+        },
+        "compound" => {
+            let mut compound = CompoundSchema {
+                deny_unknown: parse_flag(&path.key("deny_unknown"), get("deny_unknown"))?,
+                ..CompoundSchema::default()
+            };
+            if let Some(other) = get("other") {
+                compound.other = Some(Box::new(parse(&path.key("other"), other)?));
+            }
+            match get("fields") {
+                None => {}
+                Some(ValueRef::Compound(fields)) => {
+                    let fields_path = path.key("fields");
+                    for field in fields {
+                        if let Value::End = field {
+                            continue;
+                        }
+                        let name = field.get_name().to_string();
+                        let field_path = fields_path.key(&name);
+                        let value = field.as_value_ref();
+                        let optional = match value {
+                            ValueRef::Compound(entries) => {
+                                parse_flag(&field_path.key("optional"), entry(entries, "optional"))?
+                            }
+                            _ => false,
+                        };
+                        compound.fields.push(Field {
+                            schema: parse(&field_path, value)?,
+                            name,
+                            required: !optional,
+                        });
+                    }
+                }
+                Some(other) => {
+                    return Err(invalid(
+                        &path.key("fields"),
+                        format!("{} is not a compound", other.to_nameless()),
+                    ))
+                }
+            }
+            Schema::Compound(compound)
+        }
+        "one_of" => {
+            let schemas_path = path.key("schemas");
+            match get("schemas") {
+                Some(ValueRef::List(schemas)) => Schema::OneOf(
+                    schemas
+                        .iter()
+                        .enumerate()
+                        .map(|(index, schema)| parse(&schemas_path.index(index), schema))
+                        .collect::<Result<_, _>>()?,
+                ),
+                Some(other) => {
+                    return Err(invalid(
+                        &schemas_path,
+                        format!("{} is not a list of schemas", other.to_nameless()),
+                    ))
+                }
+                None => return Err(invalid(path, "one_of is missing its schemas")),
+            }
+        }
+        other => return Err(invalid(path, format!("unknown type {:?}", other))),
+    };
+    Ok(schema)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::snbt::to_value;
+    use crate::value::schema::{report, Length, Schema, Violation};
+    use crate::value::{List, NBTPath, NameLessValue, Value};
+    use crate::Tag;
+
+    fn item(id: NameLessValue, count: i8) -> Vec<Value> {
+        vec![
+            id.into_named("id"),
+            NameLessValue::Byte(count).into_named("Count"),
+        ]
+    }
+
+    fn item_schema() -> Schema {
+        Schema::compound()
+            .required("id", Schema::string())
+            .required("Count", Schema::byte().range(1..=64))
+            .optional("tag", Schema::compound())
+            .deny_unknown()
+            .into()
+    }
+
+    #[test]
+    fn validate() {
+        let schema: Schema = Schema::compound()
+            .required("Inventory", Schema::list(item_schema()).length(0..=36))
+            .required("Health", Schema::float().range(0.0..=20.0))
+            .into();
+        let mut bad_item = item(NameLessValue::Int(1), 65);
+        bad_item.push(NameLessValue::Byte(0).into_named("Slot"));
+        let value = NameLessValue::Compound(vec![NameLessValue::List(List::Compound(vec![
+            item(NameLessValue::String("minecraft:stone".to_string()), 64),
+            bad_item,
+        ]))
+        .into_named("Inventory")])
+        .into_named("");
+        let violations = schema.validate(&value);
+        let path = |path: &str| path.parse::<NBTPath>().unwrap();
+        assert_eq!(
+            violations,
+            vec![
+                Violation::WrongTag {
+                    path: path("Inventory[1].id"),
+                    expected: Some(Tag::String),
+                    found: Tag::Int,
+                },
+                Violation::OutOfRange {
+                    path: path("Inventory[1].Count"),
+                    value: NameLessValue::Byte(65),
+                    min: Some(1.0),
+                    max: Some(64.0),
+                },
+                Violation::Unknown {
+                    path: path("Inventory[1].Slot"),
+                },
+                Violation::Missing {
+                    path: path("Health"),
+                },
+            ]
+        );
+        assert_eq!(
+            report(&violations[1..2]),
+            "Inventory[1].Count: 65b is not in 1..=64\n"
+        );
+
+        let strings = NameLessValue::List(List::String(vec!["a".to_string()])).into_named("");
+        assert_eq!(
+            Schema::from(Schema::list(Schema::int())).validate(&strings),
+            vec![Violation::WrongElementTag {
+                path: NBTPath::root(),
+                expected: Tag::Int,
+                found: Tag::String,
+            }]
+        );
+        assert!(Schema::from(Schema::list(Schema::int()))
+            .is_valid(&NameLessValue::List(List::End).into_named("")));
+    }
+
+    #[test]
+    fn from_snbt() {
+        let schema = Schema::from_value(
+            &to_value(
+                r#"{type:"compound",deny_unknown:1b,fields:{id:"string",Count:{type:"byte",min:1,max:64},tag:{type:"compound",optional:1b}}}"#,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(schema, item_schema());
+
+        let schema = Schema::from_value(
+            &to_value(r#"{type:"list",max_length:2,elements:{type:"string",values:["a","b"]}}"#)
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            schema,
+            Schema::List {
+                elements: Box::new(Schema::one_of_strings(["a", "b"]).into()),
+                length: Length {
+                    min: None,
+                    max: Some(2)
+                },
+            }
+        );
+
+        let error =
+            Schema::from_value(&to_value(r#"{type:"compound",fields:{id:"text"}}"#).unwrap())
+                .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            r#"Invalid schema at fields.id: unknown type "text""#
+        );
+    }
+}
@@ -0,0 +1,351 @@
+//! Borrowed views of values within a tree. Lookups return these instead of copying the subtree
+use crate::value::path::{NBTPath, PathSegment};
+use crate::value::{List, NameLessValue, Value};
+use crate::Tag;
+
+/// A borrowed [NameLessValue]
+#[derive(Debug, Clone, Copy)]
+pub enum ValueRef<'a> {
+    End,
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    ByteArray(&'a [i8]),
+    String(&'a str),
+    List(&'a List),
+    Compound(&'a [Value]),
+    IntArray(&'a [i32]),
+    LongArray(&'a [i64]),
+    /// See [Value::Boolean]
+    Boolean(bool),
+}
+
+/// A mutable borrow of a value within a tree
+#[derive(Debug)]
+pub enum ValueMut<'a> {
+    End,
+    Byte(&'a mut i8),
+    Short(&'a mut i16),
+    Int(&'a mut i32),
+    Long(&'a mut i64),
+    Float(&'a mut f32),
+    Double(&'a mut f64),
+    ByteArray(&'a mut Vec<i8>),
+    String(&'a mut String),
+    List(&'a mut List),
+    Compound(&'a mut Vec<Value>),
+    IntArray(&'a mut Vec<i32>),
+    LongArray(&'a mut Vec<i64>),
+    /// See [Value::Boolean]
+    Boolean(&'a mut bool),
+}
+
+impl<'a> ValueRef<'a> {
+    pub fn tag(&self) -> Tag {
+        match self {
+            ValueRef::End => Tag::End,
+            ValueRef::Byte(_) | ValueRef::Boolean(_) => Tag::Byte,
+            ValueRef::Short(_) => Tag::Short,
+            ValueRef::Int(_) => Tag::Int,
+            ValueRef::Long(_) => Tag::Long,
+            ValueRef::Float(_) => Tag::Float,
+            ValueRef::Double(_) => Tag::Double,
+            ValueRef::ByteArray(_) => Tag::ByteArray,
+            ValueRef::String(_) => Tag::String,
+            ValueRef::List(_) => Tag::List,
+            ValueRef::Compound(_) => Tag::Compound,
+            ValueRef::IntArray(_) => Tag::IntArray,
+            ValueRef::LongArray(_) => Tag::LongArray,
+        }
+    }
+
+    /// Copies the value out of the tree
+    pub fn to_nameless(self) -> NameLessValue {
+        match self {
+            ValueRef::End => NameLessValue::End,
+            ValueRef::Byte(v) => NameLessValue::Byte(v),
+            ValueRef::Short(v) => NameLessValue::Short(v),
+            ValueRef::Int(v) => NameLessValue::Int(v),
+            ValueRef::Long(v) => NameLessValue::Long(v),
+            ValueRef::Float(v) => NameLessValue::Float(v),
+            ValueRef::Double(v) => NameLessValue::Double(v),
+            ValueRef::ByteArray(v) => NameLessValue::ByteArray(v.to_vec()),
+            ValueRef::String(v) => NameLessValue::String(v.to_string()),
+            ValueRef::List(v) => NameLessValue::List(v.clone()),
+            ValueRef::Compound(v) => NameLessValue::Compound(v.to_vec()),
+            ValueRef::IntArray(v) => NameLessValue::IntArray(v.to_vec()),
+            ValueRef::LongArray(v) => NameLessValue::LongArray(v.to_vec()),
+            ValueRef::Boolean(v) => NameLessValue::Boolean(v),
+        }
+    }
+
+    /// See [Value::as_bool]
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            ValueRef::Byte(value) => Some(*value != 0),
+            ValueRef::Boolean(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// The entry of a compound or the element of a list or array
+    pub fn child(self, segment: &PathSegment) -> Option<ValueRef<'a>> {
+        match (self, segment) {
+            (ValueRef::Compound(entries), PathSegment::Key(key)) => entries
+                .iter()
+                .find(|v| v.get_name() == key)
+                .map(Value::as_value_ref),
+            (ValueRef::List(list), PathSegment::Index(index)) => list.get(*index),
+            (ValueRef::ByteArray(array), PathSegment::Index(index)) => {
+                array.get(*index).copied().map(ValueRef::Byte)
+            }
+            (ValueRef::IntArray(array), PathSegment::Index(index)) => {
+                array.get(*index).copied().map(ValueRef::Int)
+            }
+            (ValueRef::LongArray(array), PathSegment::Index(index)) => {
+                array.get(*index).copied().map(ValueRef::Long)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn get_path(self, path: &NBTPath) -> Option<ValueRef<'a>> {
+        path.segments()
+            .iter()
+            .try_fold(self, |value, segment| value.child(segment))
+    }
+}
+
+impl PartialEq for ValueRef<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (ValueRef::End, ValueRef::End) => true,
+            (ValueRef::Byte(a), ValueRef::Byte(b)) => a == b,
+            (ValueRef::Short(a), ValueRef::Short(b)) => a == b,
+            (ValueRef::Int(a), ValueRef::Int(b)) => a == b,
+            (ValueRef::Long(a), ValueRef::Long(b)) => a == b,
+            (ValueRef::Float(a), ValueRef::Float(b)) => a == b,
+            (ValueRef::Double(a), ValueRef::Double(b)) => a == b,
+            (ValueRef::ByteArray(a), ValueRef::ByteArray(b)) => a == b,
+            (ValueRef::String(a), ValueRef::String(b)) => a == b,
+            (ValueRef::List(a), ValueRef::List(b)) => a == b,
+            (ValueRef::Compound(a), ValueRef::Compound(b)) => a == b,
+            (ValueRef::IntArray(a), ValueRef::IntArray(b)) => a == b,
+            (ValueRef::LongArray(a), ValueRef::LongArray(b)) => a == b,
+            (ValueRef::Boolean(a), ValueRef::Boolean(b)) => a == b,
+            (ValueRef::Boolean(a), ValueRef::Byte(b))
+            | (ValueRef::Byte(b), ValueRef::Boolean(a)) => i8::from(*a) == *b,
+            _ => false,
+        }
+    }
+}
+
+impl PartialEq<NameLessValue> for ValueRef<'_> {
+    fn eq(&self, other: &NameLessValue) -> bool {
+        *self == other.as_value_ref()
+    }
+}
+
+impl PartialEq<ValueRef<'_>> for NameLessValue {
+    fn eq(&self, other: &ValueRef<'_>) -> bool {
+        self.as_value_ref() == *other
+    }
+}
+
+impl<'a> ValueMut<'a> {
+    pub fn tag(&self) -> Tag {
+        self.as_value_ref().tag()
+    }
+
+    pub fn as_value_ref(&self) -> ValueRef<'_> {
+        match self {
+            ValueMut::End => ValueRef::End,
+            ValueMut::Byte(v) => ValueRef::Byte(**v),
+            ValueMut::Short(v) => ValueRef::Short(**v),
+            ValueMut::Int(v) => ValueRef::Int(**v),
+            ValueMut::Long(v) => ValueRef::Long(**v),
+            ValueMut::Float(v) => ValueRef::Float(**v),
+            ValueMut::Double(v) => ValueRef::Double(**v),
+            ValueMut::ByteArray(v) => ValueRef::ByteArray(v),
+            ValueMut::String(v) => ValueRef::String(v),
+            ValueMut::List(v) => ValueRef::List(v),
+            ValueMut::Compound(v) => ValueRef::Compound(v),
+            ValueMut::IntArray(v) => ValueRef::IntArray(v),
+            ValueMut::LongArray(v) => ValueRef::LongArray(v),
+            ValueMut::Boolean(v) => ValueRef::Boolean(**v),
+        }
+    }
+
+    /// The entry of a compound or the element of a list or array
+    pub fn child(self, segment: &PathSegment) -> Option<ValueMut<'a>> {
+        match (self, segment) {
+            (ValueMut::Compound(entries), PathSegment::Key(key)) => entries
+                .iter_mut()
+                .find(|v| v.get_name() == key)
+                .map(Value::as_value_mut),
+            (ValueMut::List(list), PathSegment::Index(index)) => list.get_mut(*index),
+            (ValueMut::ByteArray(array), PathSegment::Index(index)) => {
+                array.get_mut(*index).map(ValueMut::Byte)
+            }
+            (ValueMut::IntArray(array), PathSegment::Index(index)) => {
+                array.get_mut(*index).map(ValueMut::Int)
+            }
+            (ValueMut::LongArray(array), PathSegment::Index(index)) => {
+                array.get_mut(*index).map(ValueMut::Long)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn get_path(self, path: &NBTPath) -> Option<ValueMut<'a>> {
+        path.segments()
+            .iter()
+            .try_fold(self, |value, segment| value.child(segment))
+    }
+}
+
+impl Value {
+    /// Borrows the payload of the value
+    pub fn as_value_ref(&self) -> ValueRef<'_> {
+        match self {
+            Value::End => ValueRef::End,
+            Value::Byte { value, .. } => ValueRef::Byte(*value),
+            Value::Short { value, .. } => ValueRef::Short(*value),
+            Value::Int { value, .. } => ValueRef::Int(*value),
+            Value::Long { value, .. } => ValueRef::Long(*value),
+            Value::Float { value, .. } => ValueRef::Float(*value),
+            Value::Double { value, .. } => ValueRef::Double(*value),
+            Value::ByteArray { value, .. } => ValueRef::ByteArray(value),
+            Value::String { value, .. } => ValueRef::String(value),
+            Value::List { value, .. } => ValueRef::List(value),
+            Value::Compound { value, .. } => ValueRef::Compound(value),
+            Value::IntArray { value, .. } => ValueRef::IntArray(value),
+            Value::LongArray { value, .. } => ValueRef::LongArray(value),
+            Value::Boolean { value, .. } => ValueRef::Boolean(*value),
+        }
+    }
+
+    pub fn as_value_mut(&mut self) -> ValueMut<'_> {
+        match self {
+            Value::End => ValueMut::End,
+            Value::Byte { value, .. } => ValueMut::Byte(value),
+            Value::Short { value, .. } => ValueMut::Short(value),
+            Value::Int { value, .. } => ValueMut::Int(value),
+            Value::Long { value, .. } => ValueMut::Long(value),
+            Value::Float { value, .. } => ValueMut::Float(value),
+            Value::Double { value, .. } => ValueMut::Double(value),
+            Value::ByteArray { value, .. } => ValueMut::ByteArray(value),
+            Value::String { value, .. } => ValueMut::String(value),
+            Value::List { value, .. } => ValueMut::List(value),
+            Value::Compound { value, .. } => ValueMut::Compound(value),
+            Value::IntArray { value, .. } => ValueMut::IntArray(value),
+            Value::LongArray { value, .. } => ValueMut::LongArray(value),
+            Value::Boolean { value, .. } => ValueMut::Boolean(value),
+        }
+    }
+
+    /// The value at the path. The root is the value itself
+    pub fn get_path(&self, path: &NBTPath) -> Option<ValueRef<'_>> {
+        self.as_value_ref().get_path(path)
+    }
+
+    pub fn get_path_mut(&mut self, path: &NBTPath) -> Option<ValueMut<'_>> {
+        self.as_value_mut().get_path(path)
+    }
+}
+
+impl NameLessValue {
+    pub fn as_value_ref(&self) -> ValueRef<'_> {
+        match self {
+            NameLessValue::End => ValueRef::End,
+            NameLessValue::Byte(v) => ValueRef::Byte(*v),
+            NameLessValue::Short(v) => ValueRef::Short(*v),
+            NameLessValue::Int(v) => ValueRef::Int(*v),
+            NameLessValue::Long(v) => ValueRef::Long(*v),
+            NameLessValue::Float(v) => ValueRef::Float(*v),
+            NameLessValue::Double(v) => ValueRef::Double(*v),
+            NameLessValue::ByteArray(v) => ValueRef::ByteArray(v),
+            NameLessValue::String(v) => ValueRef::String(v),
+            NameLessValue::List(v) => ValueRef::List(v),
+            NameLessValue::Compound(v) => ValueRef::Compound(v),
+            NameLessValue::IntArray(v) => ValueRef::IntArray(v),
+            NameLessValue::LongArray(v) => ValueRef::LongArray(v),
+            NameLessValue::Boolean(v) => ValueRef::Boolean(*v),
+        }
+    }
+
+    pub fn as_value_mut(&mut self) -> ValueMut<'_> {
+        match self {
+            NameLessValue::End => ValueMut::End,
+            NameLessValue::Byte(v) => ValueMut::Byte(v),
+            NameLessValue::Short(v) => ValueMut::Short(v),
+            NameLessValue::Int(v) => ValueMut::Int(v),
+            NameLessValue::Long(v) => ValueMut::Long(v),
+            NameLessValue::Float(v) => ValueMut::Float(v),
+            NameLessValue::Double(v) => ValueMut::Double(v),
+            NameLessValue::ByteArray(v) => ValueMut::ByteArray(v),
+            NameLessValue::String(v) => ValueMut::String(v),
+            NameLessValue::List(v) => ValueMut::List(v),
+            NameLessValue::Compound(v) => ValueMut::Compound(v),
+            NameLessValue::IntArray(v) => ValueMut::IntArray(v),
+            NameLessValue::LongArray(v) => ValueMut::LongArray(v),
+            NameLessValue::Boolean(v) => ValueMut::Boolean(v),
+        }
+    }
+
+    /// See [Value::get_path]
+    pub fn get_path(&self, path: &NBTPath) -> Option<ValueRef<'_>> {
+        self.as_value_ref().get_path(path)
+    }
+
+    pub fn get_path_mut(&mut self, path: &NBTPath) -> Option<ValueMut<'_>> {
+        self.as_value_mut().get_path(path)
+    }
+}
+
+impl List {
+    /// Borrows the element at the index
+    pub fn get(&self, index: usize) -> Option<ValueRef<'_>> {
+        match self {
+            List::End => None,
+            List::Byte(v) => v.get(index).copied().map(ValueRef::Byte),
+            List::Short(v) => v.get(index).copied().map(ValueRef::Short),
+            List::Int(v) => v.get(index).copied().map(ValueRef::Int),
+            List::Long(v) => v.get(index).copied().map(ValueRef::Long),
+            List::Float(v) => v.get(index).copied().map(ValueRef::Float),
+            List::Double(v) => v.get(index).copied().map(ValueRef::Double),
+            List::ByteArray(v) => v.get(index).map(|v| ValueRef::ByteArray(v)),
+            List::String(v) => v.get(index).map(|v| ValueRef::String(v)),
+            List::List(v) => v.get(index).map(ValueRef::List),
+            List::Compound(v) => v.get(index).map(|v| ValueRef::Compound(v)),
+            List::IntArray(v) => v.get(index).map(|v| ValueRef::IntArray(v)),
+            List::LongArray(v) => v.get(index).map(|v| ValueRef::LongArray(v)),
+        }
+    }
+
+    /// The element type can not be changed through the borrow
+    pub fn get_mut(&mut self, index: usize) -> Option<ValueMut<'_>> {
+        match self {
+            List::End => None,
+            List::Byte(v) => v.get_mut(index).map(ValueMut::Byte),
+            List::Short(v) => v.get_mut(index).map(ValueMut::Short),
+            List::Int(v) => v.get_mut(index).map(ValueMut::Int),
+            List::Long(v) => v.get_mut(index).map(ValueMut::Long),
+            List::Float(v) => v.get_mut(index).map(ValueMut::Float),
+            List::Double(v) => v.get_mut(index).map(ValueMut::Double),
+            List::ByteArray(v) => v.get_mut(index).map(ValueMut::ByteArray),
+            List::String(v) => v.get_mut(index).map(ValueMut::String),
+            List::List(v) => v.get_mut(index).map(ValueMut::List),
+            List::Compound(v) => v.get_mut(index).map(ValueMut::Compound),
+            List::IntArray(v) => v.get_mut(index).map(ValueMut::IntArray),
+            List::LongArray(v) => v.get_mut(index).map(ValueMut::LongArray),
+        }
+    }
+
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = ValueRef<'_>> + '_ {
+        (0..self.len()).map(move |index| self.get(index).expect("index is within the length"))
+    }
+}
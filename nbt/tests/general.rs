@@ -1,5 +1,7 @@
-use axolotl_nbt::value::Value;
-use axolotl_nbt::NBTDataType;
+use axolotl_nbt::axolotl_nbt_macros::NbtCompound;
+use axolotl_nbt::migrate::{Fix, Migrator, Versioned};
+use axolotl_nbt::value::{codegen, List, NameLessValue, Operation, Patch, Value, ValueRef};
+use axolotl_nbt::{NBTDataType, NBTError, Tag};
 use std::env::current_dir;
 use std::fs::{read, File};
 use std::path::PathBuf;
@@ -56,3 +58,618 @@ pub fn test_one() {
 pub fn test_two() {
     test_file("test_two");
 }
+
+#[test]
+pub fn boolean_round_trip() {
+    let value = Value::Compound {
+        name: "".to_string(),
+        value: vec![
+            Value::Boolean {
+                name: "OnGround".to_string(),
+                value: true,
+            },
+            Value::Boolean {
+                name: "Invulnerable".to_string(),
+                value: false,
+            },
+        ],
+    };
+    let mut bytes = Vec::new();
+    value.clone().write_alone(&mut bytes).expect("a write");
+    let read = Value::read(&mut bytes.as_slice()).expect("a value");
+    assert_eq!(value, read);
+    assert_eq!(
+        read,
+        Value::Compound {
+            name: "".to_string(),
+            value: vec![
+                Value::Byte {
+                    name: "OnGround".to_string(),
+                    value: 1,
+                },
+                Value::Byte {
+                    name: "Invulnerable".to_string(),
+                    value: 0,
+                },
+            ],
+        }
+    );
+    assert_ne!(NameLessValue::Boolean(true), NameLessValue::Byte(2));
+    assert_eq!(NameLessValue::Byte(2).as_bool(), Some(true));
+}
+
+#[test]
+pub fn empty_list_round_trip() {
+    let value = Value::Compound {
+        name: "".to_string(),
+        value: vec![
+            Value::List {
+                name: "Tags".to_string(),
+                value: List::End,
+            },
+            Value::List {
+                name: "Nested".to_string(),
+                value: List::List(vec![List::End, List::new(Tag::Int)]),
+            },
+            Value::Int {
+                name: "After".to_string(),
+                value: 5,
+            },
+        ],
+    };
+    let mut bytes = Vec::new();
+    value.clone().write_alone(&mut bytes).expect("a write");
+    let read = Value::read(&mut bytes.as_slice()).expect("a value");
+    assert_eq!(value, read);
+}
+
+#[test]
+pub fn typed_list() {
+    let mut list = List::new(Tag::Int);
+    list.push(NameLessValue::Int(1)).expect("an int");
+    list.push(NameLessValue::Int(2)).expect("an int");
+    assert!(matches!(
+        list.push(NameLessValue::String("three".to_string())),
+        Err(NBTError::ListTypeMismatch(Tag::Int, Tag::String))
+    ));
+    assert_eq!(list, List::Int(vec![1, 2]));
+
+    // Empty lists are equal whatever their tag
+    assert_eq!(List::End, List::new(Tag::Int));
+    assert_eq!(List::new(Tag::String), List::new(Tag::Compound));
+    assert_ne!(List::End, List::Int(vec![1]));
+    assert_ne!(List::Int(vec![1]), List::Long(vec![1]));
+
+    let value = Value::List {
+        name: "Pos".to_string(),
+        value: List::Double(vec![1.0, 64.0, -3.5]),
+    };
+    let mut bytes = Vec::new();
+    value.clone().write_alone(&mut bytes).expect("a write");
+    let read = Value::read(&mut bytes.as_slice()).expect("a value");
+    assert_eq!(value, read);
+
+    // Numeric lists are written in one go
+    let mut bytes = Vec::new();
+    NameLessValue::List(List::Short(vec![1, -2]))
+        .write_alone(&mut bytes)
+        .expect("a write");
+    assert_eq!(bytes, [Tag::Short as u8, 0, 0, 0, 2, 0, 1, 0xff, 0xfe]);
+    for list in [
+        List::Byte(vec![1, -1]),
+        List::Short(vec![300, -300]),
+        List::Int(vec![1, i32::MIN]),
+        List::Long(vec![1, i64::MAX]),
+        List::Float(vec![0.5, -1.0]),
+        List::Double(vec![0.25, f64::MAX]),
+    ] {
+        let value = Value::List {
+            name: "Numbers".to_string(),
+            value: list,
+        };
+        let mut bytes = Vec::new();
+        value.clone().write_alone(&mut bytes).expect("a write");
+        let read = Value::read(&mut bytes.as_slice()).expect("a value");
+        assert_eq!(value, read);
+    }
+}
+
+#[derive(Debug, PartialEq, NbtCompound)]
+#[allow(non_snake_case)]
+pub struct Item {
+    pub id: String,
+    pub Count: i8,
+}
+
+#[derive(Debug, PartialEq, NbtCompound)]
+#[allow(non_snake_case)]
+pub struct Entity {
+    pub id: String,
+    pub Health: f32,
+    pub Pos: Vec<f64>,
+    pub UUID: Vec<i32>,
+    pub Tags: Vec<String>,
+    pub HandItems: Vec<Item>,
+    pub Item: Option<Item>,
+    pub CustomName: Option<String>,
+}
+
+#[test]
+pub fn derive_nbt_compound() {
+    let item = |id: &str, count: i8| {
+        vec![
+            Value::String {
+                name: "id".to_string(),
+                value: id.to_string(),
+            },
+            Value::Byte {
+                name: "Count".to_string(),
+                value: count,
+            },
+        ]
+    };
+    let value = Value::Compound {
+        name: "".to_string(),
+        value: vec![
+            Value::String {
+                name: "id".to_string(),
+                value: "minecraft:zombie".to_string(),
+            },
+            Value::Float {
+                name: "Health".to_string(),
+                value: 20.0,
+            },
+            Value::Compound {
+                name: "Brain".to_string(),
+                value: vec![Value::List {
+                    name: "memories".to_string(),
+                    value: List::Int(vec![1, 2]),
+                }],
+            },
+            Value::List {
+                name: "Pos".to_string(),
+                value: List::Double(vec![1.0, 64.0, -3.5]),
+            },
+            Value::IntArray {
+                name: "UUID".to_string(),
+                value: vec![1, 2, 3, 4],
+            },
+            Value::List {
+                name: "Tags".to_string(),
+                value: List::End,
+            },
+            Value::List {
+                name: "HandItems".to_string(),
+                value: List::Compound(vec![item("minecraft:stone", 64), item("minecraft:air", 0)]),
+            },
+            Value::Compound {
+                name: "Item".to_string(),
+                value: item("minecraft:dirt", 1),
+            },
+        ],
+    };
+    let mut bytes = Vec::new();
+    value.write_alone(&mut bytes).expect("a write");
+    let mut reader = bytes.as_slice();
+    assert_eq!(Tag::read(&mut reader).expect("a tag"), Tag::Compound);
+    let (name, entity) = Entity::read_with_name(&mut reader).expect("an entity");
+    assert_eq!(name, "");
+    assert!(reader.is_empty());
+    let expected = Entity {
+        id: "minecraft:zombie".to_string(),
+        Health: 20.0,
+        Pos: vec![1.0, 64.0, -3.5],
+        UUID: vec![1, 2, 3, 4],
+        Tags: vec![],
+        HandItems: vec![
+            Item {
+                id: "minecraft:stone".to_string(),
+                Count: 64,
+            },
+            Item {
+                id: "minecraft:air".to_string(),
+                Count: 0,
+            },
+        ],
+        Item: Some(Item {
+            id: "minecraft:dirt".to_string(),
+            Count: 1,
+        }),
+        CustomName: None,
+    };
+    assert_eq!(entity, expected);
+
+    let mut bytes = Vec::new();
+    expected.write("", &mut bytes).expect("a write");
+    let read = Value::read(&mut bytes.as_slice()).expect("a value");
+    let Value::Compound {
+        value: compound, ..
+    } = read
+    else {
+        panic!("Expected a compound");
+    };
+    let names: Vec<&str> = compound.iter().map(|value| value.get_name()).collect();
+    assert_eq!(
+        names,
+        ["id", "Health", "Pos", "UUID", "Tags", "HandItems", "Item"]
+    );
+    assert_eq!(
+        compound[3],
+        Value::IntArray {
+            name: "UUID".to_string(),
+            value: vec![1, 2, 3, 4],
+        }
+    );
+
+    let mut bytes = Vec::new();
+    Value::Compound {
+        name: "".to_string(),
+        value: item("minecraft:stone", 1)[..1].to_vec(),
+    }
+    .write_alone(&mut bytes)
+    .expect("a write");
+    let mut reader = &bytes[1..];
+    assert!(matches!(
+        Item::read_with_name(&mut reader),
+        Err(NBTError::MissingField(field)) if field == "Count"
+    ));
+}
+
+#[derive(Debug, PartialEq, NbtCompound)]
+#[nbt(rename_all = "PascalCase")]
+pub struct Mob {
+    #[nbt(rename = "id")]
+    pub id: String,
+    pub hurt_time: i16,
+    #[nbt(default)]
+    pub fall_distance: f32,
+    #[nbt(default, skip_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[nbt(tag = "list")]
+    pub block_pos: Vec<i32>,
+    #[nbt(skip)]
+    pub ticks: u32,
+    #[nbt(flatten)]
+    pub extra: Vec<Value>,
+}
+
+#[derive(Debug, PartialEq, NbtCompound)]
+#[nbt(deny_unknown)]
+pub struct Strict {
+    pub id: String,
+}
+
+#[test]
+pub fn nbt_compound_attributes() {
+    let value = Value::Compound {
+        name: "".to_string(),
+        value: vec![
+            Value::String {
+                name: "id".to_string(),
+                value: "minecraft:pig".to_string(),
+            },
+            Value::Short {
+                name: "HurtTime".to_string(),
+                value: 3,
+            },
+            Value::List {
+                name: "BlockPos".to_string(),
+                value: List::Int(vec![1, 2, 3]),
+            },
+            Value::Byte {
+                name: "Saddle".to_string(),
+                value: 1,
+            },
+        ],
+    };
+    let mut bytes = Vec::new();
+    value.clone().write_alone(&mut bytes).expect("a write");
+    let (_, mob) = Mob::read_with_name(&mut &bytes[1..]).expect("a mob");
+    assert_eq!(
+        mob,
+        Mob {
+            id: "minecraft:pig".to_string(),
+            hurt_time: 3,
+            fall_distance: 0.0,
+            tags: vec![],
+            block_pos: vec![1, 2, 3],
+            ticks: 0,
+            extra: vec![Value::Byte {
+                name: "Saddle".to_string(),
+                value: 1,
+            }],
+        }
+    );
+
+    let mut written = Vec::new();
+    mob.write("", &mut written).expect("a write");
+    let Value::Compound {
+        value: mut compound,
+        ..
+    } = Value::read(&mut written.as_slice()).expect("a value")
+    else {
+        panic!("Expected a compound");
+    };
+    // FallDistance was defaulted and is written back, Tags is empty and skipped
+    assert_eq!(
+        compound.remove(2),
+        Value::Float {
+            name: "FallDistance".to_string(),
+            value: 0.0,
+        }
+    );
+    assert_eq!(
+        Value::Compound {
+            name: "".to_string(),
+            value: compound,
+        },
+        value
+    );
+
+    assert!(matches!(
+        Strict::read_with_name(&mut &bytes[1..]),
+        Err(NBTError::UnknownField(field)) if field == "HurtTime"
+    ));
+}
+
+#[derive(Debug, PartialEq, NbtCompound)]
+pub struct Lenient {
+    pub id: String,
+    pub count: i8,
+}
+
+#[test]
+pub fn nbt_compound_skips_unknown() {
+    let value = Value::Compound {
+        name: "".to_string(),
+        value: vec![
+            Value::LongArray {
+                name: "UUID".to_string(),
+                value: vec![1, 2],
+            },
+            Value::String {
+                name: "id".to_string(),
+                value: "minecraft:pig".to_string(),
+            },
+            Value::List {
+                name: "Passengers".to_string(),
+                value: List::Compound(vec![vec![
+                    Value::String {
+                        name: "id".to_string(),
+                        value: "minecraft:zombie".to_string(),
+                    },
+                    Value::List {
+                        name: "Motion".to_string(),
+                        value: List::Double(vec![0.0, 1.0, 0.5]),
+                    },
+                ]]),
+            },
+            Value::List {
+                name: "Nested".to_string(),
+                value: List::List(vec![
+                    List::String(vec!["a".to_string()]),
+                    List::Int(vec![1]),
+                ]),
+            },
+            Value::ByteArray {
+                name: "Bytes".to_string(),
+                value: vec![1, 2, 3],
+            },
+            Value::Byte {
+                name: "count".to_string(),
+                value: 2,
+            },
+        ],
+    };
+    let mut bytes = Vec::new();
+    value.write_alone(&mut bytes).expect("a write");
+    let (_, read) = Lenient::read_with_name(&mut &bytes[1..]).expect("a compound");
+    assert_eq!(
+        read,
+        Lenient {
+            id: "minecraft:pig".to_string(),
+            count: 2,
+        }
+    );
+}
+
+#[derive(Debug, PartialEq, NbtCompound)]
+pub struct AnyItem {
+    pub id: String,
+    pub tag: Option<Value>,
+    #[nbt(rename = "Damage")]
+    pub damage: NameLessValue,
+}
+
+#[test]
+pub fn nbt_compound_any_tag() {
+    let value = Value::Compound {
+        name: "".to_string(),
+        value: vec![
+            Value::String {
+                name: "id".to_string(),
+                value: "minecraft:diamond_sword".to_string(),
+            },
+            Value::Compound {
+                name: "tag".to_string(),
+                value: vec![Value::Int {
+                    name: "Damage".to_string(),
+                    value: 5,
+                }],
+            },
+            Value::Short {
+                name: "Damage".to_string(),
+                value: 5,
+            },
+        ],
+    };
+    let mut bytes = Vec::new();
+    value.clone().write_alone(&mut bytes).expect("a write");
+    let (_, item) = AnyItem::read_with_name(&mut &bytes[1..]).expect("an item");
+    assert_eq!(
+        item,
+        AnyItem {
+            id: "minecraft:diamond_sword".to_string(),
+            tag: Some(Value::Compound {
+                name: "tag".to_string(),
+                value: vec![Value::Int {
+                    name: "Damage".to_string(),
+                    value: 5,
+                }],
+            }),
+            damage: NameLessValue::Short(5),
+        }
+    );
+
+    let mut written = Vec::new();
+    item.write("", &mut written).expect("a write");
+    assert_eq!(written, bytes);
+}
+
+/// A type of its own that happens to be called `Value`
+mod counter {
+    use axolotl_nbt::binary::Binary;
+    use axolotl_nbt::{NBTDataType, NBTError, Tag};
+    use std::io::{Read, Write};
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Value(pub i32);
+
+    impl NBTDataType<Binary> for Value {
+        fn read<R: Read>(reader: &mut R) -> Result<Self, NBTError> {
+            Ok(Value(i32::read(reader)?))
+        }
+
+        fn write<W: Write, Name: AsRef<[u8]>>(
+            self,
+            name: Name,
+            writer: &mut W,
+        ) -> Result<(), NBTError> {
+            self.0.write(name, writer)
+        }
+
+        fn write_alone<W: Write>(self, writer: &mut W) -> Result<(), NBTError> {
+            self.0.write_alone(writer)
+        }
+
+        fn get_tag() -> Tag {
+            Tag::Int
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, NbtCompound)]
+pub struct Counted {
+    pub count: counter::Value,
+}
+
+#[test]
+pub fn nbt_compound_value_of_its_own() {
+    let count = |value: Value| {
+        let mut bytes = Vec::new();
+        Value::Compound {
+            name: "".to_string(),
+            value: vec![value],
+        }
+        .write_alone(&mut bytes)
+        .expect("a write");
+        bytes
+    };
+    let bytes = count(Value::Int {
+        name: "count".to_string(),
+        value: 5,
+    });
+    let (_, counted) = Counted::read_with_name(&mut &bytes[1..]).expect("a count");
+    assert_eq!(counted.count, counter::Value(5));
+    let mut written = Vec::new();
+    counted.write("", &mut written).expect("a write");
+    assert_eq!(written, bytes);
+
+    let bytes = count(Value::Short {
+        name: "count".to_string(),
+        value: 5,
+    });
+    assert!(matches!(
+        Counted::read_with_name(&mut &bytes[1..]),
+        Err(NBTError::ExpectedTag(Tag::Int, Tag::Short))
+    ));
+}
+
+#[derive(Debug, Clone, PartialEq, NbtCompound)]
+#[nbt(rename_all = "PascalCase", data_version = 3465)]
+pub struct Chunk {
+    pub x_pos: i32,
+    pub status: String,
+}
+
+#[test]
+pub fn nbt_compound_data_version() {
+    let chunk = Chunk {
+        x_pos: 4,
+        status: "minecraft:full".to_string(),
+    };
+    let mut bytes = Vec::new();
+    chunk.clone().write("", &mut bytes).expect("a write");
+    let mut value = Value::read(&mut bytes.as_slice()).expect("a value");
+    assert_eq!(
+        value.get_path(&"DataVersion".parse().unwrap()),
+        Some(ValueRef::Int(3465))
+    );
+    let (_, read) = Chunk::read_with_name(&mut &bytes[1..]).expect("a chunk");
+    assert_eq!(read, chunk);
+
+    let migrator = Migrator::new().register(Fix::rename(
+        3465,
+        "xPos".parse().unwrap(),
+        "XPos".parse().unwrap(),
+    ));
+    value
+        .apply(
+            &Patch::new()
+                .push(Operation::Replace {
+                    path: "DataVersion".parse().unwrap(),
+                    value: NameLessValue::Int(3000),
+                })
+                .push(Operation::Move {
+                    from: "XPos".parse().unwrap(),
+                    path: "xPos".parse().unwrap(),
+                }),
+        )
+        .expect("a patch");
+    assert_eq!(
+        migrator
+            .migrate_to::<Chunk>(&mut value)
+            .expect("a migration"),
+        3000
+    );
+    assert_eq!(
+        value.get_path(&"XPos".parse().unwrap()),
+        Some(ValueRef::Int(4))
+    );
+    assert_eq!(
+        value.get_path(&"DataVersion".parse().unwrap()),
+        Some(ValueRef::Int(Chunk::DATA_VERSION))
+    );
+}
+
+#[test]
+pub fn infer_from_samples() {
+    let working_directory = current_dir().expect("a current directory").join("tests");
+    let samples: Vec<Value> = ["test_one.nbt", "test_two.nbt"]
+        .iter()
+        .map(|file| {
+            let mut file = File::open(working_directory.join(file)).expect("a file");
+            Value::read(&mut file).expect("a value")
+        })
+        .collect();
+    let schema = codegen::infer(&samples);
+    for sample in &samples {
+        assert_eq!(schema.validate(sample), vec![]);
+    }
+
+    let code = codegen::generate("TestOne", &codegen::infer(&samples[..1]));
+    assert!(code.contains(
+        "pub struct TestOne {\n    pub banned_ids: Vec<i64>,\n    pub players: Vec<Players>,"
+    ));
+    assert!(code.contains("pub struct Skills {\n    pub name: String,\n    pub exp: i32,\n}"));
+}
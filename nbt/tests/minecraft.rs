@@ -0,0 +1,429 @@
+#![cfg(feature = "minecraft")]
+use axolotl_nbt::binary::binary_uuid::BinaryUUID;
+use axolotl_nbt::minecraft::block_entity::{BlockEntity, KnownBlockEntity};
+use axolotl_nbt::minecraft::{
+    BlockPos, Entity, Extra, ItemStack, LevelDat, Player, Structure, Vec3,
+};
+use axolotl_nbt::serde_impl;
+use axolotl_nbt::value::{diff, List, NameLessValue, Value};
+use axolotl_nbt::NBTDataType;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::env::current_dir;
+use std::fmt::Debug;
+use std::fs::File;
+
+fn compound(entries: Vec<(&str, NameLessValue)>) -> NameLessValue {
+    NameLessValue::Compound(
+        entries
+            .into_iter()
+            .map(|(name, value)| value.into_named(name))
+            .collect(),
+    )
+}
+
+fn entries(value: NameLessValue) -> Vec<Value> {
+    match value {
+        NameLessValue::Compound(entries) => entries,
+        other => panic!("Expected a compound, found {}", other),
+    }
+}
+
+fn string(value: &str) -> NameLessValue {
+    NameLessValue::String(value.to_string())
+}
+
+fn doubles(values: &[f64]) -> NameLessValue {
+    NameLessValue::List(List::Double(values.to_vec()))
+}
+
+fn ints(values: &[i32]) -> NameLessValue {
+    NameLessValue::List(List::Int(values.to_vec()))
+}
+
+/// Reads the value as `T` through the binary and the value deserializer and checks nothing is lost when written back
+fn round_trip<T: Serialize + DeserializeOwned + PartialEq + Debug>(value: NameLessValue) -> T {
+    let value = value.into_named("");
+    let mut bytes = Vec::new();
+    value.clone().write_alone(&mut bytes).expect("a write");
+    let typed: T = serde_impl::from_reader_binary(bytes.as_slice()).expect("a binary read");
+    assert_eq!(
+        typed,
+        serde_impl::from_value::<T>(value.clone()).expect("a value read")
+    );
+
+    let mut written = Vec::new();
+    serde_impl::to_writer(&mut written, &typed).expect("a write");
+    let written = Value::read(&mut written.as_slice()).expect("a value");
+    assert_eq!(diff(&value, &written), vec![]);
+    assert_eq!(
+        diff(&value, &serde_impl::to_value(&typed).expect("a value")),
+        vec![]
+    );
+    typed
+}
+
+/// A compound of only unknown keys
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Unknown {
+    #[serde(flatten)]
+    extra: Extra,
+}
+
+#[test]
+pub fn samples() {
+    let working_directory = current_dir().expect("a current directory").join("tests");
+    for file in ["test_one.nbt", "test_two.nbt"] {
+        let mut file = File::open(working_directory.join(file)).expect("a file");
+        let value = Value::read(&mut file).expect("a value");
+        round_trip::<Unknown>(value.into_nameless());
+    }
+}
+
+fn legacy_item(slot: i8) -> NameLessValue {
+    compound(vec![
+        ("Slot", NameLessValue::Byte(slot)),
+        ("id", string("minecraft:diamond_sword")),
+        ("Count", NameLessValue::Byte(1)),
+        (
+            "tag",
+            compound(vec![
+                ("Damage", NameLessValue::Int(12)),
+                (
+                    "display",
+                    compound(vec![("Name", string(r#"{"text":"Sting"}"#))]),
+                ),
+                (
+                    "Enchantments",
+                    NameLessValue::List(List::Compound(vec![entries(compound(vec![
+                        ("id", string("minecraft:sharpness")),
+                        ("lvl", NameLessValue::Short(5)),
+                    ]))])),
+                ),
+                ("HideFlags", NameLessValue::Int(1)),
+            ]),
+        ),
+    ])
+}
+
+fn component_item(slot: i8) -> NameLessValue {
+    compound(vec![
+        ("Slot", NameLessValue::Byte(slot)),
+        ("id", string("minecraft:elytra")),
+        ("count", NameLessValue::Int(1)),
+        (
+            "components",
+            compound(vec![
+                ("minecraft:damage", NameLessValue::Int(3)),
+                ("minecraft:custom_name", string(r#""Wings""#)),
+                ("minecraft:unbreakable", compound(vec![])),
+                (
+                    "minecraft:foo",
+                    NameLessValue::List(List::Byte(vec![1, 2])),
+                ),
+            ]),
+        ),
+    ])
+}
+
+fn entity_fields(id: Option<&str>) -> Vec<(&str, NameLessValue)> {
+    let mut fields = vec![
+        ("UUID", NameLessValue::IntArray(vec![1, 2, 3, 4])),
+        ("Pos", doubles(&[0.5, 64.0, -3.5])),
+        ("Motion", doubles(&[0.0, -0.08, 0.0])),
+        (
+            "Rotation",
+            NameLessValue::List(List::Float(vec![90.0, 0.0])),
+        ),
+        ("FallDistance", NameLessValue::Float(0.0)),
+        ("Fire", NameLessValue::Short(-20)),
+        ("Air", NameLessValue::Short(300)),
+        ("OnGround", NameLessValue::Byte(1)),
+        ("Invulnerable", NameLessValue::Byte(0)),
+        ("PortalCooldown", NameLessValue::Int(0)),
+    ];
+    if let Some(id) = id {
+        fields.insert(0, ("id", string(id)));
+    }
+    fields
+}
+
+fn player() -> NameLessValue {
+    let mut fields = entity_fields(None);
+    fields.extend([
+        ("DataVersion", NameLessValue::Int(3465)),
+        ("Health", NameLessValue::Float(20.0)),
+        ("foodLevel", NameLessValue::Int(20)),
+        ("XpLevel", NameLessValue::Int(3)),
+        ("XpP", NameLessValue::Float(0.5)),
+        ("XpTotal", NameLessValue::Int(40)),
+        ("playerGameType", NameLessValue::Int(0)),
+        ("SelectedItemSlot", NameLessValue::Int(2)),
+        (
+            "Inventory",
+            NameLessValue::List(List::Compound(vec![
+                entries(legacy_item(0)),
+                entries(component_item(1)),
+            ])),
+        ),
+        ("EnderItems", NameLessValue::List(List::End)),
+        (
+            "abilities",
+            compound(vec![
+                ("flying", NameLessValue::Byte(0)),
+                ("mayfly", NameLessValue::Byte(1)),
+                ("instabuild", NameLessValue::Byte(0)),
+                ("invulnerable", NameLessValue::Byte(0)),
+                ("mayBuild", NameLessValue::Byte(1)),
+                ("flySpeed", NameLessValue::Float(0.05)),
+                ("walkSpeed", NameLessValue::Float(0.1)),
+            ]),
+        ),
+        // Unknown keys
+        ("Brain", compound(vec![("memories", compound(vec![]))])),
+        (
+            "recipeBook",
+            compound(vec![(
+                "recipes",
+                NameLessValue::List(List::String(vec!["minecraft:torch".to_string()])),
+            )]),
+        ),
+        ("seenCredits", NameLessValue::Byte(0)),
+        ("LastDeathUUID", NameLessValue::IntArray(vec![5, 6, 7, 8])),
+        ("ModData", ints(&[4, 8, 15])),
+        (
+            "ModTimes",
+            NameLessValue::List(List::List(vec![
+                List::Long(vec![16, 23]),
+                List::Byte(vec![42]),
+            ])),
+        ),
+    ]);
+    compound(fields)
+}
+
+#[test]
+pub fn items() {
+    let item: ItemStack = round_trip(legacy_item(3));
+    assert!(matches!(item, ItemStack::Legacy(_)));
+    assert_eq!(item.id(), "minecraft:diamond_sword");
+    assert_eq!(item.slot(), Some(3));
+    let ItemStack::Legacy(legacy) = item else {
+        unreachable!()
+    };
+    let tag = legacy.tag.expect("a tag");
+    assert_eq!(tag.damage, Some(12));
+    assert_eq!(tag.enchantments.expect("enchantments")[0].lvl, 5);
+    assert_eq!(tag.extra["HideFlags"], NameLessValue::Int(1));
+
+    let item: ItemStack = round_trip(component_item(4));
+    assert_eq!(item.count(), 1);
+    let ItemStack::Components(item) = item else {
+        panic!("Expected the components form");
+    };
+    let components = item.components.expect("components");
+    assert_eq!(components.damage, Some(3));
+    assert!(components.extra.contains_key("minecraft:unbreakable"));
+    assert_eq!(
+        components.extra["minecraft:foo"],
+        NameLessValue::List(List::Byte(vec![1, 2]))
+    );
+
+    // The count defaults to one since 1.20.5
+    let item: ItemStack = round_trip(compound(vec![("id", string("minecraft:stick"))]));
+    assert_eq!(item.count(), 1);
+}
+
+#[test]
+pub fn players() {
+    let player: Player = round_trip(player());
+    assert_eq!(player.entity.uuid, Some(BinaryUUID([1, 2, 3, 4])));
+    assert_eq!(
+        player.entity.pos,
+        Vec3 {
+            x: 0.5,
+            y: 64.0,
+            z: -3.5
+        }
+    );
+    assert_eq!(player.entity.on_ground, Some(true));
+    assert_eq!(player.inventory.len(), 2);
+    assert!(player.abilities.expect("abilities").mayfly);
+    // Unknown keys keep their tag
+    assert_eq!(
+        player.entity.extra["LastDeathUUID"],
+        NameLessValue::IntArray(vec![5, 6, 7, 8])
+    );
+    assert_eq!(player.entity.extra["ModData"], ints(&[4, 8, 15]));
+
+    let mut zombie = entity_fields(Some("minecraft:zombie"));
+    let mut chicken = entity_fields(Some("minecraft:chicken"));
+    chicken.push(("ModData", ints(&[16, 23])));
+    zombie.push((
+        "Passengers",
+        NameLessValue::List(List::Compound(vec![entries(compound(chicken))])),
+    ));
+    zombie.push(("IsBaby", NameLessValue::Byte(1)));
+    let zombie: Entity = round_trip(compound(zombie));
+    assert_eq!(
+        zombie.passengers.expect("passengers")[0].id.as_deref(),
+        Some("minecraft:chicken")
+    );
+}
+
+#[test]
+pub fn level_dat() {
+    let level: LevelDat = round_trip(compound(vec![(
+        "Data",
+        compound(vec![
+            ("DataVersion", NameLessValue::Int(3465)),
+            ("LevelName", string("New World")),
+            ("version", NameLessValue::Int(19133)),
+            (
+                "Version",
+                compound(vec![
+                    ("Id", NameLessValue::Int(3465)),
+                    ("Name", string("1.20.1")),
+                    ("Series", string("main")),
+                    ("Snapshot", NameLessValue::Byte(0)),
+                ]),
+            ),
+            ("GameType", NameLessValue::Int(1)),
+            ("Difficulty", NameLessValue::Byte(2)),
+            ("hardcore", NameLessValue::Byte(0)),
+            ("allowCommands", NameLessValue::Byte(1)),
+            ("SpawnX", NameLessValue::Int(0)),
+            ("SpawnY", NameLessValue::Int(64)),
+            ("SpawnZ", NameLessValue::Int(0)),
+            ("Time", NameLessValue::Long(1200)),
+            ("DayTime", NameLessValue::Long(1000)),
+            ("LastPlayed", NameLessValue::Long(1_700_000_000_000)),
+            ("raining", NameLessValue::Byte(0)),
+            ("rainTime", NameLessValue::Int(5000)),
+            (
+                "GameRules",
+                compound(vec![("keepInventory", string("true"))]),
+            ),
+            ("Player", player()),
+            (
+                "WorldGenSettings",
+                compound(vec![("seed", NameLessValue::Long(42))]),
+            ),
+        ]),
+    )]));
+    assert_eq!(level.data.level_name, "New World");
+    assert_eq!(level.data.allow_commands, Some(true));
+    assert_eq!(
+        level.data.game_rules.expect("game rules")["keepInventory"],
+        "true"
+    );
+    assert!(level.data.player.is_some());
+}
+
+#[test]
+pub fn structures() {
+    let state = |name: &str| {
+        entries(compound(vec![
+            ("Name", string(name)),
+            ("Properties", compound(vec![("facing", string("north"))])),
+        ]))
+    };
+    let block = |index: i32, pos: &[i32], nbt: Option<NameLessValue>| {
+        let mut fields = vec![("state", NameLessValue::Int(index)), ("pos", ints(pos))];
+        fields.extend(nbt.map(|nbt| ("nbt", nbt)));
+        entries(compound(fields))
+    };
+    let mut chicken = entity_fields(Some("minecraft:chicken"));
+    chicken.remove(1);
+    let structure: Structure = round_trip(compound(vec![
+        ("DataVersion", NameLessValue::Int(3465)),
+        ("size", ints(&[2, 1, 1])),
+        (
+            "palette",
+            NameLessValue::List(List::Compound(vec![
+                state("minecraft:chest"),
+                state("minecraft:oak_sign"),
+            ])),
+        ),
+        (
+            "blocks",
+            NameLessValue::List(List::Compound(vec![
+                block(
+                    0,
+                    &[0, 0, 0],
+                    Some(compound(vec![
+                        ("id", string("minecraft:chest")),
+                        (
+                            "Items",
+                            NameLessValue::List(List::Compound(vec![entries(legacy_item(0))])),
+                        ),
+                        ("Weird", ints(&[42])),
+                    ])),
+                ),
+                block(
+                    1,
+                    &[1, 0, 0],
+                    Some(compound(vec![
+                        ("id", string("minecraft:sign")),
+                        (
+                            "front_text",
+                            compound(vec![
+                                (
+                                    "messages",
+                                    NameLessValue::List(List::String(vec![
+                                        r#""Hello""#.to_string(),
+                                        r#""""#.to_string(),
+                                        r#""""#.to_string(),
+                                        r#""""#.to_string(),
+                                    ])),
+                                ),
+                                ("color", string("black")),
+                                ("has_glowing_text", NameLessValue::Byte(0)),
+                            ]),
+                        ),
+                        ("is_waxed", NameLessValue::Byte(1)),
+                    ])),
+                ),
+                block(
+                    1,
+                    &[1, 0, 1],
+                    Some(compound(vec![
+                        ("id", string("minecraft:beehive")),
+                        ("bees", NameLessValue::List(List::End)),
+                        ("WeirdB", NameLessValue::List(List::Long(vec![7]))),
+                    ])),
+                ),
+                block(0, &[0, 0, 1], None),
+            ])),
+        ),
+        (
+            "entities",
+            NameLessValue::List(List::Compound(vec![entries(compound(vec![
+                ("pos", doubles(&[0.5, 0.0, 0.5])),
+                ("blockPos", ints(&[0, 0, 0])),
+                ("nbt", compound(chicken)),
+            ]))])),
+        ),
+    ]));
+    assert_eq!(structure.size, BlockPos { x: 2, y: 1, z: 1 });
+    let entities: Vec<&str> = structure
+        .blocks
+        .iter()
+        .filter_map(|block| block.nbt.as_ref().map(BlockEntity::id))
+        .collect();
+    assert_eq!(
+        entities,
+        ["minecraft:chest", "minecraft:sign", "minecraft:beehive"]
+    );
+    match &structure.blocks[1].nbt {
+        Some(BlockEntity::Known(KnownBlockEntity::Sign(sign))) => {
+            assert_eq!(sign.is_waxed, Some(true))
+        }
+        other => panic!("Expected a sign, found {:?}", other),
+    }
+    assert!(matches!(
+        structure.blocks[2].nbt,
+        Some(BlockEntity::Other(_))
+    ));
+    assert_eq!(structure.entities[0].nbt.uuid, None);
+}
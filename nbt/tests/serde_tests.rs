@@ -1,10 +1,11 @@
 use axolotl_nbt::binary::binary_uuid::BinaryUUID;
 use axolotl_nbt::binary::Binary;
 use axolotl_nbt::serde_impl;
-use axolotl_nbt::value::{NameLessValue, Value};
-use axolotl_nbt_macros::ListSerialize;
+use axolotl_nbt::value::{List, NameLessValue, Value};
+use axolotl_nbt::{NBTDataType, Tag};
+use axolotl_nbt_macros::{ListSerialize, NbtEnum};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::env::current_dir;
 use std::fs::File;
 use std::io::BufReader;
@@ -19,6 +20,9 @@ pub struct Armor {
     pub leggings: f32,
 }
 
+/// The derive used to generate a visitor with this name
+pub struct ArmorVisitor;
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct SimplePlayer {
     level: i32,
@@ -224,3 +228,1292 @@ pub fn empty_array_test() {
             .unwrap();
     println!("{:?}", data);
 }
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct EmptyListValueTest {
+    tags: Vec<String>,
+    nested: Vec<Vec<i32>>,
+    after: i32,
+}
+
+#[test]
+pub fn empty_list_value_test() {
+    let tests = EmptyListValueTest {
+        tags: vec![],
+        nested: vec![vec![]],
+        after: 5,
+    };
+    let mut bytes = Vec::new();
+    serde_impl::to_writer(&mut bytes, &tests).unwrap();
+    let value = Value::read(&mut bytes.as_slice()).unwrap();
+    assert_eq!(
+        value,
+        Value::Compound {
+            name: "EmptyListValueTest".to_string(),
+            value: vec![
+                Value::List {
+                    name: "tags".to_string(),
+                    value: List::End,
+                },
+                Value::List {
+                    name: "nested".to_string(),
+                    value: List::List(vec![List::End]),
+                },
+                Value::Int {
+                    name: "after".to_string(),
+                    value: 5,
+                },
+            ],
+        }
+    );
+    let data: EmptyListValueTest = serde_impl::from_reader_binary(bytes.as_slice()).unwrap();
+    assert_eq!(data, tests);
+}
+
+#[test]
+pub fn nested_compound_values() {
+    let value = Value::Compound {
+        name: "".to_string(),
+        value: vec![Value::Compound {
+            name: "Brain".to_string(),
+            value: vec![Value::Compound {
+                name: "memories".to_string(),
+                value: vec![],
+            }],
+        }],
+    };
+    let mut bytes = Vec::new();
+    serde_impl::to_writer(&mut bytes, &value).unwrap();
+    assert_eq!(Value::read(&mut bytes.as_slice()).unwrap(), value);
+    assert_eq!(serde_impl::to_value(&value).unwrap(), value);
+}
+
+#[test]
+pub fn to_value_matches_binary() {
+    let tests = ListTests {
+        list: vec![1, 2, 3, 4, 5],
+        list2: vec![1, 2, 3, 4, 5],
+        list3: vec![1, 2, 3, 4, 5],
+        list4: vec![1, 2, 3, 4, 5],
+        list_of_compounds: vec![SimplePlayer::default(), SimplePlayer::default()],
+    };
+    let mut bytes = Vec::new();
+    serde_impl::to_writer(&mut bytes, &tests).unwrap();
+    let value = serde_impl::to_value(&tests).unwrap();
+    assert_eq!(value, Value::read(&mut bytes.as_slice()).unwrap());
+    assert_eq!(value.get_name(), "ListTests");
+
+    let tests = ComplexList {
+        one: vec![vec![SimplePlayer::default()], vec![]],
+        two: vec![vec![1, 2, 3]],
+        three: vec![vec![1, 2, 3]],
+        four: vec![vec![1, 2, 3]],
+        five: vec![vec![1, 2, 3]],
+    };
+    let mut bytes = Vec::new();
+    serde_impl::to_writer(&mut bytes, &tests).unwrap();
+    let value = serde_impl::to_value(&tests).unwrap();
+    assert_eq!(value, Value::read(&mut bytes.as_slice()).unwrap());
+
+    let data: ComplexList = serde_impl::from_value(value).unwrap();
+    assert_eq!(data.three, tests.three);
+    assert_eq!(
+        serde_impl::to_nameless_value(&vec![1i64, 2]).unwrap(),
+        NameLessValue::LongArray(vec![1, 2])
+    );
+    assert_eq!(
+        serde_impl::to_nameless_value(&vec![vec![], vec![1i32]]).unwrap(),
+        NameLessValue::List(List::List(vec![List::End, List::Int(vec![1])]))
+    );
+
+    same_as_binary(vec![true, false]);
+    same_as_binary(vec![vec![true], vec![]]);
+    same_as_binary(vec![vec![], vec![1i32]]);
+    same_as_binary(vec![vec![1i32], vec![]]);
+    same_as_binary(vec![vec![vec![], vec![1i64]], vec![vec![2i64]]]);
+    same_as_binary(vec![ListOf(vec![]), ListOf(vec![1])]);
+    same_as_binary(NestedEncodings {
+        lists: vec![ListOf(vec![1, 2]), ListOf(vec![])],
+        empty: vec![ListOf(vec![]), ListOf(vec![3])],
+        deep: vec![vec![ListOf(vec![4])]],
+        arrays: vec![IntsOf(vec![]), IntsOf(vec![5])],
+    });
+}
+
+#[derive(Serialize)]
+pub struct Field<T> {
+    value: T,
+}
+
+/// Checks [serde_impl::to_value] gives the tree [serde_impl::to_writer] writes
+fn same_as_binary<T: Serialize>(value: T) {
+    let value = Field { value };
+    let mut bytes = Vec::new();
+    serde_impl::to_writer(&mut bytes, &value).unwrap();
+    assert_eq!(
+        serde_impl::to_value(&value).unwrap(),
+        Value::read(&mut bytes.as_slice()).unwrap()
+    );
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Flattened {
+    level: i32,
+    #[serde(flatten)]
+    extra: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum Untagged {
+    Named { name: String, level: i32 },
+    Other { level: i32 },
+}
+
+#[test]
+pub fn self_describing() {
+    let tests = SimplePlayer {
+        uuid: BinaryUUID([1, 2, 3, 4]),
+        ..Default::default()
+    };
+    let mut bytes = Vec::new();
+    serde_impl::to_writer(&mut bytes, &tests).unwrap();
+
+    let json: serde_json::Value = serde_impl::from_reader_binary(bytes.as_slice()).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "level": 5,
+            "name": "Player",
+            "armor": [0.0, 0.0, 0.0, 0.0],
+            "experience": 0.0,
+            "uuid": [1, 2, 3, 4],
+        })
+    );
+    let map: HashMap<String, serde_json::Value> =
+        serde_impl::from_reader_binary(bytes.as_slice()).unwrap();
+    assert_eq!(map["name"], "Player");
+
+    let flattened: Flattened = serde_impl::from_reader_binary(bytes.as_slice()).unwrap();
+    assert_eq!(flattened.level, 5);
+    assert_eq!(flattened.extra["uuid"], serde_json::json!([1, 2, 3, 4]));
+
+    let untagged: Untagged = serde_impl::from_reader_binary(bytes.as_slice()).unwrap();
+    assert_eq!(
+        untagged,
+        Untagged::Named {
+            name: "Player".to_string(),
+            level: 5
+        }
+    );
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub enum Externally {
+    Unit,
+    Newtype(i32),
+    Tuple(i16, i16),
+    Struct { health: f32, name: String },
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(tag = "id")]
+pub enum Internally {
+    #[serde(rename = "minecraft:zombie")]
+    Zombie { health: f32, baby: i8 },
+    #[serde(rename = "minecraft:item")]
+    Item { item: String },
+    #[serde(rename = "minecraft:marker")]
+    Marker,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(tag = "type", content = "data")]
+pub enum Adjacently {
+    Text(String),
+    Position { x: i32, z: i32 },
+    Empty,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Enums {
+    externally: Vec<Externally>,
+    unit: Externally,
+    struct_variant: Externally,
+    internally: Vec<Internally>,
+    adjacently: Vec<Adjacently>,
+}
+
+#[test]
+pub fn enums() {
+    let tests = Enums {
+        externally: vec![
+            Externally::Newtype(5),
+            Externally::Tuple(1, 2),
+            Externally::Struct {
+                health: 20.0,
+                name: "zombie".to_string(),
+            },
+        ],
+        unit: Externally::Unit,
+        struct_variant: Externally::Struct {
+            health: 1.0,
+            name: "pig".to_string(),
+        },
+        internally: vec![
+            Internally::Zombie {
+                health: 20.0,
+                baby: 1,
+            },
+            Internally::Item {
+                item: "minecraft:stone".to_string(),
+            },
+            Internally::Marker,
+        ],
+        adjacently: vec![
+            Adjacently::Text("hello".to_string()),
+            Adjacently::Position { x: 1, z: -1 },
+            Adjacently::Empty,
+        ],
+    };
+    let mut bytes = Vec::new();
+    serde_impl::to_writer(&mut bytes, &tests).unwrap();
+    let data: Enums = serde_impl::from_reader_binary(bytes.as_slice()).unwrap();
+    assert_eq!(data, tests);
+
+    let value = Value::read(&mut bytes.as_slice()).unwrap();
+    assert_eq!(value, serde_impl::to_value(&tests).unwrap());
+    assert_eq!(
+        value.to_string(),
+        "{externally:[{Newtype:5},{Tuple:[1s,2s]},{Struct:{health:20f,name:\"zombie\"}}],\
+         unit:\"Unit\",\
+         struct_variant:{Struct:{health:1f,name:\"pig\"}},\
+         internally:[{id:\"minecraft:zombie\",health:20f,baby:1b},{id:\"minecraft:item\",item:\"minecraft:stone\"},{id:\"minecraft:marker\"}],\
+         adjacently:[{type:\"Text\",data:\"hello\"},{type:\"Position\",data:{x:1,z:-1}},{type:\"Empty\"}]}"
+    );
+    let data: Enums = serde_impl::from_value(value).unwrap();
+    assert_eq!(data, tests);
+
+    let mut bytes = Vec::new();
+    serde_impl::to_writer(&mut bytes, &Externally::Tuple(3, 4)).unwrap();
+    let root: Externally = serde_impl::from_reader_binary(bytes.as_slice()).unwrap();
+    assert_eq!(root, Externally::Tuple(3, 4));
+    let mut bytes = Vec::new();
+    serde_impl::to_writer(&mut bytes, &Internally::Marker).unwrap();
+    let root: Internally = serde_impl::from_reader_binary(bytes.as_slice()).unwrap();
+    assert_eq!(root, Internally::Marker);
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct NestedEnums {
+    waves: Vec<Vec<Externally>>,
+}
+
+#[test]
+pub fn nested_enums() {
+    let tests = NestedEnums {
+        waves: vec![
+            vec![
+                Externally::Newtype(5),
+                Externally::Tuple(1, 2),
+                Externally::Struct {
+                    health: 20.0,
+                    name: "zombie".to_string(),
+                },
+            ],
+            vec![],
+            vec![Externally::Tuple(3, 4)],
+        ],
+    };
+    let mut bytes = Vec::new();
+    serde_impl::to_writer(&mut bytes, &tests).unwrap();
+    let value = Value::read(&mut bytes.as_slice()).unwrap();
+    assert_eq!(
+        value.to_string(),
+        "{waves:[[{Newtype:5},{Tuple:[1s,2s]},{Struct:{health:20f,name:\"zombie\"}}],[],[{Tuple:[3s,4s]}]]}"
+    );
+    assert_eq!(value, serde_impl::to_value(&tests).unwrap());
+    let data: NestedEnums = serde_impl::from_reader_binary(bytes.as_slice()).unwrap();
+    assert_eq!(data, tests);
+    let data: NestedEnums = serde_impl::from_value(value).unwrap();
+    assert_eq!(data, tests);
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct BlockPos(i32, i32, i32);
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Tuples {
+    position: (f64, f64, f64),
+    rotation: [f32; 2],
+    block: BlockPos,
+    path: Vec<(i16, i16)>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct ShortPosition {
+    position: Vec<f64>,
+}
+
+#[test]
+pub fn tuples() {
+    let tests = Tuples {
+        position: (1.5, 64.0, -3.25),
+        rotation: [90.0, 0.0],
+        block: BlockPos(1, 64, -4),
+        path: vec![(0, 0), (1, 2)],
+    };
+    let mut bytes = Vec::new();
+    serde_impl::to_writer(&mut bytes, &tests).unwrap();
+    let value = Value::read(&mut bytes.as_slice()).unwrap();
+    assert_eq!(
+        value.to_string(),
+        "{position:[1.5d,64d,-3.25d],rotation:[90f,0f],block:[I;1,64,-4],path:[[0s,0s],[1s,2s]]}"
+    );
+    assert_eq!(value, serde_impl::to_value(&tests).unwrap());
+    let data: Tuples = serde_impl::from_reader_binary(bytes.as_slice()).unwrap();
+    assert_eq!(data, tests);
+    let data: Tuples = serde_impl::from_value(value).unwrap();
+    assert_eq!(data, tests);
+
+    let mut bytes = Vec::new();
+    let short = ShortPosition {
+        position: vec![1.0, 2.0],
+    };
+    serde_impl::to_writer(&mut bytes, &short).unwrap();
+    let error = serde_impl::from_reader_binary::<_, Tuples>(bytes.as_slice()).unwrap_err();
+    assert!(matches!(error.error, serde_impl::Error::IncorrectLengthError(3, 2)));
+    let error =
+        serde_impl::from_value::<Tuples>(serde_impl::to_value(&short).unwrap()).unwrap_err();
+    assert!(matches!(error.error, serde_impl::Error::IncorrectLengthError(3, 2)));
+}
+
+#[derive(Serialize, Debug)]
+pub struct Heterogeneous {
+    pair: (i32, String),
+}
+
+#[derive(Serialize, Debug)]
+pub enum HeterogeneousVariant {
+    Pair(Vec<i32>, i32),
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct NestedLists {
+    chunks: Vec<Vec<Vec<i32>>>,
+    names: Vec<Vec<Vec<String>>>,
+    flags: Vec<Vec<i8>>,
+}
+
+#[test]
+pub fn heterogeneous_lists() {
+    let mismatch = |error: serde_impl::Error, expected: Tag, found: Tag| {
+        assert!(
+            matches!(
+                error,
+                serde_impl::Error::NBTErr(axolotl_nbt::NBTError::ListTypeMismatch(e, f))
+                    if e == expected && f == found
+            ),
+            "{:?}",
+            error
+        );
+    };
+    let mut bytes = Vec::new();
+    let error = serde_impl::to_writer(
+        &mut bytes,
+        &Heterogeneous {
+            pair: (1, "a".to_string()),
+        },
+    )
+    .unwrap_err();
+    mismatch(error, Tag::Int, Tag::String);
+    let mut bytes = Vec::new();
+    let error =
+        serde_impl::to_writer(&mut bytes, &HeterogeneousVariant::Pair(vec![1], 2)).unwrap_err();
+    mismatch(error, Tag::IntArray, Tag::Int);
+
+    let tests = NestedLists {
+        chunks: vec![vec![vec![1, 2], vec![]], vec![]],
+        names: vec![vec![vec!["a".to_string()]]],
+        flags: vec![vec![], vec![1]],
+    };
+    let mut bytes = Vec::new();
+    serde_impl::to_writer(&mut bytes, &tests).unwrap();
+    let value = Value::read(&mut bytes.as_slice()).unwrap();
+    // The first list of flags is empty. So the later ones are lists too
+    assert_eq!(
+        value.to_string(),
+        r#"{chunks:[[[I;1,2],[I;]],[]],names:[[["a"]]],flags:[[],[1b]]}"#
+    );
+    let data: NestedLists = serde_impl::from_reader_binary(bytes.as_slice()).unwrap();
+    assert_eq!(data, tests);
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Encodings {
+    #[serde(with = "serde_impl::as_list")]
+    pos: Vec<i32>,
+    #[serde(with = "serde_impl::as_list")]
+    motion: [i64; 2],
+    #[serde(with = "serde_impl::as_int_array")]
+    uuid: [u32; 4],
+    #[serde(with = "serde_impl::as_int_array")]
+    empty: Vec<i32>,
+    #[serde(with = "serde_impl::as_byte_array")]
+    flags: Vec<u8>,
+    data: serde_impl::LongArray,
+    bytes: serde_impl::ByteArray,
+    arrays: Vec<serde_impl::IntArray>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct BadEncoding {
+    #[serde(with = "serde_impl::as_int_array")]
+    shorts: Vec<i16>,
+}
+
+#[test]
+pub fn encodings() {
+    let tests = Encodings {
+        pos: vec![1, 2, 3],
+        motion: [4, 5],
+        uuid: [1, 2, 3, 4],
+        empty: vec![],
+        flags: vec![0, 127],
+        data: serde_impl::LongArray(vec![6, 7]),
+        bytes: serde_impl::ByteArray(vec![8]),
+        arrays: vec![serde_impl::IntArray(vec![9])],
+    };
+    let mut bytes = Vec::new();
+    serde_impl::to_writer(&mut bytes, &tests).unwrap();
+    let value = Value::read(&mut bytes.as_slice()).unwrap();
+    assert_eq!(
+        value.to_string(),
+        "{pos:[1,2,3],motion:[4L,5L],uuid:[I;1,2,3,4],empty:[I;],flags:[B;0b,127b],\
+         data:[L;6L,7L],bytes:[B;8b],arrays:[[I;9]]}"
+    );
+    assert_eq!(value, serde_impl::to_value(&tests).unwrap());
+    let data: Encodings = serde_impl::from_reader_binary(bytes.as_slice()).unwrap();
+    assert_eq!(data, tests);
+    let data: Encodings = serde_impl::from_value(value).unwrap();
+    assert_eq!(data, tests);
+
+    let bad = BadEncoding {
+        shorts: vec![1, 2],
+    };
+    assert!(serde_impl::to_writer(&mut Vec::new(), &bad).is_err());
+    assert!(serde_impl::to_value(&bad).is_err());
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct ListOf(#[serde(with = "serde_impl::as_list")] Vec<i32>);
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct IntsOf(#[serde(with = "serde_impl::as_int_array")] Vec<i32>);
+
+#[derive(Serialize, Debug)]
+pub struct ShortsOf(#[serde(with = "serde_impl::as_int_array")] Vec<i16>);
+
+#[derive(Serialize, Debug)]
+pub struct BadNestedEncoding {
+    shorts: Vec<ShortsOf>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct NestedEncodings {
+    lists: Vec<ListOf>,
+    empty: Vec<ListOf>,
+    deep: Vec<Vec<ListOf>>,
+    arrays: Vec<IntsOf>,
+}
+
+#[test]
+pub fn nested_encodings() {
+    let tests = NestedEncodings {
+        lists: vec![ListOf(vec![1, 2]), ListOf(vec![])],
+        empty: vec![ListOf(vec![]), ListOf(vec![3])],
+        deep: vec![vec![ListOf(vec![4])]],
+        arrays: vec![IntsOf(vec![]), IntsOf(vec![5])],
+    };
+    let mut bytes = Vec::new();
+    serde_impl::to_writer(&mut bytes, &tests).unwrap();
+    let value = Value::read(&mut bytes.as_slice()).unwrap();
+    assert_eq!(
+        value.to_string(),
+        "{lists:[[1,2],[]],empty:[[],[3]],deep:[[[4]]],arrays:[[I;],[I;5]]}"
+    );
+    assert_eq!(value, serde_impl::to_value(&tests).unwrap());
+    let data: NestedEncodings = serde_impl::from_reader_binary(bytes.as_slice()).unwrap();
+    assert_eq!(data, tests);
+
+    let bad = BadNestedEncoding {
+        shorts: vec![ShortsOf(vec![1])],
+    };
+    assert!(matches!(
+        serde_impl::to_writer(&mut Vec::new(), &bad),
+        Err(serde_impl::Error::IncorrectTagError(Tag::Int, Tag::Short))
+    ));
+    assert!(serde_impl::to_value(&bad).is_err());
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct BytesAndChars {
+    #[serde(with = "serde_bytes")]
+    raw: Vec<u8>,
+    buffers: Vec<serde_bytes::ByteBuf>,
+    letter: char,
+    letters: Vec<char>,
+}
+
+#[test]
+pub fn bytes_and_chars() {
+    let tests = BytesAndChars {
+        raw: vec![0, 1, 255],
+        buffers: vec![serde_bytes::ByteBuf::from(vec![200])],
+        letter: 'é',
+        letters: vec!['a', 'b'],
+    };
+    let mut bytes = Vec::new();
+    serde_impl::to_writer(&mut bytes, &tests).unwrap();
+    let value = Value::read(&mut bytes.as_slice()).unwrap();
+    assert_eq!(
+        value.to_string(),
+        "{raw:[B;0b,1b,-1b],buffers:[[B;-56b]],letter:\"é\",letters:[\"a\",\"b\"]}"
+    );
+    assert_eq!(value, serde_impl::to_value(&tests).unwrap());
+    let data: BytesAndChars = serde_impl::from_reader_binary(bytes.as_slice()).unwrap();
+    assert_eq!(data, tests);
+    let data: BytesAndChars = serde_impl::from_value(value).unwrap();
+    assert_eq!(data, tests);
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, PartialOrd, Ord, Eq, Clone, Copy)]
+pub enum Slot {
+    Head,
+    Feet,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Extra {
+    id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct MapKeys {
+    scores: BTreeMap<i32, i32>,
+    owners: BTreeMap<Uuid, u8>,
+    flags: BTreeMap<bool, i8>,
+    letters: BTreeMap<char, i8>,
+    slots: BTreeMap<Slot, String>,
+    #[serde(flatten)]
+    extra: Extra,
+}
+
+#[test]
+pub fn map_keys() {
+    let uuid = Uuid::from_u128(1);
+    let tests = MapKeys {
+        scores: BTreeMap::from([(-1, 10), (20, 30)]),
+        owners: BTreeMap::from([(uuid, 1)]),
+        flags: BTreeMap::from([(true, 1)]),
+        letters: BTreeMap::from([('x', 2)]),
+        slots: BTreeMap::from([(Slot::Head, "helmet".to_string())]),
+        extra: Extra {
+            id: "minecraft:zombie".to_string(),
+        },
+    };
+    let mut bytes = Vec::new();
+    serde_impl::to_writer(&mut bytes, &tests).unwrap();
+    let value = Value::read(&mut bytes.as_slice()).unwrap();
+    assert_eq!(
+        value.to_string(),
+        "{scores:{-1:10,20:30},owners:{00000000-0000-0000-0000-000000000001:1b},\
+         flags:{true:1b},letters:{x:2b},slots:{Head:\"helmet\"},id:\"minecraft:zombie\"}"
+    );
+    assert_eq!(value, serde_impl::to_value(&tests).unwrap());
+    let data: MapKeys = serde_impl::from_reader_binary(bytes.as_slice()).unwrap();
+    assert_eq!(data, tests);
+    let data: MapKeys = serde_impl::from_value(value).unwrap();
+    assert_eq!(data, tests);
+
+    let mut bytes = Vec::new();
+    serde_impl::to_writer(&mut bytes, &HashMap::from([("a", 1)])).unwrap();
+    let invalid: Result<HashMap<i32, i32>, _> = serde_impl::from_reader_binary(bytes.as_slice());
+    assert!(invalid.is_err());
+
+    let binary = serde_impl::to_writer(&mut Vec::new(), &ValueWithoutKey).unwrap_err();
+    let value = serde_impl::to_value(&ValueWithoutKey).unwrap_err();
+    assert!(matches!(binary, serde_impl::Error::Custom(_)));
+    assert!(matches!(value, serde_impl::Error::Custom(_)));
+    assert_eq!(binary.to_string(), value.to_string());
+
+    // The error of a key is returned whatever the value is
+    let strings = BTreeMap::from([(BadKey, vec!["a".to_string()])]);
+    let compounds = BTreeMap::from([(BadKey, vec![Extra { id: "a".to_string() }])]);
+    let empty = BTreeMap::from([(BadKey, Vec::<i32>::new())]);
+    let errors = [
+        serde_impl::to_writer(&mut Vec::new(), &strings).unwrap_err(),
+        serde_impl::to_writer(&mut Vec::new(), &compounds).unwrap_err(),
+        serde_impl::to_writer(&mut Vec::new(), &empty).unwrap_err(),
+        serde_impl::to_writer(&mut Vec::new(), &BTreeMap::from([(BadKey, 1)])).unwrap_err(),
+        serde_impl::to_value(&strings).unwrap_err(),
+    ];
+    for error in errors {
+        assert!(matches!(&error, serde_impl::Error::Custom(message) if message == "bad key"));
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct FlattenedValues {
+    id: String,
+    kept: BTreeMap<String, NameLessValue>,
+    #[serde(flatten)]
+    rest: BTreeMap<String, NameLessValue>,
+}
+
+#[test]
+pub fn flattened_values() {
+    let lists = BTreeMap::from([
+        (
+            "bytes".to_string(),
+            NameLessValue::List(List::Byte(vec![1])),
+        ),
+        (
+            "ints".to_string(),
+            NameLessValue::List(List::Int(vec![1, 2])),
+        ),
+        ("int_array".to_string(), NameLessValue::IntArray(vec![1, 2])),
+        (
+            "longs".to_string(),
+            NameLessValue::List(List::List(vec![List::Long(vec![3]), List::End])),
+        ),
+        (
+            "long_arrays".to_string(),
+            NameLessValue::List(List::LongArray(vec![vec![4], vec![5, 6]])),
+        ),
+    ]);
+    let tests = FlattenedValues {
+        id: "minecraft:zombie".to_string(),
+        kept: lists.clone(),
+        rest: lists,
+    };
+    let mut bytes = Vec::new();
+    serde_impl::to_writer(&mut bytes, &tests).unwrap();
+    let value = Value::read(&mut bytes.as_slice()).unwrap();
+    let snbt = "{bytes:[1b],int_array:[I;1,2],ints:[1,2],long_arrays:[[L;4L],[L;5L,6L]],longs:[[3L],[]]}";
+    assert_eq!(
+        value.to_string(),
+        format!(
+            "{{id:\"minecraft:zombie\",kept:{snbt},{}",
+            &snbt[1..]
+        )
+    );
+    assert_eq!(value, serde_impl::to_value(&tests).unwrap());
+
+    // serde buffers flattened values, so lists of bytes, ints and longs come back as arrays
+    let mut expected = tests.rest.clone();
+    expected.insert("bytes".to_string(), NameLessValue::ByteArray(vec![1]));
+    expected.insert("ints".to_string(), NameLessValue::IntArray(vec![1, 2]));
+    expected.insert(
+        "longs".to_string(),
+        NameLessValue::List(List::LongArray(vec![vec![3], vec![]])),
+    );
+    let data: FlattenedValues = serde_impl::from_reader_binary(bytes.as_slice()).unwrap();
+    assert_eq!(data.kept, tests.kept);
+    assert_eq!(data.rest, expected);
+    let data: FlattenedValues = serde_impl::from_value(value).unwrap();
+    assert_eq!(data.kept, tests.kept);
+    assert_eq!(data.rest, expected);
+
+    // Other self describing formats read the lists as sequences
+    let json: serde_json::Value = serde_impl::from_reader_binary(bytes.as_slice()).unwrap();
+    assert_eq!(json["ints"], serde_json::json!([1, 2]));
+    assert_eq!(json["longs"], serde_json::json!([[3], []]));
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(tag = "id")]
+pub enum TaggedLists {
+    Zombie {
+        #[serde(with = "serde_impl::as_list")]
+        pos: Vec<i32>,
+        #[serde(with = "serde_impl::as_list")]
+        times: Vec<i64>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum UntaggedLists {
+    Bytes {
+        #[serde(with = "serde_impl::as_list")]
+        flags: Vec<i8>,
+    },
+    Ints {
+        #[serde(with = "serde_impl::as_list")]
+        pos: Vec<i32>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Inner {
+    #[serde(with = "serde_impl::as_list")]
+    times: Vec<i64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct FlattenedLists {
+    #[serde(with = "serde_impl::as_list")]
+    pos: Vec<i32>,
+    #[serde(flatten)]
+    inner: Inner,
+}
+
+fn buffered<T: Serialize + serde::de::DeserializeOwned + PartialEq + std::fmt::Debug>(
+    tests: T,
+    snbt: &str,
+) {
+    let mut bytes = Vec::new();
+    serde_impl::to_writer(&mut bytes, &tests).unwrap();
+    let value = Value::read(&mut bytes.as_slice()).unwrap();
+    assert_eq!(value.to_string(), snbt);
+    let data: T = serde_impl::from_reader_binary(bytes.as_slice()).unwrap();
+    assert_eq!(data, tests);
+    let data: T = serde_impl::from_value(value).unwrap();
+    assert_eq!(data, tests);
+}
+
+#[test]
+pub fn buffered_lists() {
+    buffered(
+        TaggedLists::Zombie {
+            pos: vec![1, 2],
+            times: vec![3],
+        },
+        r#"{id:"Zombie",pos:[1,2],times:[3L]}"#,
+    );
+    buffered(UntaggedLists::Ints { pos: vec![1, 2] }, "{pos:[1,2]}");
+    buffered(UntaggedLists::Bytes { flags: vec![1] }, "{flags:[1b]}");
+    buffered(
+        FlattenedLists {
+            pos: vec![1, 2],
+            inner: Inner { times: vec![3] },
+        },
+        "{pos:[1,2],times:[3L]}",
+    );
+}
+
+/// A map that serializes a value without its key
+pub struct ValueWithoutKey;
+
+impl Serialize for ValueWithoutKey {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+        let mut map = serializer.serialize_map(Some(1))?;
+        map.serialize_value(&1)?;
+        map.end()
+    }
+}
+
+/// A map key that can not be serialized
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+pub struct BadKey;
+
+impl Serialize for BadKey {
+    fn serialize<S: serde::Serializer>(&self, _: S) -> Result<S::Ok, S::Error> {
+        Err(serde::ser::Error::custom("bad key"))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Optionals {
+    name: Option<String>,
+    scores: Option<Vec<i32>>,
+    armor: Option<Vec<Extra>>,
+    owner: Option<Extra>,
+    marker: (),
+    flagged: Option<()>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct OptionalEncodings {
+    #[serde(with = "serde_impl::as_list", default)]
+    pos: Option<Vec<i32>>,
+    #[serde(with = "serde_impl::as_int_array", default)]
+    uuid: Option<Vec<i32>>,
+}
+
+#[test]
+pub fn options() {
+    let tests = Optionals {
+        name: None,
+        scores: Some(vec![1, 2]),
+        armor: Some(vec![Extra {
+            id: "minecraft:iron_helmet".to_string(),
+        }]),
+        owner: Some(Extra {
+            id: "minecraft:player".to_string(),
+        }),
+        marker: (),
+        flagged: Some(()),
+    };
+    let mut bytes = Vec::new();
+    serde_impl::to_writer(&mut bytes, &tests).unwrap();
+    let value = Value::read(&mut bytes.as_slice()).unwrap();
+    assert_eq!(
+        value.to_string(),
+        "{scores:[I;1,2],armor:[{id:\"minecraft:iron_helmet\"}],owner:{id:\"minecraft:player\"},\
+         marker:{},flagged:{}}"
+    );
+    assert_eq!(value, serde_impl::to_value(&tests).unwrap());
+    let data: Optionals = serde_impl::from_reader_binary(bytes.as_slice()).unwrap();
+    assert_eq!(data, tests);
+    let data: Optionals = serde_impl::from_value(value).unwrap();
+    assert_eq!(data, tests);
+
+    // Missing entries are None
+    let empty = Optionals {
+        name: None,
+        scores: None,
+        armor: None,
+        owner: None,
+        marker: (),
+        flagged: None,
+    };
+    let mut bytes = Vec::new();
+    serde_impl::to_writer(&mut bytes, &empty).unwrap();
+    let value = Value::read(&mut bytes.as_slice()).unwrap();
+    assert_eq!(value.to_string(), "{marker:{}}");
+    let data: Optionals = serde_impl::from_reader_binary(bytes.as_slice()).unwrap();
+    assert_eq!(data, empty);
+    let data: Optionals = serde_impl::from_value(value).unwrap();
+    assert_eq!(data, empty);
+
+    // None behind an adapter is left out too
+    for tests in [
+        OptionalEncodings {
+            pos: None,
+            uuid: None,
+        },
+        OptionalEncodings {
+            pos: Some(vec![1]),
+            uuid: Some(vec![]),
+        },
+    ] {
+        let mut bytes = Vec::new();
+        serde_impl::to_writer(&mut bytes, &tests).unwrap();
+        let value = Value::read(&mut bytes.as_slice()).unwrap();
+        assert_eq!(value, serde_impl::to_value(&tests).unwrap());
+        let data: OptionalEncodings = serde_impl::from_value(value).unwrap();
+        assert_eq!(data, tests);
+    }
+
+    // Some at the root is transparent
+    let mut bytes = Vec::new();
+    serde_impl::to_writer(&mut bytes, &Some(&empty)).unwrap();
+    let data: Option<Optionals> = serde_impl::from_reader_binary(bytes.as_slice()).unwrap();
+    assert_eq!(data, Some(empty));
+
+    // Lists can not have holes
+    let holes = HashMap::from([("values", vec![Some(1), None])]);
+    assert!(serde_impl::to_writer(&mut Vec::new(), &holes).is_err());
+    assert!(serde_impl::to_value(&holes).is_err());
+    assert!(serde_impl::to_writer(&mut Vec::new(), &None::<Optionals>).is_err());
+    assert!(serde_impl::to_value(&None::<Optionals>).is_err());
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Unsigned {
+    byte: u8,
+    short: u16,
+    int: u32,
+    long: u64,
+    ints: Vec<u32>,
+    wide: i128,
+    id: u128,
+}
+
+#[test]
+pub fn unsigned_policies() {
+    use axolotl_nbt::serde_impl::serialize::NBTSerializer;
+    use axolotl_nbt::serde_impl::UnsignedPolicy;
+    use axolotl_nbt::serde_impl::value::serialize::ValueSerializer;
+
+    let write = |value: &Unsigned, policy: UnsignedPolicy| {
+        let mut bytes = Vec::new();
+        value
+            .serialize(NBTSerializer::<'_, _, Binary>::new(&mut bytes).unsigned(policy))
+            .map(|_| bytes)
+    };
+    let tests = Unsigned {
+        byte: 200,
+        short: 40000,
+        int: u32::MAX,
+        long: 5,
+        ints: vec![3_000_000_000, 1],
+        wide: -2,
+        id: 1 << 96 | 7,
+    };
+
+    let bytes = write(&tests, UnsignedPolicy::BitCast).unwrap();
+    let value = Value::read(&mut bytes.as_slice()).unwrap();
+    assert_eq!(
+        value.to_string(),
+        "{byte:-56b,short:-25536s,int:-1,long:5L,ints:[I;-1294967296,1],\
+         wide:[I;-1,-1,-1,-2],id:[I;1,0,0,7]}"
+    );
+    assert_eq!(value, serde_impl::to_value(&tests).unwrap());
+    let data: Unsigned = serde_impl::from_reader_binary(bytes.as_slice()).unwrap();
+    assert_eq!(data, tests);
+    let data: Unsigned = serde_impl::from_value(value).unwrap();
+    assert_eq!(data, tests);
+
+    let bytes = write(&tests, UnsignedPolicy::Widen).unwrap();
+    let value = Value::read(&mut bytes.as_slice()).unwrap();
+    assert_eq!(
+        value.to_string(),
+        "{byte:200s,short:40000,int:4294967295L,long:5L,ints:[L;3000000000L,1L],\
+         wide:[I;-1,-1,-1,-2],id:[I;1,0,0,7]}"
+    );
+    let widened = tests
+        .serialize(ValueSerializer::default().unsigned(UnsignedPolicy::Widen))
+        .unwrap();
+    assert_eq!(value, widened);
+    let data: Unsigned = serde_impl::from_reader_binary(bytes.as_slice()).unwrap();
+    assert_eq!(data, tests);
+    let data: Unsigned = serde_impl::from_value(value).unwrap();
+    assert_eq!(data, tests);
+
+    assert!(write(&tests, UnsignedPolicy::Checked).is_err());
+    let small = Unsigned {
+        byte: 1,
+        short: 2,
+        int: 3,
+        long: 4,
+        ints: vec![5],
+        wide: 6,
+        id: 7,
+    };
+    let bytes = write(&small, UnsignedPolicy::Checked).unwrap();
+    assert_eq!(bytes, write(&small, UnsignedPolicy::BitCast).unwrap());
+
+    // A wider tag must fit
+    let negative = Value::Compound {
+        name: String::new(),
+        value: vec![Value::Long {
+            name: "int".to_string(),
+            value: -1,
+        }],
+    };
+    let invalid: Result<HashMap<String, u32>, _> = serde_impl::from_value(negative);
+    assert!(invalid.is_err());
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[allow(non_snake_case)]
+pub struct ItemSlot {
+    Count: i8,
+    id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[allow(non_snake_case)]
+pub struct Inventory {
+    Inventory: Vec<ItemSlot>,
+    OnGround: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Save {
+    player: Inventory,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[allow(non_snake_case)]
+pub struct UnsignedSlot {
+    Count: u8,
+}
+
+/// A root compound holding a single entry named `a`
+fn entry(tag: Tag, payload: &[u8]) -> Vec<u8> {
+    let mut bytes = vec![Tag::Compound as u8, 0, 0, tag as u8, 0, 1, b'a'];
+    bytes.extend_from_slice(payload);
+    bytes.push(Tag::End as u8);
+    bytes
+}
+
+#[test]
+pub fn hostile_lengths() {
+    // A list of End takes no bytes per element. So it can not have any
+    let mut payload = vec![Tag::End as u8];
+    payload.extend_from_slice(&2_000_000_000i32.to_be_bytes());
+    let bytes = entry(Tag::List, &payload);
+    assert!(serde_impl::from_reader_binary::<_, serde_json::Value>(bytes.as_slice()).is_err());
+    assert!(serde_impl::from_reader_binary::<_, NameLessValue>(bytes.as_slice()).is_err());
+
+    // Negative lengths are empty
+    for tag in [Tag::End, Tag::Int] {
+        let mut payload = vec![tag as u8];
+        payload.extend_from_slice(&(-1i32).to_be_bytes());
+        let bytes = entry(Tag::List, &payload);
+        let json: serde_json::Value = serde_impl::from_reader_binary(bytes.as_slice()).unwrap();
+        assert_eq!(json, serde_json::json!({ "a": [] }));
+        let value: NameLessValue = serde_impl::from_reader_binary(bytes.as_slice()).unwrap();
+        assert!(matches!(value, NameLessValue::Compound(_)));
+    }
+    let bytes = entry(Tag::IntArray, &(-1i32).to_be_bytes());
+    let json: serde_json::Value = serde_impl::from_reader_binary(bytes.as_slice()).unwrap();
+    assert_eq!(json, serde_json::json!({ "a": [] }));
+    let tuple: Result<HashMap<String, (i32,)>, _> = serde_impl::from_reader_binary(bytes.as_slice());
+    assert!(matches!(
+        tuple.unwrap_err().error,
+        serde_impl::Error::IncorrectLengthError(1, 0)
+    ));
+}
+
+#[test]
+pub fn error_location() {
+    let slot = |count: NameLessValue| {
+        NameLessValue::Compound(vec![
+            count.into_named("Count"),
+            NameLessValue::String("minecraft:stone".to_string()).into_named("id"),
+        ])
+    };
+    let save = |count: NameLessValue, on_ground: NameLessValue| Value::Compound {
+        name: String::new(),
+        value: vec![NameLessValue::Compound(vec![
+            NameLessValue::List(
+                List::try_from(vec![
+                    slot(NameLessValue::Byte(1)),
+                    slot(NameLessValue::Byte(2)),
+                    slot(count),
+                ])
+                .unwrap(),
+            )
+            .into_named("Inventory"),
+            on_ground.into_named("OnGround"),
+        ])
+        .into_named("player")],
+    };
+    let bad_count = save(
+        NameLessValue::String("many".to_string()),
+        NameLessValue::Byte(1),
+    );
+    let mut bytes = Vec::new();
+    bad_count.clone().write_alone(&mut bytes).unwrap();
+
+    let error = serde_impl::from_reader_binary::<_, Save>(bytes.as_slice()).unwrap_err();
+    assert_eq!(error.path.to_string(), "player.Inventory[2].Count");
+    assert_eq!(error.expected.as_deref(), Some("i8"));
+    assert_eq!(error.found, Some(Tag::String));
+    // The string "many" was read right before the error
+    let many = bytes.windows(4).position(|window| window == b"many").unwrap();
+    assert_eq!(error.offset, Some(many as u64 + 4));
+    assert!(error.to_string().contains("at player.Inventory[2].Count"));
+
+    let error = serde_impl::from_value::<Save>(bad_count).unwrap_err();
+    assert_eq!(error.path.to_string(), "player.Inventory[2].Count");
+    assert_eq!(error.expected.as_deref(), Some("i8"));
+    assert_eq!(error.found, Some(Tag::String));
+    assert_eq!(error.offset, None);
+
+    let bad_bool = save(NameLessValue::Byte(3), NameLessValue::Int(1));
+    let mut bytes = Vec::new();
+    bad_bool.clone().write_alone(&mut bytes).unwrap();
+    let error = serde_impl::from_reader_binary::<_, Save>(bytes.as_slice()).unwrap_err();
+    assert_eq!(error.path.to_string(), "player.OnGround");
+    assert!(matches!(
+        error.error,
+        serde_impl::Error::IncorrectTagError(Tag::Byte, Tag::Int)
+    ));
+    assert_eq!(error.expected.as_deref(), Some("a boolean"));
+    assert_eq!(error.found, Some(Tag::Int));
+    let error = serde_impl::from_value::<Save>(bad_bool).unwrap_err();
+    assert_eq!(error.path.to_string(), "player.OnGround");
+    assert_eq!(error.expected.as_deref(), Some("a boolean"));
+    assert_eq!(error.found, Some(Tag::Int));
+
+    let wrong_count = slot(NameLessValue::String("many".to_string())).into_named("");
+    let mut bytes = Vec::new();
+    wrong_count.clone().write_alone(&mut bytes).unwrap();
+    let binary = serde_impl::from_reader_binary::<_, UnsignedSlot>(bytes.as_slice()).unwrap_err();
+    let value = serde_impl::from_value::<UnsignedSlot>(wrong_count).unwrap_err();
+    for error in [binary, value] {
+        assert_eq!(error.path.to_string(), "Count");
+        assert!(matches!(
+            error.error,
+            serde_impl::Error::IncorrectTagError(Tag::Byte, Tag::String)
+        ));
+        assert_eq!(error.expected.as_deref(), Some("u8"));
+        assert_eq!(error.found, Some(Tag::String));
+    }
+}
+
+#[derive(Debug, PartialEq, ListSerialize)]
+pub struct Pos(f64, f64, f64);
+
+#[derive(Debug, PartialEq, ListSerialize)]
+pub struct Vec3<T> {
+    x: T,
+    y: T,
+    z: T,
+}
+
+#[derive(Debug, PartialEq, ListSerialize)]
+#[list(encoding = "list")]
+pub struct Path {
+    length: i32,
+    #[list(rest)]
+    points: Vec<i32>,
+}
+
+#[derive(Debug, PartialEq, ListSerialize)]
+pub struct DropChances(f32, #[list(default)] f32);
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct ListShapes {
+    pos: Pos,
+    block: Vec3<i32>,
+    motion: Vec3<f32>,
+    path: Path,
+    chances: DropChances,
+}
+
+#[test]
+pub fn list_serialize_shapes() {
+    let tests = ListShapes {
+        pos: Pos(1.0, 64.0, -3.5),
+        block: Vec3 { x: 1, y: 2, z: 3 },
+        motion: Vec3 {
+            x: 0.5,
+            y: 0.0,
+            z: -0.5,
+        },
+        path: Path {
+            length: 2,
+            points: vec![4, 5],
+        },
+        chances: DropChances(0.5, 0.25),
+    };
+    let value = serde_impl::to_value(&tests).unwrap();
+    let Value::Compound { value: compound, .. } = &value else {
+        panic!("Expected a compound");
+    };
+    let nameless: Vec<NameLessValue> = compound.iter().cloned().map(Value::into_nameless).collect();
+    assert_eq!(
+        nameless,
+        vec![
+            NameLessValue::List(List::Double(vec![1.0, 64.0, -3.5])),
+            NameLessValue::IntArray(vec![1, 2, 3]),
+            NameLessValue::List(List::Float(vec![0.5, 0.0, -0.5])),
+            NameLessValue::List(List::Int(vec![2, 4, 5])),
+            NameLessValue::List(List::Float(vec![0.5, 0.25])),
+        ]
+    );
+    let mut bytes = Vec::new();
+    serde_impl::to_writer(&mut bytes, &tests).unwrap();
+    let data: ListShapes = serde_impl::from_reader_binary(bytes.as_slice()).unwrap();
+    assert_eq!(data, tests);
+    let data: ListShapes = serde_impl::from_value(value).unwrap();
+    assert_eq!(data, tests);
+
+    let short = NameLessValue::List(List::Float(vec![0.5]));
+    let chances: DropChances = serde_impl::from_nameless_value(short).unwrap();
+    assert_eq!(chances, DropChances(0.5, 0.0));
+    let empty = NameLessValue::List(List::Int(vec![0]));
+    let path: Path = serde_impl::from_nameless_value(empty).unwrap();
+    assert_eq!(path.points, Vec::<i32>::new());
+    let long = NameLessValue::List(List::Double(vec![1.0, 2.0, 3.0, 4.0]));
+    assert!(serde_impl::from_nameless_value::<Pos>(long).is_err());
+    assert!(serde_impl::from_nameless_value::<Path>(NameLessValue::List(List::End)).is_err());
+}
+
+#[derive(Debug, Clone, PartialEq, NbtEnum)]
+pub enum Dimension {
+    #[nbt(string = "minecraft:overworld")]
+    Overworld,
+    #[nbt(string = "minecraft:the_nether")]
+    Nether,
+    Unknown(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, NbtEnum)]
+#[nbt(tag = "byte")]
+pub enum Facing {
+    #[nbt(id = 2)]
+    North,
+    #[nbt(id = 3)]
+    South,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, NbtEnum)]
+pub enum GameType {
+    #[nbt(id = 0)]
+    Survival,
+    #[nbt(id = 1)]
+    Creative,
+    Unknown(i32),
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[allow(non_snake_case)]
+pub struct EnumKeys {
+    Dimension: Dimension,
+    Modded: Dimension,
+    Facing: Facing,
+    GameType: GameType,
+    Future: GameType,
+}
+
+#[test]
+pub fn nbt_enums() {
+    let tests = EnumKeys {
+        Dimension: Dimension::Nether,
+        Modded: Dimension::Unknown("mod:moon".to_string()),
+        Facing: Facing::South,
+        GameType: GameType::Creative,
+        Future: GameType::Unknown(7),
+    };
+    let value = serde_impl::to_value(&tests).unwrap();
+    let Value::Compound { value: compound, .. } = &value else {
+        panic!("Expected a compound");
+    };
+    let nameless: Vec<NameLessValue> = compound.iter().cloned().map(Value::into_nameless).collect();
+    assert_eq!(
+        nameless,
+        vec![
+            NameLessValue::String("minecraft:the_nether".to_string()),
+            NameLessValue::String("mod:moon".to_string()),
+            NameLessValue::Byte(3),
+            NameLessValue::Int(1),
+            NameLessValue::Int(7),
+        ]
+    );
+    let mut bytes = Vec::new();
+    serde_impl::to_writer(&mut bytes, &tests).unwrap();
+    let data: EnumKeys = serde_impl::from_reader_binary(bytes.as_slice()).unwrap();
+    assert_eq!(data, tests);
+    let data: EnumKeys = serde_impl::from_value(value).unwrap();
+    assert_eq!(data, tests);
+
+    let mut bytes = Vec::new();
+    Facing::North.write("Facing", &mut bytes).unwrap();
+    let value = Value::read(&mut bytes.as_slice()).unwrap();
+    assert_eq!(value, NameLessValue::Byte(2).into_named("Facing"));
+    let (name, facing) = <Facing as NBTDataType<Binary>>::read_with_name(&mut &bytes[1..]).unwrap();
+    assert_eq!((name.as_str(), facing), ("Facing", Facing::North));
+    let mut bytes = Vec::new();
+    Dimension::Unknown("mod:moon".to_string()).write_alone(&mut bytes).unwrap();
+    let dimension = <Dimension as NBTDataType<Binary>>::read(&mut bytes.as_slice()).unwrap();
+    assert_eq!(dimension.as_str(), "mod:moon");
+    assert_eq!(GameType::Unknown(7).id(), 7);
+
+    let unknown = serde_impl::from_nameless_value::<Facing>(NameLessValue::Byte(9));
+    assert!(unknown.is_err());
+    let mut bytes = Vec::new();
+    9i8.write_alone(&mut bytes).unwrap();
+    assert!(matches!(
+        <Facing as NBTDataType<Binary>>::read(&mut bytes.as_slice()),
+        Err(axolotl_nbt::NBTError::UnknownVariant(id)) if id == "9"
+    ));
+}
@@ -0,0 +1,25 @@
+use axolotl_nbt::axolotl_nbt_macros::NbtEnum;
+
+#[derive(NbtEnum)]
+pub enum GameType {
+    #[nbt(id = 0)]
+    Survival,
+    Unknown(String),
+}
+
+#[derive(NbtEnum)]
+#[nbt(tag = "byte")]
+pub enum Facing {
+    #[nbt(id = 0)]
+    Down,
+    Unknown(i32),
+}
+
+#[derive(NbtEnum)]
+pub enum Dimension {
+    #[nbt(string = "minecraft:overworld")]
+    Overworld,
+    Unknown(i32),
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use axolotl_nbt::axolotl_nbt_macros::NbtCompound;
+
+#[derive(NbtCompound)]
+pub struct Heightmaps {
+    #[nbt(tag = "int_array")]
+    pub surface: Vec<i64>,
+    #[nbt(tag = "long_array")]
+    pub ocean: Option<Vec<u8>>,
+    #[nbt(tag = "byte_array")]
+    pub motion: String,
+}
+
+fn main() {}